@@ -2,13 +2,11 @@ pub mod parser;
 pub use parser::*;
 
 use std::io::Write;
-use std::{
-    fs::{self},
-    io,
-    path::Path,
-};
+use std::path::Path;
+#[cfg(feature = "wasm")]
+use std::fs;
 use std::fs::File;
-use open_stock::{Customer, Kiosk, Product, Store, Transaction};
+use open_stock::{Customer, Employee, Kiosk, Product, Promotion, Store, Transaction};
 
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::wasm_bindgen;
@@ -19,6 +17,10 @@ pub type InlineDatabase = (
     Vec<Transaction>,
     Vec<Store>,
     Vec<Kiosk>,
+    Vec<Employee>,
+    Vec<Promotion>,
+    Vec<GiftCard>,
+    Vec<PromotionTerms>,
 );
 
 #[cfg(feature = "wasm")]
@@ -26,6 +28,10 @@ pub type InlineDatabase = (
 pub fn convert_from_directory(input: String) {
     let path = Path::new(&input);
 
+    if let Err(err) = register_mapping_files(path) {
+        println!("[warn]: Failed to read mapping files, {}", err);
+    }
+
     let classifications = match traverse_directories(path, &classify_type) {
         Ok(mut v) => {
             v.sort_by(|a, b| (a.variant as u32).cmp(&(b.variant as u32)));
@@ -39,12 +45,12 @@ pub fn convert_from_directory(input: String) {
         }
     };
 
-    let mut db: InlineDatabase = (vec![], vec![], vec![], vec![], vec![]);
+    let mut db: InlineDatabase = Default::default();
 
     for c in classifications {
         println!("{}", c);
 
-        match csv::Reader::from_path(c.path) {
+        match open_reader(&c.path) {
             Ok(rdr) => {
                 read_file(rdr, c.branding, c.variant, &mut db);
             }
@@ -73,7 +79,8 @@ pub fn convert_from_directory(input: String) {
 }
 
 #[cfg(not(feature = "wasm"))]
-pub fn convert_from_directory(input: String) -> String {
+#[allow(clippy::useless_format)]
+pub fn convert_from_directory(input: String, options: &ConversionOptions) -> String {
     let path = Path::new(&input);
 
     let classifications = classify_by_path(path)
@@ -81,20 +88,22 @@ pub fn convert_from_directory(input: String) -> String {
 
     println!("Yielded Following Classifications: {:?}", classifications);
 
-    let mut db: InlineDatabase = (vec![], vec![], vec![], vec![], vec![]);
+    let mut db: InlineDatabase = Default::default();
 
-    for c in classifications {
-        println!("{}", c);
+    options.scope(|| {
+        for c in classifications {
+            println!("{}", c);
 
-        match csv::Reader::from_path(c.path) {
-            Ok(rdr) => {
-                read_file(rdr, c.branding, c.variant, &mut db);
-            }
-            Err(error) => {
-                println!("{:?}", error)
+            match open_reader(&c.path) {
+                Ok(rdr) => {
+                    read_file(rdr, c.branding, c.variant, &mut db);
+                }
+                Err(error) => {
+                    println!("{:?}", error)
+                }
             }
         }
-    }
+    });
 
     match serde_json::to_string(&db) {
         Ok(string_value) => {
@@ -1,13 +1,9 @@
 #[cfg(feature = "cli")]
-use odm_migration_utility::convert_from_directory;
-
-use odm_migration_utility::InlineDatabase;
+use odm_migration_utility::{convert_from_directory, ConversionOptions, MetafieldTarget};
 
 #[cfg(feature = "cli")]
 use clap::{self, Command};
 
-mod parser;
-
 fn main() {
     #[cfg(feature = "cli")]
     let cmd = clap::Command::new("odm")
@@ -27,6 +23,25 @@ fn main() {
                         .id("folder")
                         .required(true)
                         .value_parser(clap::value_parser!(String)),
+                )
+                .arg(
+                    clap::arg!(--metafield <PLACEMENT> "Places a Shopify metafield, as namespace.key=specification|tag|description|ignore")
+                        .id("metafield")
+                        .required(false)
+                        .action(clap::ArgAction::Append)
+                        .value_parser(parse_metafield),
+                )
+                .arg(
+                    clap::arg!(--"weight-unit" <UNIT> "Reads the weights of a format in a unit other than its default, as format=unit")
+                        .id("weight-unit")
+                        .required(false)
+                        .action(clap::ArgAction::Append)
+                        .value_parser(parse_weight_unit),
+                )
+                .arg(
+                    clap::arg!(--"lowercase-tags" "Lowercases every tag, rather than keeping the first spelling of each")
+                        .id("lowercase-tags")
+                        .action(clap::ArgAction::SetTrue),
                 ),
         )
         .get_matches();
@@ -34,15 +49,63 @@ fn main() {
     #[cfg(feature = "cli")]
     match cmd.subcommand_name() {
         Some("parse") => {
-            let folder: String = cmd
-                .subcommand_matches("parse")
-                .expect("?")
+            let matches = cmd.subcommand_matches("parse").expect("?");
+
+            let folder: String = matches
                 .get_one::<String>("folder")
                 .expect("Expected value 'folder'. ")
                 .to_string();
 
-            convert_from_directory(folder.to_string());
+            let mut options = ConversionOptions::new();
+            options.lowercase_tags = matches.get_flag("lowercase-tags");
+
+            for (path, target) in matches
+                .get_many::<(String, MetafieldTarget)>("metafield")
+                .unwrap_or_default()
+            {
+                options.set_metafield_target(path, *target);
+            }
+
+            for (format, unit) in matches
+                .get_many::<(String, String)>("weight-unit")
+                .unwrap_or_default()
+            {
+                options.set_weight_unit(format, unit);
+            }
+
+            convert_from_directory(folder.to_string(), &options);
         }
         _ => unreachable!("This shouldn't happen, please file a bug report."),
     }
 }
+
+/// Reads a `--metafield` placement, given as `namespace.key=target`.
+#[cfg(feature = "cli")]
+fn parse_metafield(value: &str) -> Result<(String, MetafieldTarget), String> {
+    let (path, target) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected namespace.key=target, got {}", value))?;
+
+    let target = target.trim().parse::<MetafieldTarget>().map_err(|_| {
+        format!(
+            "expected one of specification, tag, description or ignore, got {}",
+            target
+        )
+    })?;
+
+    Ok((path.trim().to_string(), target))
+}
+
+/// Reads a `--weight-unit` setting, given as `format=unit`.
+#[cfg(feature = "cli")]
+fn parse_weight_unit(value: &str) -> Result<(String, String), String> {
+    let (format, unit) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected format=unit, got {}", value))?;
+
+    if !ConversionOptions::new().set_weight_unit(format, unit) {
+        return Err(format!("{} is not a recognised weight unit", unit));
+    }
+
+    Ok((format.trim().to_string(), unit.trim().to_string()))
+}
@@ -0,0 +1,891 @@
+//! Formats declared in a mapping file, rather than compiled in.
+//!
+//! For systems too small to warrant a module of their own, a mapping file
+//! names the column of an export which holds each field, such as:
+//!
+//! ```toml
+//! name = "tinypos"
+//! type = "Product"
+//! header = "Item,Code,Size,Price,Stock"
+//! group_by = "Item"
+//! options = ["Size"]
+//!
+//! [fields]
+//! name = "Item"
+//! variant_code = { column = "Code", transforms = ["trim", "uppercase"] }
+//! retail_price = { column = "Price", transforms = [{ multiply = 0.01 }] }
+//! quantity = "Stock"
+//! ```
+//!
+//! Consecutive rows sharing a `group_by` value (or leaving it empty) are the
+//! variants of one product, named by their `options` columns. Mapping files
+//! are written in TOML, or in JSON when given a `.json` extension, and any
+//! named `<name>.mapping.toml` (or `.mapping.json`) alongside the exports
+//! are registered before the exports are classified.
+//!
+//! Only products (`type = "Product"`) and customers (`type = "Customer"`)
+//! can be declared. Transactions, along with every other type of record,
+//! need a compiled-in format, as their rows are joined to the products and
+//! customers already read, and a mapping declaring them is rejected.
+
+use crate::{
+    parser::{
+        add_variant_option, identify, lowercase_tags, merge_tags, parse_flag, register_format,
+        resolve_store, Format, ParseFailure, ParseType, SpecialPricing, BUILTIN_FORMATS,
+    },
+    InlineDatabase,
+};
+use chrono::Utc;
+use csv::{Reader, StringRecord};
+use open_stock::{
+    Address, ContactInformation, Customer, DiscountValue, Email, Location, MobileNumber, Note,
+    Product, ProductIdentification, ProductVisibility, Quantity, Stock, StockInformation,
+    VariantInformation,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, fs::File, path::Path};
+use uuid::Uuid;
+
+/// The fields a product mapping may fill. Those from `variant_name` onward
+/// are read from every row of a product, the others from its first row.
+const PRODUCT_FIELDS: [&str; 14] = [
+    "name",
+    "sku",
+    "description",
+    "tags",
+    "images",
+    "brand",
+    "tax_code",
+    "variant_name",
+    "variant_code",
+    "barcode",
+    "retail_price",
+    "marginal_price",
+    "quantity",
+    "store",
+];
+
+const CUSTOMER_FIELDS: [&str; 14] = [
+    "name",
+    "first_name",
+    "last_name",
+    "email",
+    "phone",
+    "mobile",
+    "street",
+    "street2",
+    "city",
+    "country",
+    "po_code",
+    "notes",
+    "accepts_marketing",
+    "tax_exempt",
+];
+
+/// The suffixes of mapping files, which are registered rather than parsed.
+const MAPPING_SUFFIXES: [&str; 2] = [".mapping.toml", ".mapping.json"];
+
+/// A change made to the value of a cell, in the order given.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transform {
+    Trim,
+    Lowercase,
+    Uppercase,
+    /// Splits a value into several, such as a list of tags. Fields which
+    /// hold a single value take the first.
+    Split(String),
+    /// Scales a numeric value, such as a price exported in cents.
+    Multiply(f32),
+    Replace {
+        from: String,
+        to: String,
+    },
+    /// Used in place of an empty value.
+    Default(String),
+}
+
+impl Transform {
+    fn apply(&self, values: Vec<String>) -> Vec<String> {
+        match self {
+            Transform::Split(separator) => values
+                .iter()
+                .flat_map(|value| value.split(separator.as_str()))
+                .map(str::to_string)
+                .collect(),
+            Transform::Trim => values
+                .iter()
+                .map(|value| value.trim().to_string())
+                .collect(),
+            Transform::Lowercase => values.iter().map(|value| value.to_lowercase()).collect(),
+            Transform::Uppercase => values.iter().map(|value| value.to_uppercase()).collect(),
+            Transform::Multiply(factor) => values
+                .into_iter()
+                .map(|value| match value.trim().parse::<f32>() {
+                    Ok(number) => (number * factor).to_string(),
+                    Err(_) => value,
+                })
+                .collect(),
+            Transform::Replace { from, to } => values
+                .iter()
+                .map(|value| value.replace(from.as_str(), to))
+                .collect(),
+            Transform::Default(default) => values
+                .into_iter()
+                .map(|value| {
+                    if value.trim().is_empty() {
+                        default.clone()
+                    } else {
+                        value
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The column a field is read from, given by name alone
+/// or together with the transforms made to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FieldMapping {
+    Column(String),
+    Transformed {
+        column: String,
+        #[serde(default)]
+        transforms: Vec<Transform>,
+    },
+}
+
+impl FieldMapping {
+    fn column(&self) -> &str {
+        match self {
+            FieldMapping::Column(column) => column,
+            FieldMapping::Transformed { column, .. } => column,
+        }
+    }
+
+    fn transforms(&self) -> &[Transform] {
+        match self {
+            FieldMapping::Column(_) => &[],
+            FieldMapping::Transformed { transforms, .. } => transforms,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeclarativeFormat {
+    /// Names the format, as the branding of the exports it classifies.
+    pub name: String,
+
+    /// Either `Product` or `Customer`.
+    #[serde(rename = "type")]
+    pub parse_type: ParseType,
+
+    /// The header line of the export, which it is classified by.
+    pub header: String,
+
+    #[serde(default)]
+    pub group_by: String,
+
+    /// The columns which hold the option values of each variant.
+    #[serde(default)]
+    pub options: Vec<String>,
+
+    pub fields: BTreeMap<String, FieldMapping>,
+}
+
+impl DeclarativeFormat {
+    fn validate(&self) -> Result<(), ParseFailure> {
+        let fields: &[&str] = match self.parse_type {
+            ParseType::Product => &PRODUCT_FIELDS,
+            ParseType::Customer => &CUSTOMER_FIELDS,
+            other => {
+                return Err(ParseFailure::FormatFailure(format!(
+                    "Format {} declares {} records, only products and customers can be declared.",
+                    self.name, other
+                )))
+            }
+        };
+
+        if self.name.is_empty()
+            || BUILTIN_FORMATS
+                .iter()
+                .any(|format| format.name() == self.name)
+        {
+            return Err(ParseFailure::FormatFailure(format!(
+                "Format name {:?} is empty or already taken.",
+                self.name
+            )));
+        }
+
+        if let Some(field) = self
+            .fields
+            .keys()
+            .find(|field| !fields.contains(&field.as_str()))
+        {
+            return Err(ParseFailure::FormatFailure(format!(
+                "Format {} maps unknown field {}, expected one of {}.",
+                self.name,
+                field,
+                fields.join(", ")
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads the format of a mapping file, in JSON when given a `.json`
+/// extension and in TOML otherwise.
+fn read_mapping_file(path: &Path) -> Result<DeclarativeFormat, ParseFailure> {
+    let content =
+        fs::read_to_string(path).map_err(|error| ParseFailure::ReadFailure(error.to_string()))?;
+
+    let format: DeclarativeFormat = match path.extension().and_then(|extension| extension.to_str())
+    {
+        Some("json") => serde_json::from_str(&content)
+            .map_err(|error| ParseFailure::FormatFailure(error.to_string()))?,
+        _ => toml::from_str(&content)
+            .map_err(|error| ParseFailure::FormatFailure(error.to_string()))?,
+    };
+
+    format.validate()?;
+
+    Ok(format)
+}
+
+/// Reads and registers the format of a mapping file.
+pub fn register_mapping_file(path: &Path) -> Result<(), ParseFailure> {
+    register_format(read_mapping_file(path)?);
+
+    Ok(())
+}
+
+/// Registers the mapping files placed in a directory of exports.
+pub fn register_mapping_files(dir: &Path) -> Result<(), std::io::Error> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if is_mapping_file(&path) {
+            if let Err(error) = register_mapping_file(&path) {
+                println!(
+                    "[warn]: Mapping {} was not registered: {:?}",
+                    path.display(),
+                    error
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn is_mapping_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| MAPPING_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)))
+}
+
+/// A row of an export, read through the mapping of its format.
+struct Row<'a> {
+    format: &'a DeclarativeFormat,
+    headers: &'a StringRecord,
+    record: &'a StringRecord,
+}
+
+impl Row<'_> {
+    fn column(&self, column: &str) -> String {
+        self.headers
+            .iter()
+            .position(|header| header.trim() == column)
+            .and_then(|index| self.record.get(index))
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    fn values(&self, field: &str) -> Vec<String> {
+        match self.format.fields.get(field) {
+            Some(mapping) => mapping
+                .transforms()
+                .iter()
+                .fold(vec![self.column(mapping.column())], |values, transform| {
+                    transform.apply(values)
+                }),
+            None => vec![],
+        }
+    }
+
+    fn value(&self, field: &str) -> String {
+        self.values(field).into_iter().next().unwrap_or_default()
+    }
+
+    fn number(&self, field: &str) -> Option<f32> {
+        self.value(field).trim().parse::<f32>().ok()
+    }
+}
+
+fn read_rows(reader: &mut Reader<File>) -> Result<(StringRecord, Vec<StringRecord>), ParseFailure> {
+    let headers = reader
+        .headers()
+        .map_err(|error| ParseFailure::ReadFailure(error.to_string()))?
+        .clone();
+
+    let records = reader
+        .records()
+        .filter_map(|record| match record {
+            Ok(record) => Some(record),
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                None
+            }
+        })
+        .collect();
+
+    Ok((headers, records))
+}
+
+impl Format for DeclarativeFormat {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn header(&self, parse_type: ParseType) -> Option<String> {
+        if parse_type == self.parse_type {
+            Some(self.header.clone())
+        } else {
+            None
+        }
+    }
+
+    fn read(
+        &self,
+        mut reader: Reader<File>,
+        parse_type: ParseType,
+        db: &mut InlineDatabase,
+    ) -> Result<(), ParseFailure> {
+        match parse_type {
+            ParseType::Product => {
+                let mut products = parse_products(&mut reader, self, db)?;
+                db.0.append(&mut products);
+            }
+            ParseType::Customer => {
+                let mut customers = parse_customers(&mut reader, self)?;
+                db.1.append(&mut customers);
+            }
+            other => {
+                return Err(ParseFailure::FormatFailure(format!(
+                    "Format {} does not read {} records.",
+                    self.name, other
+                )))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_products(
+    reader: &mut Reader<File>,
+    format: &DeclarativeFormat,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let (headers, records) = read_rows(reader)?;
+
+    let mut products: Vec<Product> = vec![];
+    let mut group = String::new();
+
+    for record in &records {
+        let row = Row {
+            format,
+            headers: &headers,
+            record,
+        };
+
+        let key = if format.group_by.is_empty() {
+            None
+        } else {
+            Some(row.column(&format.group_by))
+        };
+
+        let continues = match &key {
+            Some(key) => !products.is_empty() && (key.is_empty() || *key == group),
+            None => false,
+        };
+
+        if !continues {
+            products.push(build_product(&row));
+        }
+
+        if let Some(key) = key.filter(|key| !key.is_empty()) {
+            group = key;
+        }
+
+        let product = products.last_mut().unwrap();
+        let variant = build_variant(&row, product, db);
+        product.variants.push(variant);
+    }
+
+    Ok(products)
+}
+
+fn build_product(row: &Row) -> Product {
+    let name = row.value("name");
+    let sku = row.value("sku");
+    let description = row.value("description");
+
+    let mut tags = vec![];
+    merge_tags(
+        &mut tags,
+        row.values("tags").iter().map(|tag| tag.trim().to_string()),
+        lowercase_tags(),
+    );
+
+    Product {
+        name: name.clone(),
+        company: String::new(),
+        variant_groups: vec![],
+        variants: vec![],
+        sku: sku.clone(),
+        images: row
+            .values("images")
+            .into_iter()
+            .filter(|image| !image.trim().is_empty())
+            .collect(),
+        tags,
+        description: description.clone(),
+        specifications: vec![],
+        name_long: name,
+        identification: ProductIdentification {
+            sku,
+            ..Default::default()
+        },
+        description_long: description,
+        visible: ProductVisibility::ShowWhenInStock,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+fn build_variant(row: &Row, product: &mut Product, db: &mut InlineDatabase) -> VariantInformation {
+    let mut chosen = vec![];
+
+    for option in &row.format.options {
+        let value = row.column(option).trim().to_string();
+
+        if !value.is_empty() {
+            add_variant_option(product, option, &value);
+            chosen.push(value);
+        }
+    }
+
+    let name = match row.value("variant_name") {
+        name if !name.is_empty() => name,
+        _ if !chosen.is_empty() => chosen.join(" "),
+        _ => product.name.clone(),
+    };
+
+    let code = match row.value("variant_code") {
+        code if !code.is_empty() => code,
+        _ => row.value("sku"),
+    };
+
+    let barcode = row.value("barcode");
+    let price = row.number("retail_price").unwrap_or(0.0);
+    let tracked = row.format.fields.contains_key("quantity");
+
+    // Rows which name no store are stocked at one named after the format.
+    let store = if tracked {
+        match row.value("store") {
+            store if !store.is_empty() => resolve_store(&store, db),
+            _ => resolve_store(&row.format.name, db),
+        }
+    } else {
+        None
+    };
+
+    VariantInformation {
+        name,
+        stock: match store {
+            Some(store) => vec![Stock {
+                store: Location {
+                    store_code: store.code.clone(),
+                    store_id: store.id.clone(),
+                    contact: store.contact.clone(),
+                },
+                quantity: Quantity {
+                    quantity_sellable: row.number("quantity").unwrap_or(0.0),
+                    quantity_unsellable: 0.0,
+                    quantity_on_order: 0.0,
+                    quantity_allocated: 0.0,
+                },
+            }],
+            None => vec![],
+        },
+        images: vec![],
+        retail_price: price,
+        marginal_price: row.number("marginal_price").unwrap_or(price),
+        loyalty_discount: DiscountValue::Absolute(0),
+        variant_code: if code.is_empty() {
+            vec![]
+        } else {
+            vec![code.clone()]
+        },
+        order_history: vec![],
+        stock_information: StockInformation {
+            stock_group: String::new(),
+            sales_group: String::new(),
+            value_stream: String::new(),
+            brand: row.value("brand"),
+            tax_code: row.value("tax_code"),
+            weight: "0".to_string(),
+            volume: "0.00".to_string(),
+            max_volume: "0.00".to_string(),
+            back_order: false,
+            discontinued: false,
+            non_diminishing: false,
+            shippable: true,
+            size_override_unit: String::new(),
+            size_x_unit: String::new(),
+            size_y_unit: String::new(),
+            size_z_unit: String::new(),
+            size_x: 0.0,
+            size_y: 0.0,
+            size_z: 0.0,
+            min_stock_before_alert: 0.0,
+            min_stock_level: 0.0,
+            colli: String::new(),
+        },
+        identification: identify(&code, &barcode),
+        barcode,
+        id: Uuid::new_v4().to_string(),
+        buy_max: -1.0,
+        buy_min: 1.0,
+        stock_tracking: tracked,
+    }
+}
+
+fn parse_customers(
+    reader: &mut Reader<File>,
+    format: &DeclarativeFormat,
+) -> Result<Vec<Customer>, ParseFailure> {
+    let (headers, records) = read_rows(reader)?;
+    let author = format!("{}-IMPORT", format.name.to_uppercase());
+
+    Ok(records
+        .iter()
+        .map(|record| {
+            let row = Row {
+                format,
+                headers: &headers,
+                record,
+            };
+
+            let person = format!("{} {}", row.value("first_name"), row.value("last_name"))
+                .trim()
+                .to_string();
+
+            let name = match row.value("name") {
+                name if !name.is_empty() => name,
+                _ => person.clone(),
+            };
+
+            let mobile = row.value("mobile");
+            let phone = row.value("phone");
+
+            Customer {
+                id: Uuid::new_v4().to_string(),
+                name: name.clone(),
+                contact: ContactInformation {
+                    name: if person.is_empty() { name } else { person },
+                    mobile: MobileNumber::from(if mobile.is_empty() {
+                        phone.clone()
+                    } else {
+                        mobile.clone()
+                    }),
+                    email: Email::from(row.value("email")),
+                    landline: if mobile.is_empty() {
+                        String::new()
+                    } else {
+                        phone
+                    },
+                    address: Address {
+                        street: row.value("street"),
+                        street2: row.value("street2"),
+                        city: row.value("city"),
+                        country: row.value("country"),
+                        po_code: row.value("po_code"),
+                        lat: 0.0,
+                        lon: 0.0,
+                    },
+                },
+                customer_notes: row
+                    .values("notes")
+                    .into_iter()
+                    .filter(|note| !note.trim().is_empty())
+                    .map(|message| Note {
+                        message,
+                        author: author.clone(),
+                        timestamp: Utc::now(),
+                    })
+                    .collect(),
+                balance: 0,
+                special_pricing: SpecialPricing::tax_exempt(parse_flag(&row.value("tax_exempt")))
+                    .to_field(),
+                accepts_marketing: parse_flag(&row.value("accepts_marketing")),
+                created_at: Utc::now(),
+                updated_at: Utc::now(),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(mapping: &str) -> DeclarativeFormat {
+        toml::from_str(mapping).expect("mapping should deserialize")
+    }
+
+    fn rejection(mapping: &str) -> String {
+        match format(mapping).validate() {
+            Err(ParseFailure::FormatFailure(message)) => message,
+            other => panic!("expected the mapping to be rejected, got {:?}", other),
+        }
+    }
+
+    fn apply(transform: Transform, value: &str) -> Vec<String> {
+        transform.apply(vec![value.to_string()])
+    }
+
+    /// Reads an export through a format, writing it out first as readers are of files.
+    fn read_products(
+        format: &DeclarativeFormat,
+        export: &str,
+        db: &mut InlineDatabase,
+    ) -> Vec<Product> {
+        let path = std::env::temp_dir().join(format!("odm_declarative_{}.csv", format.name));
+        fs::write(&path, export).unwrap();
+
+        let mut reader = Reader::from_path(&path).unwrap();
+        let products = parse_products(&mut reader, format, db).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        products
+    }
+
+    #[test]
+    fn split_separates_every_value() {
+        assert_eq!(
+            apply(Transform::Split("|".to_string()), "shirts | linen"),
+            ["shirts ", " linen"]
+        );
+        assert_eq!(
+            Transform::Split(",".to_string()).apply(vec!["a,b".to_string(), "c".to_string()]),
+            ["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn multiply_scales_only_numbers() {
+        assert_eq!(apply(Transform::Multiply(0.01), " 4500 "), ["45"]);
+        assert_eq!(apply(Transform::Multiply(0.01), "n/a"), ["n/a"]);
+    }
+
+    #[test]
+    fn default_replaces_only_empty_values() {
+        assert_eq!(apply(Transform::Default("0".to_string()), "  "), ["0"]);
+        assert_eq!(apply(Transform::Default("0".to_string()), "12"), ["12"]);
+    }
+
+    #[test]
+    fn transforms_are_made_in_the_order_given() {
+        let format = format(
+            r#"
+            name = "ordered"
+            type = "Product"
+            header = "Tags"
+
+            [fields]
+            tags = { column = "Tags", transforms = [{ split = ";" }, "trim", "uppercase"] }
+            "#,
+        );
+        let headers = StringRecord::from(vec!["Tags"]);
+        let record = StringRecord::from(vec![" a ; b"]);
+        let row = Row {
+            format: &format,
+            headers: &headers,
+            record: &record,
+        };
+
+        assert_eq!(row.values("tags"), ["A", "B"]);
+        assert_eq!(row.value("tags"), "A");
+    }
+
+    #[test]
+    fn rows_sharing_a_group_are_variants_of_one_product() {
+        let format = format(
+            r#"
+            name = "grouped"
+            type = "Product"
+            header = "Item,Size,Stock"
+            group_by = "Item"
+            options = ["Size"]
+
+            [fields]
+            name = "Item"
+            quantity = "Stock"
+            "#,
+        );
+        let mut db = InlineDatabase::default();
+        let products = read_products(
+            &format,
+            "Item,Size,Stock\nShirt,S,1\n,M,2\nShirt,L,3\nHat,,4\nShirt,XL,5\n",
+            &mut db,
+        );
+
+        let names: Vec<(&str, usize)> = products
+            .iter()
+            .map(|product| (product.name.as_str(), product.variants.len()))
+            .collect();
+        assert_eq!(names, [("Shirt", 3), ("Hat", 1), ("Shirt", 1)]);
+        assert_eq!(products[0].variants[1].name, "M");
+        assert_eq!(products[1].variants[0].name, "Hat");
+    }
+
+    #[test]
+    fn rows_are_products_of_their_own_without_a_group() {
+        let format = format(
+            r#"
+            name = "ungrouped"
+            type = "Product"
+            header = "Item"
+
+            [fields]
+            name = "Item"
+            "#,
+        );
+        let mut db = InlineDatabase::default();
+        let products = read_products(&format, "Item\nShirt\n\nShirt\n", &mut db);
+
+        assert_eq!(products.len(), 2);
+        assert!(products[0].variants[0].stock.is_empty());
+    }
+
+    #[test]
+    fn stock_is_held_at_the_named_store_or_one_named_after_the_format() {
+        let format = format(
+            r#"
+            name = "stocked"
+            type = "Product"
+            header = "Item,Outlet,Stock"
+
+            [fields]
+            name = "Item"
+            store = "Outlet"
+            quantity = "Stock"
+            "#,
+        );
+        let mut db = InlineDatabase::default();
+        resolve_store("Warehouse", &mut db);
+
+        let products = read_products(
+            &format,
+            "Item,Outlet,Stock\nShirt,Fremantle,1\nHat,,2\n",
+            &mut db,
+        );
+
+        assert_eq!(
+            products[0].variants[0].stock[0].store.contact.name,
+            "Fremantle"
+        );
+        // The first store is not taken to be the format's.
+        assert_eq!(
+            products[1].variants[0].stock[0].store.contact.name,
+            "stocked"
+        );
+        assert_eq!(db.3.len(), 3);
+    }
+
+    #[test]
+    fn mappings_of_other_records_are_rejected() {
+        let message = rejection(
+            r#"
+            name = "orders"
+            type = "Transaction"
+            header = "Order"
+            fields = {}
+            "#,
+        );
+
+        assert!(message.contains("only products and customers can be declared"));
+    }
+
+    #[test]
+    fn mappings_named_after_builtin_formats_are_rejected() {
+        for name in ["", "shopify"] {
+            let message = rejection(&format!(
+                r#"
+                name = "{}"
+                type = "Product"
+                header = "Item"
+                fields = {{}}
+                "#,
+                name
+            ));
+
+            assert!(message.contains("is empty or already taken"));
+        }
+    }
+
+    #[test]
+    fn mappings_of_unknown_fields_are_rejected() {
+        let message = rejection(
+            r#"
+            name = "misnamed"
+            type = "Customer"
+            header = "Name"
+
+            [fields]
+            full_name = "Name"
+            "#,
+        );
+
+        assert!(message.starts_with("Format misnamed maps unknown field full_name"));
+    }
+
+    #[test]
+    fn mappings_are_read_as_json_or_toml_by_extension() {
+        let items =
+            read_mapping_file(Path::new("examples/declarative_import/items.mapping.toml")).unwrap();
+        assert_eq!(items.name, "tinypos-items");
+        assert_eq!(items.parse_type, ParseType::Product);
+        assert_eq!(items.group_by, "Item");
+        assert_eq!(items.options, ["Size", "Colour"]);
+        assert!(matches!(
+            items.fields["retail_price"].transforms(),
+            [Transform::Multiply(factor)] if (*factor - 0.01).abs() < f32::EPSILON
+        ));
+
+        let customers = read_mapping_file(Path::new(
+            "examples/declarative_import/customers.mapping.json",
+        ))
+        .unwrap();
+        assert_eq!(customers.name, "tinypos-customers");
+        assert_eq!(customers.parse_type, ParseType::Customer);
+        assert_eq!(customers.fields["first_name"].column(), "First");
+        assert!(matches!(
+            customers.fields["notes"].transforms(),
+            [Transform::Split(separator), Transform::Trim] if separator == ";"
+        ));
+
+        // A mapping in JSON is not read as TOML.
+        let path = std::env::temp_dir().join("odm_declarative_mapping.toml");
+        fs::copy("examples/declarative_import/customers.mapping.json", &path).unwrap();
+        let result = read_mapping_file(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert!(matches!(result, Err(ParseFailure::FormatFailure(_))));
+    }
+}
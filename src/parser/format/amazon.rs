@@ -0,0 +1,418 @@
+//! Amazon Seller Central listings reports, which are separated by tabs.
+//!
+//! Each listing names its ASIN, which is kept as the article code of its
+//! variant. Reports which carry the `parent-child` and `parent-sku` columns
+//! (as category listings reports do) list each parent ASIN before its
+//! children, which are joined to it as its variants. Their options are read
+//! from the attribute columns the report carries, such as `size-name`.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{add_variant_option, identify_listing, resolve_store},
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::{Reader, StringRecord};
+use open_stock::{
+    DiscountValue, Location, Product, ProductVisibility, Quantity, Stock, StockInformation, Store,
+    VariantInformation,
+};
+use phf::{phf_map, Map};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "item-name\titem-description\tlisting-id\tseller-sku\tprice\tquantity\topen-date\timage-url\titem-is-marketplace\tproduct-id-type\tzshop-shipping-fee\titem-note\titem-condition\tzshop-category1\tzshop-browse-path\tzshop-storefront-feature\tasin1\tasin2\tasin3\twill-ship-internationally\texpedited-shipping\tzshop-boldface\tproduct-id\tbid-for-featured-placement\tadd-delete\tpending-quantity\tfulfillment-channel\tmerchant-shipping-group\tstatus",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "ZZZZ",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "ZZZZ",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The store standing for the seller, stocking the listings it fulfils itself.
+const MERCHANT: &str = "Amazon";
+
+/// The store stocking the listings fulfilled by Amazon.
+const FULFILLED_BY_AMAZON: &str = "Fulfilled by Amazon";
+
+/// The columns naming the options of a child listing, and the option each names.
+const ATTRIBUTE_COLUMNS: [(&str, &str); 7] = [
+    ("size-name", "Size"),
+    ("color-name", "Color"),
+    ("style-name", "Style"),
+    ("pattern-name", "Pattern"),
+    ("material-type", "Material"),
+    ("flavor-name", "Flavor"),
+    ("scent-name", "Scent"),
+];
+
+/// The conditions named by the `item-condition` column.
+static CONDITIONS: Map<&'static str, &'static str> = phf_map! {
+    "1" => "Used - Like New",
+    "2" => "Used - Very Good",
+    "3" => "Used - Good",
+    "4" => "Used - Acceptable",
+    "5" => "Collectible - Like New",
+    "6" => "Collectible - Very Good",
+    "7" => "Collectible - Good",
+    "8" => "Collectible - Acceptable",
+    "10" => "Refurbished",
+    "11" => "New",
+};
+
+/// Reads the `open-date` of a listing, such as `2023-05-01 10:00:00 PDT`,
+/// ignoring its time zone.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+
+    NaiveDateTime::parse_from_str(value.get(..19).unwrap_or(value), "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|timestamp| timestamp.and_utc())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "item-name")]
+    item_name: String,
+
+    #[serde(rename = "item-description")]
+    item_description: String,
+
+    #[serde(rename = "listing-id")]
+    listing_id: String,
+
+    #[serde(rename = "seller-sku")]
+    seller_sku: String,
+
+    #[serde(rename = "price")]
+    price: String,
+
+    #[serde(rename = "quantity")]
+    quantity: String,
+
+    #[serde(rename = "open-date")]
+    open_date: String,
+
+    #[serde(rename = "image-url")]
+    image_url: String,
+
+    /// `1` (ASIN), `2` (ISBN), `3` (UPC) or `4` (EAN).
+    #[serde(rename = "product-id-type")]
+    product_id_type: String,
+
+    #[serde(rename = "item-condition")]
+    item_condition: String,
+
+    #[serde(rename = "asin1")]
+    asin: String,
+
+    #[serde(rename = "product-id")]
+    product_id: String,
+
+    /// `DEFAULT` for listings fulfilled by the merchant,
+    /// otherwise the Amazon network which fulfils them.
+    #[serde(rename = "fulfillment-channel")]
+    fulfillment_channel: String,
+
+    /// `Active`, `Inactive` or `Incomplete`.
+    #[serde(rename = "status")]
+    status: String,
+
+    /// `parent` or `child`, when the report relates its listings.
+    #[serde(rename = "parent-child", default)]
+    parent_child: String,
+
+    #[serde(rename = "parent-sku", default)]
+    parent_sku: String,
+
+    /// Read from the attribute columns, as `(option, value)`.
+    #[serde(skip)]
+    attributes: Vec<(String, String)>,
+}
+
+impl ProductRecord {
+    fn is_parent(&self) -> bool {
+        self.parent_child.eq_ignore_ascii_case("parent")
+    }
+
+    fn is_child_of(&self, parent: &ProductRecord) -> bool {
+        self.parent_child.eq_ignore_ascii_case("child") && self.parent_sku == parent.seller_sku
+    }
+
+    /// The barcode a listing was matched to the catalog by, if it was not its ASIN.
+    fn barcode(&self) -> &str {
+        match self.product_id_type.as_str() {
+            "2" | "3" | "4" => &self.product_id,
+            _ => "",
+        }
+    }
+
+    fn fulfilled_by_amazon(&self) -> bool {
+        !self.fulfillment_channel.is_empty()
+            && !self.fulfillment_channel.eq_ignore_ascii_case("DEFAULT")
+    }
+}
+
+/// Parses a listings report, reading the options of child listings
+/// from whichever attribute columns the report carries.
+pub fn parse_products(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(error) => return Err(ParseFailure::ReadFailure(error.to_string())),
+    };
+
+    let collected: Vec<Result<ProductRecord, csv::Error>> = reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let mut product: ProductRecord = record.deserialize(Some(&headers))?;
+            product.attributes = read_attributes(&headers, &record);
+            Ok(product)
+        })
+        .collect();
+
+    parse_collected(collected, db)
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+fn read_attributes(headers: &StringRecord, record: &StringRecord) -> Vec<(String, String)> {
+    ATTRIBUTE_COLUMNS
+        .iter()
+        .filter_map(|(column, option)| {
+            headers
+                .iter()
+                .position(|header| header == *column)
+                .and_then(|index| record.get(index))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(|value| (option.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Listings fulfilled by the merchant are stocked at the merchant's store,
+/// those fulfilled by Amazon are stocked at Amazon's.
+fn listing_store(record: &ProductRecord, db: &mut InlineDatabase) -> Option<Store> {
+    if record.fulfilled_by_amazon() {
+        resolve_store(FULFILLED_BY_AMAZON, db)
+    } else {
+        resolve_store(MERCHANT, db)
+    }
+}
+
+fn build_variant(record: &ProductRecord, name: String, store: Option<Store>) -> VariantInformation {
+    let price = record.price.parse::<f32>().unwrap_or(0.0);
+
+    VariantInformation {
+        name,
+        stock: match store {
+            Some(store) => vec![Stock {
+                store: Location {
+                    store_code: store.code.clone(),
+                    store_id: store.id.clone(),
+                    contact: store.contact.clone(),
+                },
+                quantity: Quantity {
+                    quantity_sellable: record.quantity.parse::<f32>().unwrap_or(0.0),
+                    quantity_unsellable: 0.0,
+                    quantity_on_order: 0.0,
+                    quantity_allocated: 0.0,
+                },
+            }],
+            None => vec![],
+        },
+        images: if record.image_url.is_empty() {
+            vec![]
+        } else {
+            vec![record.image_url.clone()]
+        },
+        retail_price: price,
+        marginal_price: price,
+        loyalty_discount: DiscountValue::Absolute(0),
+        variant_code: [&record.seller_sku, &record.listing_id]
+            .into_iter()
+            .filter(|code| !code.is_empty())
+            .cloned()
+            .collect(),
+        order_history: vec![],
+        stock_information: StockInformation {
+            stock_group: String::new(),
+            sales_group: String::new(),
+            value_stream: String::new(),
+            brand: String::new(),
+            tax_code: String::new(),
+            weight: "0".to_string(),
+            volume: "0.00".to_string(),
+            max_volume: "0.00".to_string(),
+            back_order: false,
+            discontinued: false,
+            non_diminishing: false,
+            shippable: true,
+            size_override_unit: String::new(),
+            size_x_unit: String::new(),
+            size_y_unit: String::new(),
+            size_z_unit: String::new(),
+            size_x: 0.0,
+            size_y: 0.0,
+            size_z: 0.0,
+            min_stock_before_alert: 0.0,
+            min_stock_level: 0.0,
+            colli: String::new(),
+        },
+        barcode: record.barcode().to_string(),
+        id: Uuid::new_v4().to_string(),
+        buy_max: -1.0,
+        buy_min: 1.0,
+        identification: identify_listing(&record.seller_sku, record.barcode(), &record.asin),
+        stock_tracking: true,
+    }
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let first = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        // A parent ASIN cannot be bought, only the children which follow it.
+        let children: Vec<&ProductRecord> = if first.is_parent() {
+            reader[*line..]
+                .iter()
+                .map_while(|row| row.as_ref().ok())
+                .take_while(|row| row.is_child_of(first))
+                .collect()
+        } else {
+            vec![]
+        };
+
+        *line += children.len();
+
+        let mut product = Product {
+            name: first.item_name.clone(),
+            company: String::new(),
+            variant_groups: vec![],
+            variants: vec![],
+            sku: first.seller_sku.clone(),
+            images: if first.image_url.is_empty() {
+                vec![]
+            } else {
+                vec![first.image_url.clone()]
+            },
+            tags: vec![],
+            description: first.item_description.clone(),
+            specifications: vec![],
+            name_long: first.item_name.clone(),
+            identification: identify_listing(&first.seller_sku, "", &first.asin),
+            description_long: first.item_description.clone(),
+            visible: if first.status.is_empty() || first.status.eq_ignore_ascii_case("Active") {
+                ProductVisibility::ShowWhenInStock
+            } else {
+                ProductVisibility::AlwaysHidden
+            },
+            created_at: parse_timestamp(&first.open_date).unwrap_or(Utc::now()),
+            updated_at: Utc::now(),
+        };
+
+        if let Some(condition) = CONDITIONS.get(first.item_condition.trim()) {
+            product
+                .specifications
+                .push(("Condition".to_string(), condition.to_string()));
+        }
+
+        if first.fulfilled_by_amazon() {
+            product.specifications.push((
+                "Fulfillment Channel".to_string(),
+                first.fulfillment_channel.clone(),
+            ));
+        }
+
+        if !first.is_parent() {
+            let store = listing_store(first, db);
+            product
+                .variants
+                .push(build_variant(first, first.item_name.clone(), store));
+
+            return Ok(product);
+        }
+
+        if children.is_empty() {
+            println!(
+                "[warn]: Parent listing {} has no children in the report.",
+                first.seller_sku
+            );
+        }
+
+        for child in children {
+            for (option, value) in &child.attributes {
+                add_variant_option(&mut product, option, value);
+            }
+
+            let name = child
+                .attributes
+                .iter()
+                .map(|(_, value)| value.as_str())
+                .collect::<Vec<&str>>()
+                .join(" ");
+
+            let name = if name.is_empty() {
+                child.item_name.clone()
+            } else {
+                name
+            };
+
+            let store = listing_store(child, db);
+            product.variants.push(build_variant(child, name, store));
+        }
+
+        Ok(product)
+    }
+}
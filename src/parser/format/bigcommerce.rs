@@ -0,0 +1,1110 @@
+//! BigCommerce exports.
+//!
+//! The "Bulk Edit" product export lists each product as a `Product` row,
+//! followed by a `SKU` row per variant and a `Rule` row per price rule.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_variant_option, identify, lowercase_tags, parse_flag, parse_tags, resolve_store,
+        split_payment_methods, weight_in_kilograms, weight_unit, Barcode, PaymentKind,
+        PaymentMethodTable, ProductStatus, SpecialPricing,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::{Reader, StringRecord};
+use open_stock::{
+    Address, ContactInformation, Customer, CustomerType, DiscountValue, Email, FulfillmentStatus,
+    Location, MobileNumber, Note, Order, OrderStatus, OrderStatusAssignment, OrderType, Payment,
+    PaymentAction, PaymentMethod, PaymentProcessor, PaymentStatus, PickStatus, Price, Processable,
+    Product, ProductIdentification, ProductInstance, ProductPurchase, Quantity, Stock,
+    StockInformation, Store, Transaction, TransactionCustomer, TransactionType, VariantInformation,
+};
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "Item Type,Product ID,Product Name,Product Type,Product Code/SKU,Bin Picking Number,Brand Name,Option Set,Option Set Align,Product Description,Price,Cost Price,Retail Price,Sale Price,Fixed Shipping Cost,Free Shipping,Product Warranty,Product Weight,Product Width,Product Height,Product Depth,Allow Purchases?,Product Visible?,Product Availability,Track Inventory,Current Stock Level,Low Stock Level,Category,Product Image File - 1,Product Image Description - 1,Product Image Is Thumbnail - 1,Product Image Sort - 1,Search Keywords,Page Title,Meta Keywords,Meta Description,Product Condition,Product Tax Class,Product UPC/EAN",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "Customer ID,First Name,Last Name,Company,Email,Phone,Notes,Store Credit,Customer Group,Date Joined,Addresses,Receive Review/Abandoned Cart Emails?,Tax Exempt Category",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "Order ID,Customer ID,Customer Name,Customer Email,Customer Phone,Order Date,Order Status,Subtotal (inc tax),Subtotal (ex tax),Tax Total,Shipping Cost (inc tax),Shipping Cost (ex tax),Order Total (inc tax),Order Total (ex tax),Payment Method,Total Quantity,Date Shipped,Order Currency Code,Order Notes,Customer Message,Billing First Name,Billing Last Name,Billing Company,Billing Street 1,Billing Street 2,Billing Suburb,Billing State,Billing Zip,Billing Country,Billing Phone,Billing Email,Shipping First Name,Shipping Last Name,Shipping Company,Shipping Street 1,Shipping Street 2,Shipping Suburb,Shipping State,Shipping Zip,Shipping Country,Shipping Phone,Shipping Email,Shipping Method,Product Details,Coupon Details,Store Credit Redeemed,Gift Certificate Amount Redeemed",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The unit weights are exported in unless set otherwise with
+/// `ConversionOptions::set_weight_unit`,
+/// being the unit BigCommerce stores are created with.
+pub const DEFAULT_WEIGHT_UNIT: &str = "lb";
+
+/// The store standing for the single warehouse orders are fulfilled from.
+const WAREHOUSE: &str = "BigCommerce";
+
+/// The prefix of the numbered product image columns,
+/// e.g. `Product Image File - 1`.
+const IMAGE_PREFIX: &str = "Product Image File - ";
+
+/// The fields of each address within a customer's `Addresses` column.
+const ADDRESS_FIELDS: [&str; 11] = [
+    "Address ID",
+    "Address First Name",
+    "Address Last Name",
+    "Address Company",
+    "Address Line 1",
+    "Address Line 2",
+    "City",
+    "State/Province",
+    "Zip/Postcode",
+    "Country",
+    "Address Phone",
+];
+
+/// The fields of each line item within an order's `Product Details` column.
+const PRODUCT_DETAIL_FIELDS: [&str; 8] = [
+    "Product ID",
+    "Product Qty",
+    "Product SKU",
+    "Product Name",
+    "Product Weight",
+    "Product Variation Details",
+    "Product Unit Price",
+    "Product Total Price",
+];
+
+pub static PAYMENT_METHODS: PaymentMethodTable = phf_map! {
+    "credit card" => PaymentKind::Card,
+    "stripe" => PaymentKind::Card,
+    "square" => PaymentKind::Card,
+    "braintree" => PaymentKind::Card,
+    "cash on delivery" => PaymentKind::Cash,
+    "bank deposit" => PaymentKind::Transfer,
+    "gift certificate" => PaymentKind::GiftCard,
+    "store credit" => PaymentKind::StoreCredit,
+};
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "BIGCOMMERCE-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Reads the dates of exports, which follow the store's date
+/// format setting, but default to `7/05/2023` or `2023-05-07 10:00:00`.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                .map(|timestamp| timestamp.and_utc())
+                .ok()
+        })
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%d/%m/%Y")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|timestamp| timestamp.and_utc())
+        })
+}
+
+/// Reads one entry of a `Key: value, Key: value` list, as the `Addresses`
+/// and `Product Details` columns are exported. Values may hold commas of
+/// their own, so entries are only split before one of the expected `keys`.
+fn parse_key_values(entry: &str, keys: &[&str]) -> Vec<(String, String)> {
+    let mut fields: Vec<(String, String)> = vec![];
+
+    for segment in entry.split(", ") {
+        let field = keys.iter().find_map(|key| {
+            segment
+                .trim_start()
+                .strip_prefix(key)
+                .and_then(|rest| rest.strip_prefix(':'))
+                .map(|value| (key.to_string(), value.trim().to_string()))
+        });
+
+        match (field, fields.last_mut()) {
+            (Some(field), _) => fields.push(field),
+            (None, Some((_, value))) => {
+                value.push_str(", ");
+                value.push_str(segment.trim());
+            }
+            (None, None) => {}
+        }
+    }
+
+    fields
+}
+
+fn field<'a>(fields: &'a [(String, String)], key: &str) -> &'a str {
+    fields
+        .iter()
+        .find(|(name, _)| name == key)
+        .map_or("", |(_, value)| value.as_str())
+}
+
+/// Reads the options of a `SKU` or `Rule` row, named as `[S]Size=Small,[CS]Colour=Red`,
+/// where the bracketed prefix is the type of the option's control.
+fn parse_options(value: &str) -> Vec<(String, String)> {
+    value
+        .split(',')
+        .filter_map(|option| {
+            let option = match option.trim().split_once(']') {
+                Some((_, option)) => option,
+                None => option.trim(),
+            };
+
+            option
+                .split_once('=')
+                .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Applies a rule's price adjustment, such as `[FIXED]15.00` or `[ADD]2.50`.
+fn adjust_price(price: f32, adjustment: &str) -> f32 {
+    let (kind, amount) = match adjustment.trim().split_once(']') {
+        Some((kind, amount)) => (kind.trim_start_matches('['), amount),
+        None => ("FIXED", adjustment),
+    };
+
+    let amount = match amount.trim().parse::<f32>() {
+        Ok(amount) => amount,
+        Err(_) => return price,
+    };
+
+    match kind.to_uppercase().as_str() {
+        "ADD" => price + amount,
+        "REMOVE" => price - amount,
+        _ => amount,
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "Item Type")]
+    item_type: String,
+
+    #[serde(rename = "Product Name")]
+    name: String,
+
+    #[serde(rename = "Product Type")]
+    product_type: String,
+
+    #[serde(rename = "Product Code/SKU")]
+    sku: String,
+
+    #[serde(rename = "Brand Name")]
+    brand: String,
+
+    #[serde(rename = "Product Description")]
+    description: String,
+
+    #[serde(rename = "Price")]
+    price: String,
+
+    #[serde(rename = "Cost Price")]
+    cost_price: String,
+
+    #[serde(rename = "Product Weight")]
+    weight: String,
+
+    #[serde(rename = "Product Width")]
+    width: String,
+
+    #[serde(rename = "Product Height")]
+    height: String,
+
+    #[serde(rename = "Product Depth")]
+    depth: String,
+
+    #[serde(rename = "Allow Purchases?")]
+    allow_purchases: String,
+
+    #[serde(rename = "Product Visible?")]
+    visible: String,
+
+    #[serde(rename = "Track Inventory")]
+    track_inventory: String,
+
+    #[serde(rename = "Current Stock Level")]
+    stock_level: String,
+
+    #[serde(rename = "Low Stock Level")]
+    low_stock_level: String,
+
+    #[serde(rename = "Category")]
+    category: String,
+
+    #[serde(rename = "Search Keywords")]
+    search_keywords: String,
+
+    #[serde(rename = "Page Title")]
+    page_title: String,
+
+    #[serde(rename = "Meta Description")]
+    meta_description: String,
+
+    #[serde(rename = "Product Condition")]
+    condition: String,
+
+    #[serde(rename = "Product Tax Class")]
+    tax_class: String,
+
+    #[serde(rename = "Product UPC/EAN")]
+    barcode: String,
+
+    /// The `Product Image File - N` columns, in order.
+    #[serde(skip)]
+    images: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerRecord {
+    #[serde(rename = "First Name")]
+    first_name: String,
+
+    #[serde(rename = "Last Name")]
+    last_name: String,
+
+    #[serde(rename = "Company")]
+    company: String,
+
+    #[serde(rename = "Email")]
+    email: String,
+
+    #[serde(rename = "Phone")]
+    phone: String,
+
+    #[serde(rename = "Notes")]
+    notes: String,
+
+    #[serde(rename = "Store Credit")]
+    store_credit: String,
+
+    #[serde(rename = "Customer Group")]
+    customer_group: String,
+
+    #[serde(rename = "Date Joined")]
+    date_joined: String,
+
+    #[serde(rename = "Addresses")]
+    addresses: String,
+
+    #[serde(rename = "Receive Review/Abandoned Cart Emails?")]
+    accepts_marketing: String,
+
+    #[serde(rename = "Tax Exempt Category")]
+    tax_exempt_category: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionRecord {
+    #[serde(rename = "Order ID")]
+    order_id: String,
+
+    #[serde(rename = "Customer Name")]
+    customer_name: String,
+
+    #[serde(rename = "Customer Email")]
+    customer_email: String,
+
+    #[serde(rename = "Customer Phone")]
+    customer_phone: String,
+
+    #[serde(rename = "Order Date")]
+    order_date: String,
+
+    #[serde(rename = "Order Status")]
+    order_status: String,
+
+    #[serde(rename = "Order Total (inc tax)")]
+    total: String,
+
+    #[serde(rename = "Payment Method")]
+    payment_method: String,
+
+    #[serde(rename = "Date Shipped")]
+    date_shipped: String,
+
+    #[serde(rename = "Order Currency Code")]
+    currency: String,
+
+    #[serde(rename = "Order Notes")]
+    order_notes: String,
+
+    #[serde(rename = "Customer Message")]
+    customer_message: String,
+
+    #[serde(rename = "Billing Street 1")]
+    billing_street: String,
+
+    #[serde(rename = "Billing Street 2")]
+    billing_street2: String,
+
+    #[serde(rename = "Billing Suburb")]
+    billing_city: String,
+
+    #[serde(rename = "Billing State")]
+    billing_state: String,
+
+    #[serde(rename = "Billing Zip")]
+    billing_zip: String,
+
+    #[serde(rename = "Billing Country")]
+    billing_country: String,
+
+    #[serde(rename = "Shipping First Name")]
+    shipping_first_name: String,
+
+    #[serde(rename = "Shipping Last Name")]
+    shipping_last_name: String,
+
+    #[serde(rename = "Shipping Street 1")]
+    shipping_street: String,
+
+    #[serde(rename = "Shipping Street 2")]
+    shipping_street2: String,
+
+    #[serde(rename = "Shipping Suburb")]
+    shipping_city: String,
+
+    #[serde(rename = "Shipping State")]
+    shipping_state: String,
+
+    #[serde(rename = "Shipping Zip")]
+    shipping_zip: String,
+
+    #[serde(rename = "Shipping Country")]
+    shipping_country: String,
+
+    #[serde(rename = "Shipping Phone")]
+    shipping_phone: String,
+
+    #[serde(rename = "Shipping Method")]
+    shipping_method: String,
+
+    #[serde(rename = "Product Details")]
+    product_details: String,
+
+    #[serde(rename = "Coupon Details")]
+    coupon_details: String,
+
+    #[serde(rename = "Store Credit Redeemed")]
+    store_credit_redeemed: String,
+
+    #[serde(rename = "Gift Certificate Amount Redeemed")]
+    gift_certificate_redeemed: String,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+/// Parses a bulk edit product export, which carries a
+/// numbered set of columns for each product image.
+pub fn parse_products(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(error) => return Err(ParseFailure::ReadFailure(error.to_string())),
+    };
+
+    let collected: Vec<Result<ProductRecord, csv::Error>> = reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let mut product: ProductRecord = record.deserialize(Some(&headers))?;
+            product.images = read_images(&headers, &record);
+            Ok(product)
+        })
+        .collect();
+
+    parse_collected(collected, db)
+}
+
+fn read_images(headers: &StringRecord, record: &StringRecord) -> Vec<String> {
+    let mut images: Vec<(u32, String)> = headers
+        .iter()
+        .zip(record.iter())
+        .filter(|(_, value)| !value.trim().is_empty())
+        .filter_map(|(header, value)| {
+            header
+                .strip_prefix(IMAGE_PREFIX)
+                .and_then(|index| index.trim().parse::<u32>().ok())
+                .map(|index| (index, value.trim().to_string()))
+        })
+        .collect();
+
+    images.sort_by_key(|(index, _)| *index);
+    images.into_iter().map(|(_, image)| image).collect()
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+/// Builds a variant from the `Product` row, or a `SKU` row of it.
+fn build_variant(
+    product: &ProductRecord,
+    record: &ProductRecord,
+    name: String,
+    store: &Option<Store>,
+    status: ProductStatus,
+) -> VariantInformation {
+    // `SKU` rows leave empty any column they inherit from the product.
+    let inherit = |value: &str, fallback: &str| -> String {
+        if value.trim().is_empty() {
+            fallback.to_string()
+        } else {
+            value.to_string()
+        }
+    };
+
+    let price = inherit(&record.price, &product.price)
+        .parse::<f32>()
+        .unwrap_or(0.0);
+    let barcode = inherit(&record.barcode, &product.barcode);
+    let track_inventory = product.track_inventory.to_lowercase();
+
+    // Inventory is tracked either for the product as a whole, or for each SKU.
+    let stock_tracking = matches!(track_inventory.as_str(), "by product" | "by option");
+
+    let stock = match store {
+        Some(store) if stock_tracking => vec![Stock {
+            store: Location {
+                store_code: store.code.clone(),
+                store_id: store.id.clone(),
+                contact: store.contact.clone(),
+            },
+            quantity: Quantity {
+                quantity_sellable: record.stock_level.parse::<f32>().unwrap_or(0.0),
+                quantity_unsellable: 0.0,
+                quantity_on_order: 0.0,
+                quantity_allocated: 0.0,
+            },
+        }],
+        _ => vec![],
+    };
+
+    if !barcode.is_empty() && Barcode::parse(&barcode).is_none() {
+        println!(
+            "[warn]: Barcode {} of {} is not a valid EAN, UPC or ISBN.",
+            barcode, record.sku
+        );
+    }
+
+    let dimension = |value: &str, fallback: &str| -> f64 {
+        inherit(value, fallback).parse::<f64>().unwrap_or(0.0)
+    };
+
+    VariantInformation {
+        name,
+        stock,
+        images: vec![],
+        retail_price: price,
+        marginal_price: inherit(&record.cost_price, &product.cost_price)
+            .parse::<f32>()
+            .unwrap_or(price),
+        loyalty_discount: DiscountValue::Absolute(0),
+        variant_code: vec![record.sku.clone()],
+        order_history: vec![],
+        stock_information: StockInformation {
+            stock_group: product.product_type.clone(),
+            sales_group: product.category.clone(),
+            value_stream: String::new(),
+            brand: product.brand.clone(),
+            tax_code: product.tax_class.clone(),
+            // Weights and dimensions are in the store's units, which are not
+            // part of the export, so weights are read in the unit configured.
+            weight: weight_in_kilograms(
+                &inherit(&record.weight, &product.weight),
+                &weight_unit("bigcommerce", DEFAULT_WEIGHT_UNIT),
+            )
+            .unwrap_or(0.0)
+            .to_string(),
+            volume: "0.00".to_string(),
+            max_volume: "0.00".to_string(),
+            back_order: false,
+            discontinued: status.discontinued(),
+            // Digital products are never removed from inventory.
+            non_diminishing: product.product_type.eq_ignore_ascii_case("D"),
+            shippable: !product.product_type.eq_ignore_ascii_case("D"),
+            size_override_unit: String::new(),
+            size_x_unit: String::new(),
+            size_y_unit: String::new(),
+            size_z_unit: String::new(),
+            size_x: dimension(&record.width, &product.width),
+            size_y: dimension(&record.height, &product.height),
+            size_z: dimension(&record.depth, &product.depth),
+            min_stock_before_alert: inherit(&record.low_stock_level, &product.low_stock_level)
+                .parse::<f64>()
+                .unwrap_or(0.0),
+            min_stock_level: 0.0,
+            colli: String::new(),
+        },
+        barcode: barcode.clone(),
+        id: Uuid::new_v4().to_string(),
+        buy_max: -1.0,
+        buy_min: 0.0,
+        identification: identify(&record.sku, &barcode),
+        stock_tracking,
+    }
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let first = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        if first.item_type.trim() != "Product" {
+            return Err(ParseFailure::FormatFailure(format!(
+                "Expected a product, found {} row {}.",
+                first.item_type, first.sku
+            )));
+        }
+
+        // Products which cannot be purchased are no longer sold.
+        let status = if parse_flag(&first.allow_purchases) {
+            ProductStatus::Active
+        } else {
+            ProductStatus::Archived
+        };
+
+        let mut product = Product {
+            name: first.name.clone(),
+            company: first.brand.clone(),
+            variant_groups: vec![],
+            variants: vec![],
+            sku: first.sku.clone(),
+            images: first.images.clone(),
+            tags: parse_tags(&first.search_keywords, ',', lowercase_tags()),
+            description: first.description.clone(),
+            specifications: vec![],
+            name_long: first.name.clone(),
+            identification: ProductIdentification {
+                sku: first.sku.clone(),
+                ..Default::default()
+            },
+            description_long: first.description.clone(),
+            visible: status.visibility(parse_flag(&first.visible)),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        for (name, value) in [
+            ("SEO Title", &first.page_title),
+            ("SEO Description", &first.meta_description),
+            ("Condition", &first.condition),
+        ] {
+            if !value.is_empty() {
+                product
+                    .specifications
+                    .push((name.to_string(), value.to_string()));
+            }
+        }
+
+        // Orders are fulfilled from a single warehouse, which holds the stock.
+        let store = resolve_store(WAREHOUSE, db);
+
+        let mut rules: Vec<(Vec<(String, String)>, String)> = vec![];
+        let mut variant_options: Vec<Vec<(String, String)>> = vec![];
+
+        // `SKU` and `Rule` rows follow their product, as the
+        // handle of a Shopify export groups a product's rows.
+        while let Some(Ok(cloned)) = reader.get(*line) {
+            match cloned.item_type.trim() {
+                "SKU" => {
+                    let options = parse_options(&cloned.name);
+
+                    for (category, value) in &options {
+                        add_variant_option(&mut product, category, value);
+                    }
+
+                    let name = options
+                        .iter()
+                        .map(|(_, value)| value.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(" ");
+
+                    product
+                        .variants
+                        .push(build_variant(first, cloned, name, &store, status));
+                    variant_options.push(options);
+                }
+                "Rule" => rules.push((parse_options(&cloned.name), cloned.price.clone())),
+                _ => break,
+            }
+
+            *line += 1;
+        }
+
+        if product.variants.is_empty() {
+            product.variants.push(build_variant(
+                first,
+                first,
+                product.name.clone(),
+                &store,
+                status,
+            ));
+        }
+
+        // A rule adjusts the price of every variant with all of its options.
+        for (options, adjustment) in rules {
+            for (variant, values) in product.variants.iter_mut().zip(&variant_options) {
+                if options.iter().all(|option| values.contains(option)) {
+                    variant.retail_price = adjust_price(variant.retail_price, &adjustment);
+                }
+            }
+        }
+
+        Ok(product)
+    }
+}
+
+impl Parsable<CustomerRecord> for Customer {
+    fn parse_individual(
+        reader: &[Result<CustomerRecord, csv::Error>],
+        line: &mut usize,
+        _db: &mut InlineDatabase,
+    ) -> Result<Customer, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let name = format!("{} {}", cloned.first_name, cloned.last_name)
+            .trim()
+            .to_string();
+
+        let mut addresses = cloned
+            .addresses
+            .split('|')
+            .map(|entry| parse_key_values(entry, &ADDRESS_FIELDS))
+            .filter(|fields| !fields.is_empty())
+            .map(|fields| Address {
+                street: field(&fields, "Address Line 1").to_string(),
+                street2: field(&fields, "Address Line 2").to_string(),
+                city: match (field(&fields, "City"), field(&fields, "State/Province")) {
+                    (city, "") => city.to_string(),
+                    ("", state) => state.to_string(),
+                    (city, state) => format!("{}, {}", city, state),
+                },
+                country: field(&fields, "Country").to_string(),
+                po_code: field(&fields, "Zip/Postcode").to_string(),
+                lat: 0.0,
+                lon: 0.0,
+            });
+
+        let address = addresses.next().unwrap_or(Address {
+            street: String::new(),
+            street2: String::new(),
+            city: String::new(),
+            country: String::new(),
+            po_code: String::new(),
+            lat: 0.0,
+            lon: 0.0,
+        });
+
+        let mut customer_notes = vec![];
+
+        if !cloned.notes.is_empty() {
+            customer_notes.push(import_note(cloned.notes.clone()));
+        }
+
+        if !cloned.customer_group.is_empty() {
+            customer_notes.push(import_note(format!(
+                "Customer group: {}",
+                cloned.customer_group
+            )));
+        }
+
+        if cloned.store_credit.parse::<f32>().unwrap_or(0.0) != 0.0 {
+            customer_notes.push(import_note(format!(
+                "Store credit: {}",
+                cloned.store_credit
+            )));
+        }
+
+        // Customers hold a single address, others are kept as notes.
+        for additional in addresses {
+            customer_notes.push(import_note(format!(
+                "Address: {}",
+                [
+                    additional.street,
+                    additional.street2,
+                    additional.city,
+                    additional.po_code,
+                    additional.country,
+                ]
+                .iter()
+                .filter(|part| !part.is_empty())
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ")
+            )));
+        }
+
+        Ok(Customer {
+            id: Uuid::new_v4().to_string(),
+            name: if name.is_empty() {
+                cloned.company.clone()
+            } else {
+                name.clone()
+            },
+            contact: ContactInformation {
+                name: match (name.is_empty(), cloned.company.is_empty()) {
+                    (_, true) => name.clone(),
+                    (true, false) => cloned.company.clone(),
+                    (false, false) => format!("{} ({})", name, cloned.company),
+                },
+                mobile: MobileNumber::from(cloned.phone.clone()),
+                email: Email::from(cloned.email.clone()),
+                landline: String::new(),
+                address,
+            },
+            customer_notes,
+            balance: 0,
+            special_pricing: SpecialPricing::tax_exempt(!cloned.tax_exempt_category.is_empty())
+                .to_field(),
+            accepts_marketing: parse_flag(&cloned.accepts_marketing),
+            created_at: parse_timestamp(&cloned.date_joined).unwrap_or(Utc::now()),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+/// Builds a payment for each tender of an order. Store credit and gift
+/// certificates are redeemed alongside the order's payment method.
+fn build_payments(record: &TransactionRecord, date: DateTime<Utc>) -> Vec<Payment> {
+    let total = record.total.parse::<f32>().unwrap_or(0.0);
+    let store_credit = record.store_credit_redeemed.parse::<f32>().unwrap_or(0.0);
+    let gift_certificate = record
+        .gift_certificate_redeemed
+        .parse::<f32>()
+        .unwrap_or(0.0);
+
+    let method = split_payment_methods(&record.payment_method, &PAYMENT_METHODS)
+        .into_iter()
+        .next()
+        .map_or(
+            PaymentMethod::Other(record.payment_method.clone()),
+            |split| split.method,
+        );
+
+    [
+        (PaymentKind::StoreCredit.into(), store_credit),
+        (PaymentKind::GiftCard.into(), gift_certificate),
+        (method, total - store_credit - gift_certificate),
+    ]
+    .into_iter()
+    .filter(|(_, amount)| *amount > 0.0)
+    .map(|(payment_method, amount)| Payment {
+        id: Uuid::new_v4().to_string(),
+        payment_method,
+        fulfillment_date: date,
+        amount: Price {
+            quantity: amount,
+            currency: record.currency.clone(),
+        },
+        processing_fee: Price {
+            quantity: 0.0,
+            currency: record.currency.clone(),
+        },
+        status: PaymentStatus::Complete(Processable::Anonymous(String::from("bigcommerce"))),
+        processor: PaymentProcessor::anonymous(String::from("bigcommerce")),
+        order_ids: vec![],
+        delay_action: PaymentAction::Complete,
+        delay_duration: String::new(),
+    })
+    .collect()
+}
+
+impl Parsable<TransactionRecord> for Transaction {
+    fn parse_individual(
+        reader: &[Result<TransactionRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Transaction, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let date = parse_timestamp(&cloned.order_date).unwrap_or(Utc::now());
+        let shipped = parse_timestamp(&cloned.date_shipped).unwrap_or(date);
+
+        let customer = match db.1.iter().find(|customer| {
+            !cloned.customer_email.is_empty()
+                && customer
+                    .contact
+                    .email
+                    .full
+                    .eq_ignore_ascii_case(&cloned.customer_email)
+        }) {
+            Some(customer) => customer.clone(),
+            None => {
+                // Guest checkouts have no customer of their own.
+                let customer = Customer {
+                    id: Uuid::new_v4().to_string(),
+                    name: cloned.customer_name.clone(),
+                    contact: ContactInformation {
+                        name: cloned.customer_name.clone(),
+                        mobile: MobileNumber::from(cloned.customer_phone.clone()),
+                        email: Email::from(cloned.customer_email.clone()),
+                        landline: String::new(),
+                        address: Address {
+                            street: cloned.billing_street.clone(),
+                            street2: cloned.billing_street2.clone(),
+                            city: cloned.billing_city.clone(),
+                            country: cloned.billing_country.clone(),
+                            po_code: cloned.billing_zip.clone(),
+                            lat: 0.0,
+                            lon: 0.0,
+                        },
+                    },
+                    customer_notes: vec![],
+                    balance: 0,
+                    special_pricing: String::new(),
+                    accepts_marketing: false,
+                    created_at: date,
+                    updated_at: Utc::now(),
+                };
+
+                db.1.push(customer.clone());
+
+                customer
+            }
+        };
+
+        let status = match cloned.order_status.to_lowercase().as_str() {
+            "completed" | "shipped" => OrderStatus::Fulfilled(shipped),
+            "cancelled" | "declined" | "refunded" => {
+                OrderStatus::Failed(cloned.order_status.clone())
+            }
+            _ => OrderStatus::Processing(date),
+        };
+
+        let pick_status = match status {
+            OrderStatus::Fulfilled(_) => PickStatus::Picked,
+            _ => PickStatus::Pending,
+        };
+
+        let store = match resolve_store(WAREHOUSE, db) {
+            Some(store) => store,
+            None => {
+                return Err(ParseFailure::FormatFailure(format!(
+                    "Order {} has no store to originate from.",
+                    cloned.order_id
+                )))
+            }
+        };
+        let origin = Location {
+            contact: store.contact.clone(),
+            store_code: store.code.clone(),
+            store_id: store.id.clone(),
+        };
+
+        let order_type = if cloned.shipping_method.to_lowercase().contains("pickup") {
+            OrderType::Pickup
+        } else if cloned.shipping_street.is_empty() {
+            // Orders of digital products have nothing to ship.
+            OrderType::Direct
+        } else {
+            OrderType::Shipment
+        };
+
+        let destination = Location {
+            contact: match order_type {
+                OrderType::Shipment => ContactInformation {
+                    name: format!(
+                        "{} {}",
+                        cloned.shipping_first_name, cloned.shipping_last_name
+                    )
+                    .trim()
+                    .to_string(),
+                    mobile: MobileNumber::from(cloned.shipping_phone.clone()),
+                    email: Email::from(cloned.customer_email.clone()),
+                    landline: cloned.shipping_phone.clone(),
+                    address: Address {
+                        street: cloned.shipping_street.clone(),
+                        street2: cloned.shipping_street2.clone(),
+                        city: match (
+                            cloned.shipping_city.is_empty(),
+                            cloned.shipping_state.is_empty(),
+                        ) {
+                            (_, true) => cloned.shipping_city.clone(),
+                            (true, false) => cloned.shipping_state.clone(),
+                            (false, false) => {
+                                format!("{}, {}", cloned.shipping_city, cloned.shipping_state)
+                            }
+                        },
+                        country: cloned.shipping_country.clone(),
+                        po_code: cloned.shipping_zip.clone(),
+                        lat: 0.0,
+                        lon: 0.0,
+                    },
+                },
+                OrderType::Pickup => origin.contact.clone(),
+                _ => customer.contact.clone(),
+            },
+            store_code: origin.store_code.clone(),
+            store_id: origin.store_id.clone(),
+        };
+
+        let products = cloned
+            .product_details
+            .split('|')
+            .map(|entry| parse_key_values(entry, &PRODUCT_DETAIL_FIELDS))
+            .filter(|fields| !fields.is_empty())
+            .map(|fields| {
+                let quantity = field(&fields, "Product Qty").parse::<f32>().unwrap_or(0.0);
+                let variation = field(&fields, "Product Variation Details");
+
+                ProductPurchase {
+                    id: Uuid::new_v4().to_string(),
+                    product_code: field(&fields, "Product SKU").to_string(),
+                    product_name: field(&fields, "Product Name").to_string(),
+                    product_sku: field(&fields, "Product SKU").to_string(),
+                    product_cost: field(&fields, "Product Unit Price")
+                        .parse::<f32>()
+                        .unwrap_or(0.0),
+                    discount: DiscountValue::Absolute(0),
+                    product_variant_name: if variation.is_empty() {
+                        field(&fields, "Product Name").to_string()
+                    } else {
+                        variation.to_string()
+                    },
+                    quantity,
+                    tags: vec![],
+                    transaction_type: TransactionType::Out,
+                    instances: (0..quantity as u32)
+                        .map(|_| ProductInstance {
+                            id: Uuid::new_v4().to_string(),
+                            fulfillment_status: FulfillmentStatus {
+                                pick_status: pick_status.clone(),
+                                pick_history: vec![],
+                                last_updated: shipped,
+                                notes: vec![],
+                            },
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let order_notes: Vec<Note> = [
+            ("", &cloned.order_notes),
+            ("Customer message: ", &cloned.customer_message),
+            ("Coupon: ", &cloned.coupon_details),
+        ]
+        .iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(prefix, value)| import_note(format!("{}{}", prefix, value)))
+        .collect();
+
+        Ok(Transaction {
+            id: Uuid::new_v4().to_string(),
+            customer: TransactionCustomer {
+                customer_type: CustomerType::Individual,
+                customer_id: customer.id.clone(),
+            },
+            // Orders are placed online, at no register.
+            kiosk: String::new(),
+            transaction_type: TransactionType::Out,
+            products: vec![Order {
+                id: Uuid::new_v4().to_string(),
+                destination,
+                origin,
+                products,
+                status: OrderStatusAssignment {
+                    status,
+                    assigned_products: vec![],
+                    timestamp: shipped,
+                },
+                status_history: vec![],
+                order_history: vec![],
+                previous_failed_fulfillment_attempts: vec![],
+                order_notes: order_notes.clone(),
+                reference: cloned.order_id.clone(),
+                creation_date: date,
+                discount: DiscountValue::Absolute(0),
+                order_type,
+            }],
+            order_total: cloned.total.parse::<f32>().unwrap_or(0.0).round() as i64,
+            payment: build_payments(cloned, date),
+            order_date: date,
+            order_notes,
+            salesperson: String::new(),
+            created_at: date,
+            updated_at: Utc::now(),
+        })
+    }
+}
@@ -0,0 +1,862 @@
+//! Clover POS exports.
+//!
+//! Clover exports inventory as a workbook, whose `Items`, `Modifier Groups`
+//! and `Categories` sheets are each read as a file of their own. Items are
+//! parsed first, then extended by the modifier groups and categories that
+//! name them.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_variant_option, apportion_payment, identify, lowercase_tags, merge_tags, named_contact,
+        parse_flag, resolve_employee, resolve_kiosk, resolve_store, split_payment_methods,
+        PaymentKind, PaymentMethodTable,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::Reader;
+use open_stock::{
+    Address, ContactInformation, Customer, CustomerType, DiscountValue, Email, FulfillmentStatus,
+    Location, MobileNumber, Note, Order, OrderStatus, OrderStatusAssignment, OrderType, Payment,
+    PaymentAction, PaymentMethod, PaymentProcessor, PaymentStatus, PickStatus, Price, Processable,
+    Product, ProductIdentification, ProductInstance, ProductPurchase, ProductVisibility, Quantity,
+    Stock, StockInformation, Store, Transaction, TransactionCustomer, TransactionType,
+    VariantInformation,
+};
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "Clover ID,Name,Alternate Name,Price,Price Type,Price Unit,Tax Rates,Cost,Product Code,SKU,Modifier Groups,Quantity,Printer Labels,Hidden,Non-revenue item,Discountable,Max Discount Allowed,Categories",
+        ParseType::ProductModifier => "Modifier Group Name,Modifier Group Alternate Name,Modifier Name,Modifier Alternate Name,Modifier Price",
+        ParseType::ProductCategory => "Category Name,Items in Category",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "Customer ID,First Name,Last Name,Phone Number,Email Address,Address Line 1,Address Line 2,City,State,Postal Code,Country,Customer Since,Marketing Allowed,Note",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "Order ID,Order Date,Order Employee,Device,Customer Name,Currency,Order Total,Tender,Order Payment State,Order Note,Item Name,Item SKU,Item Quantity,Item Price,Modifiers,Item Discount,Refunded",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The store standing for the merchant's location.
+const MERCHANT: &str = "Clover";
+
+/// Modifier groups multiply an item's variants, beyond this
+/// many only the variant groups themselves are created.
+const MAX_VARIANTS: usize = 250;
+
+pub static PAYMENT_METHODS: PaymentMethodTable = phf_map! {
+    "cash" => PaymentKind::Cash,
+    "credit card" => PaymentKind::Card,
+    "debit card" => PaymentKind::Card,
+    "card" => PaymentKind::Card,
+    "check" => PaymentKind::Transfer,
+    "gift card" => PaymentKind::GiftCard,
+    "store credit" => PaymentKind::StoreCredit,
+};
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "CLOVER-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Reads the timestamps of exports, such as `05-May-2023 10:02 AM NZST`.
+/// The trailing time zone abbreviation is ambiguous, so it is ignored.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    let without_zone = match value.rsplit_once(' ') {
+        Some((rest, zone))
+            if zone.chars().all(|c| c.is_ascii_uppercase()) && zone != "AM" && zone != "PM" =>
+        {
+            rest
+        }
+        _ => value,
+    };
+
+    ["%d-%b-%Y %I:%M %p", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(without_zone, format).ok())
+        .map(|timestamp| timestamp.and_utc())
+}
+
+/// Splits a list column, such as `Modifier Groups` or `Categories`.
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|entry| entry.trim().to_string())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+/// The specification a product's modifier groups are kept in,
+/// until the `Modifier Groups` sheet is parsed.
+const MODIFIER_GROUPS: &str = "Modifier Groups";
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "Clover ID")]
+    clover_id: String,
+
+    #[serde(rename = "Name")]
+    name: String,
+
+    #[serde(rename = "Alternate Name")]
+    alternate_name: String,
+
+    #[serde(rename = "Price")]
+    price: String,
+
+    /// `Fixed`, `Variable` (priced at sale) or `Per Unit`.
+    #[serde(rename = "Price Type")]
+    price_type: String,
+
+    #[serde(rename = "Price Unit")]
+    price_unit: String,
+
+    #[serde(rename = "Tax Rates")]
+    tax_rates: String,
+
+    #[serde(rename = "Cost")]
+    cost: String,
+
+    #[serde(rename = "Product Code")]
+    product_code: String,
+
+    #[serde(rename = "SKU")]
+    sku: String,
+
+    #[serde(rename = "Modifier Groups")]
+    modifier_groups: String,
+
+    #[serde(rename = "Quantity")]
+    quantity: String,
+
+    #[serde(rename = "Hidden")]
+    hidden: String,
+
+    #[serde(rename = "Non-revenue item")]
+    non_revenue: String,
+
+    #[serde(rename = "Categories")]
+    categories: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ModifierRecord {
+    #[serde(rename = "Modifier Group Name")]
+    group: String,
+
+    #[serde(rename = "Modifier Name")]
+    name: String,
+
+    #[serde(rename = "Modifier Price")]
+    price: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerRecord {
+    #[serde(rename = "Customer ID")]
+    customer_id: String,
+
+    #[serde(rename = "First Name")]
+    first_name: String,
+
+    #[serde(rename = "Last Name")]
+    last_name: String,
+
+    #[serde(rename = "Phone Number")]
+    phone: String,
+
+    #[serde(rename = "Email Address")]
+    email: String,
+
+    #[serde(rename = "Address Line 1")]
+    address_street: String,
+
+    #[serde(rename = "Address Line 2")]
+    address_street2: String,
+
+    #[serde(rename = "City")]
+    address_city: String,
+
+    #[serde(rename = "State")]
+    address_state: String,
+
+    #[serde(rename = "Postal Code")]
+    address_postcode: String,
+
+    #[serde(rename = "Country")]
+    address_country: String,
+
+    #[serde(rename = "Customer Since")]
+    customer_since: String,
+
+    #[serde(rename = "Marketing Allowed")]
+    marketing_allowed: String,
+
+    #[serde(rename = "Note")]
+    note: String,
+}
+
+/// A line item of the orders export, which repeats
+/// the order's columns on each of its line items.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionRecord {
+    #[serde(rename = "Order ID")]
+    order_id: String,
+
+    #[serde(rename = "Order Date")]
+    order_date: String,
+
+    #[serde(rename = "Order Employee")]
+    employee: String,
+
+    #[serde(rename = "Device")]
+    device: String,
+
+    #[serde(rename = "Customer Name")]
+    customer_name: String,
+
+    #[serde(rename = "Currency")]
+    currency: String,
+
+    #[serde(rename = "Order Total")]
+    total: String,
+
+    #[serde(rename = "Tender")]
+    tender: String,
+
+    #[serde(rename = "Order Payment State")]
+    payment_state: String,
+
+    #[serde(rename = "Order Note")]
+    note: String,
+
+    #[serde(rename = "Item Name")]
+    item_name: String,
+
+    #[serde(rename = "Item SKU")]
+    item_sku: String,
+
+    #[serde(rename = "Item Quantity")]
+    item_quantity: String,
+
+    #[serde(rename = "Item Price")]
+    item_price: String,
+
+    #[serde(rename = "Modifiers")]
+    modifiers: String,
+
+    #[serde(rename = "Item Discount")]
+    item_discount: String,
+
+    #[serde(rename = "Refunded")]
+    refunded: String,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+fn location(store: &Store) -> Location {
+    Location {
+        store_code: store.code.clone(),
+        store_id: store.id.clone(),
+        contact: store.contact.clone(),
+    }
+}
+
+/// Clover devices belong to a single merchant location.
+fn merchant_store(db: &mut InlineDatabase) -> Option<Store> {
+    resolve_store(MERCHANT, db)
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let price = cloned.price.parse::<f32>().unwrap_or(0.0);
+        let store = merchant_store(db);
+
+        let stock = match &store {
+            Some(store) if !cloned.quantity.is_empty() => vec![Stock {
+                store: location(store),
+                quantity: Quantity {
+                    quantity_sellable: cloned.quantity.parse::<f32>().unwrap_or(0.0),
+                    quantity_unsellable: 0.0,
+                    quantity_on_order: 0.0,
+                    quantity_allocated: 0.0,
+                },
+            }],
+            _ => vec![],
+        };
+
+        let mut specifications = vec![];
+
+        if !cloned.modifier_groups.is_empty() {
+            specifications.push((MODIFIER_GROUPS.to_string(), cloned.modifier_groups.clone()));
+        }
+
+        if cloned.price_type == "Variable" {
+            specifications.push(("Price Type".to_string(), "Variable".to_string()));
+        }
+
+        if cloned.price_type == "Per Unit" && !cloned.price_unit.is_empty() {
+            specifications.push(("Price Unit".to_string(), cloned.price_unit.clone()));
+        }
+
+        let categories = split_list(&cloned.categories);
+        let sku = if cloned.sku.is_empty() {
+            cloned.clover_id.clone()
+        } else {
+            cloned.sku.clone()
+        };
+
+        Ok(Product {
+            name: cloned.name.clone(),
+            company: String::new(),
+            variant_groups: vec![],
+            variants: vec![VariantInformation {
+                name: cloned.name.clone(),
+                stock,
+                images: vec![],
+                retail_price: price,
+                marginal_price: cloned.cost.parse::<f32>().unwrap_or(price),
+                loyalty_discount: DiscountValue::Absolute(0),
+                variant_code: vec![sku.clone()],
+                order_history: vec![],
+                stock_information: StockInformation {
+                    stock_group: String::new(),
+                    sales_group: categories.first().cloned().unwrap_or_default(),
+                    value_stream: String::new(),
+                    brand: String::new(),
+                    tax_code: cloned.tax_rates.clone(),
+                    weight: "0".to_string(),
+                    volume: "0.00".to_string(),
+                    max_volume: "0.00".to_string(),
+                    back_order: false,
+                    discontinued: false,
+                    // Non-revenue items, such as bag charges,
+                    // are not drawn from inventory.
+                    non_diminishing: parse_flag(&cloned.non_revenue),
+                    shippable: true,
+                    size_override_unit: String::new(),
+                    size_x_unit: String::new(),
+                    size_y_unit: String::new(),
+                    size_z_unit: String::new(),
+                    size_x: 0.0,
+                    size_y: 0.0,
+                    size_z: 0.0,
+                    min_stock_before_alert: 0.0,
+                    min_stock_level: 0.0,
+                    colli: String::new(),
+                },
+                barcode: cloned.product_code.clone(),
+                id: Uuid::new_v4().to_string(),
+                buy_max: -1.0,
+                // Items priced per unit may be sold in part.
+                buy_min: 0.0,
+                identification: identify(&sku, &cloned.product_code),
+                stock_tracking: !cloned.quantity.is_empty(),
+            }],
+            sku: cloned.clover_id.clone(),
+            images: vec![],
+            tags: categories,
+            description: String::new(),
+            specifications,
+            name_long: if cloned.alternate_name.is_empty() {
+                cloned.name.clone()
+            } else {
+                cloned.alternate_name.clone()
+            },
+            identification: ProductIdentification {
+                sku: cloned.clover_id.clone(),
+                ..Default::default()
+            },
+            description_long: String::new(),
+            visible: if parse_flag(&cloned.hidden) {
+                ProductVisibility::AlwaysHidden
+            } else {
+                ProductVisibility::ShowWhenInStock
+            },
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+/// Parses the `Modifier Groups` sheet, expanding each product which names
+/// a group into a variant for every combination of its groups' modifiers.
+/// Each modifier adds its price to that of the item.
+///
+/// No products are created, so the returned list is always empty.
+pub fn parse_modifier_groups(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let mut groups: Vec<(String, Vec<(String, f32)>)> = vec![];
+
+    for record in reader.deserialize::<ModifierRecord>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        let modifier = (record.name, record.price.parse::<f32>().unwrap_or(0.0));
+
+        match groups.iter_mut().find(|(name, _)| *name == record.group) {
+            Some((_, modifiers)) => modifiers.push(modifier),
+            None => groups.push((record.group, vec![modifier])),
+        }
+    }
+
+    for product in db.0.iter_mut() {
+        let names = match product
+            .specifications
+            .iter()
+            .position(|(name, _)| name == MODIFIER_GROUPS)
+        {
+            Some(index) => split_list(&product.specifications.remove(index).1),
+            None => continue,
+        };
+
+        let base = match product.variants.first() {
+            Some(variant) => variant.clone(),
+            None => continue,
+        };
+
+        // Every combination of one modifier from each group, with its price.
+        let mut combinations: Vec<(Vec<String>, f32)> = vec![(vec![], 0.0)];
+
+        for name in &names {
+            let modifiers = match groups.iter().find(|(group, _)| group == name) {
+                Some((_, modifiers)) => modifiers,
+                None => {
+                    println!(
+                        "[warn]: Modifier group {} of {} is not in the export.",
+                        name, product.name
+                    );
+                    continue;
+                }
+            };
+
+            for (modifier, _) in modifiers {
+                add_variant_option(product, name, modifier);
+            }
+
+            combinations = combinations
+                .iter()
+                .flat_map(|(chosen, price)| {
+                    modifiers.iter().map(move |(modifier, adjustment)| {
+                        let mut chosen = chosen.clone();
+                        chosen.push(modifier.clone());
+                        (chosen, price + adjustment)
+                    })
+                })
+                .collect();
+        }
+
+        if combinations.len() > MAX_VARIANTS {
+            println!(
+                "[warn]: {} has {} modifier combinations, only its variant groups are kept.",
+                product.name,
+                combinations.len()
+            );
+            continue;
+        }
+
+        if combinations.iter().all(|(chosen, _)| chosen.is_empty()) {
+            continue;
+        }
+
+        // The item's stock cannot be divided between its
+        // variants, so it is all held by the first of them.
+        product.variants = combinations
+            .into_iter()
+            .enumerate()
+            .map(|(index, (chosen, adjustment))| {
+                let mut variant = base.clone();
+
+                variant.id = Uuid::new_v4().to_string();
+                variant.name = chosen.join(" ");
+                variant.retail_price = base.retail_price + adjustment;
+                variant.variant_code = vec![format!(
+                    "{}-{}",
+                    base.variant_code.first().cloned().unwrap_or_default(),
+                    chosen.join("-")
+                )];
+
+                if index > 0 {
+                    for stock in variant.stock.iter_mut() {
+                        stock.quantity.quantity_sellable = 0.0;
+                    }
+                }
+
+                variant
+            })
+            .collect();
+    }
+
+    Ok(vec![])
+}
+
+/// Parses the `Categories` sheet, which names a category in its first
+/// column followed by the items within it. Each item is tagged with the
+/// categories it belongs to.
+///
+/// No products are created, so the returned list is always empty.
+pub fn parse_categories(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        let mut cells = record.iter();
+        let category = match cells.next().map(str::trim) {
+            Some(category) if !category.is_empty() => category.to_string(),
+            _ => continue,
+        };
+
+        for item in cells.flat_map(split_list) {
+            let mut found = false;
+
+            for product in db.0.iter_mut().filter(|product| product.name == item) {
+                found = true;
+                merge_tags(&mut product.tags, [category.clone()], lowercase_tags());
+
+                for variant in product.variants.iter_mut() {
+                    if variant.stock_information.sales_group.is_empty() {
+                        variant.stock_information.sales_group = category.clone();
+                    }
+                }
+            }
+
+            if !found {
+                println!(
+                    "[warn]: Item {} of category {} is not in the export.",
+                    item, category
+                );
+            }
+        }
+    }
+
+    Ok(vec![])
+}
+
+impl Parsable<CustomerRecord> for Customer {
+    fn parse_individual(
+        reader: &[Result<CustomerRecord, csv::Error>],
+        line: &mut usize,
+        _db: &mut InlineDatabase,
+    ) -> Result<Customer, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let name = format!("{} {}", cloned.first_name, cloned.last_name)
+            .trim()
+            .to_string();
+
+        let mut customer_notes = vec![import_note(format!(
+            "Clover customer ID: {}",
+            cloned.customer_id
+        ))];
+
+        if !cloned.note.is_empty() {
+            customer_notes.push(import_note(cloned.note.clone()));
+        }
+
+        Ok(Customer {
+            id: Uuid::new_v4().to_string(),
+            name: name.clone(),
+            contact: ContactInformation {
+                name,
+                mobile: MobileNumber::from(cloned.phone.clone()),
+                email: Email::from(cloned.email.clone()),
+                landline: String::new(),
+                address: Address {
+                    street: cloned.address_street.clone(),
+                    street2: cloned.address_street2.clone(),
+                    city: match (
+                        cloned.address_city.is_empty(),
+                        cloned.address_state.is_empty(),
+                    ) {
+                        (_, true) => cloned.address_city.clone(),
+                        (true, false) => cloned.address_state.clone(),
+                        (false, false) => {
+                            format!("{}, {}", cloned.address_city, cloned.address_state)
+                        }
+                    },
+                    country: cloned.address_country.clone(),
+                    po_code: cloned.address_postcode.clone(),
+                    lat: 0.0,
+                    lon: 0.0,
+                },
+            },
+            customer_notes,
+            balance: 0,
+            special_pricing: String::new(),
+            accepts_marketing: parse_flag(&cloned.marketing_allowed),
+            created_at: parse_timestamp(&cloned.customer_since).unwrap_or(Utc::now()),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+impl Parsable<TransactionRecord> for Transaction {
+    fn parse_individual(
+        reader: &[Result<TransactionRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Transaction, ParseFailure> {
+        let order = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        let items: Vec<&TransactionRecord> = reader[*line..]
+            .iter()
+            .map_while(|row| row.as_ref().ok())
+            .take_while(|row| row.order_id == order.order_id)
+            .collect();
+
+        *line += items.len();
+
+        let date = parse_timestamp(&order.order_date).unwrap_or(Utc::now());
+        let store = merchant_store(db);
+        let kiosk = store
+            .as_ref()
+            .and_then(|store| resolve_kiosk(&order.device, store, db));
+        let employee = resolve_employee(&order.employee, db);
+        let customer =
+            db.1.iter()
+                .find(|customer| {
+                    !order.customer_name.is_empty() && customer.name == order.customer_name
+                })
+                .cloned();
+
+        let origin = match &store {
+            Some(store) => location(store),
+            None => Location {
+                contact: named_contact(&order.device),
+                store_code: "000".to_string(),
+                store_id: "000".to_string(),
+            },
+        };
+
+        let products = items
+            .iter()
+            .map(|item| {
+                let quantity = item.item_quantity.parse::<f32>().unwrap_or(1.0);
+                let refunded = parse_flag(&item.refunded);
+
+                ProductPurchase {
+                    id: Uuid::new_v4().to_string(),
+                    product_code: item.item_sku.clone(),
+                    product_name: item.item_name.clone(),
+                    product_sku: item.item_sku.clone(),
+                    product_cost: item.item_price.parse::<f32>().unwrap_or(0.0),
+                    discount: DiscountValue::Absolute(
+                        item.item_discount
+                            .parse::<f32>()
+                            .unwrap_or(0.0)
+                            .abs()
+                            .round() as u32,
+                    ),
+                    product_variant_name: if item.modifiers.is_empty() {
+                        item.item_name.clone()
+                    } else {
+                        format!("{} ({})", item.item_name, item.modifiers)
+                    },
+                    quantity,
+                    tags: vec![],
+                    // Refunded items are returned to the store.
+                    transaction_type: if refunded {
+                        TransactionType::In
+                    } else {
+                        TransactionType::Out
+                    },
+                    instances: (0..quantity.ceil() as u32)
+                        .map(|_| ProductInstance {
+                            id: Uuid::new_v4().to_string(),
+                            fulfillment_status: FulfillmentStatus {
+                                pick_status: PickStatus::Picked,
+                                pick_history: vec![],
+                                last_updated: date,
+                                notes: vec![],
+                            },
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let total = order.total.parse::<f32>().unwrap_or(0.0);
+        let paid = order.payment_state.eq_ignore_ascii_case("paid");
+
+        // Unpaid (open) orders carry no payment.
+        let tenders = if !paid {
+            vec![]
+        } else {
+            match apportion_payment(
+                &split_payment_methods(&order.tender, &PAYMENT_METHODS),
+                total,
+            ) {
+                Some(tenders) if !tenders.is_empty() => tenders,
+                _ => vec![(PaymentMethod::Other(order.tender.clone()), total)],
+            }
+        };
+
+        let payment = tenders
+            .into_iter()
+            .map(|(payment_method, amount)| Payment {
+                id: Uuid::new_v4().to_string(),
+                payment_method,
+                fulfillment_date: date,
+                amount: Price {
+                    quantity: amount,
+                    currency: order.currency.clone(),
+                },
+                processing_fee: Price {
+                    quantity: 0.0,
+                    currency: order.currency.clone(),
+                },
+                status: PaymentStatus::Complete(Processable::Anonymous(String::from("clover"))),
+                processor: PaymentProcessor::anonymous(String::from("clover")),
+                order_ids: vec![],
+                delay_action: PaymentAction::Complete,
+                delay_duration: String::new(),
+            })
+            .collect();
+
+        let order_notes: Vec<Note> = if order.note.is_empty() {
+            vec![]
+        } else {
+            vec![import_note(order.note.clone())]
+        };
+
+        Ok(Transaction {
+            id: Uuid::new_v4().to_string(),
+            customer: TransactionCustomer {
+                customer_type: CustomerType::Individual,
+                customer_id: customer
+                    .as_ref()
+                    .map_or(String::new(), |customer| customer.id.clone()),
+            },
+            kiosk: kiosk.map_or(String::new(), |kiosk| kiosk.id),
+            transaction_type: TransactionType::Out,
+            products: vec![Order {
+                id: Uuid::new_v4().to_string(),
+                destination: Location {
+                    contact: customer
+                        .as_ref()
+                        .map_or(origin.contact.clone(), |customer| customer.contact.clone()),
+                    store_code: origin.store_code.clone(),
+                    store_id: origin.store_id.clone(),
+                },
+                origin,
+                products,
+                status: OrderStatusAssignment {
+                    status: if paid {
+                        OrderStatus::Fulfilled(date)
+                    } else {
+                        OrderStatus::Queued(date)
+                    },
+                    assigned_products: vec![],
+                    timestamp: date,
+                },
+                status_history: vec![],
+                order_history: vec![],
+                previous_failed_fulfillment_attempts: vec![],
+                order_notes: order_notes.clone(),
+                reference: order.order_id.clone(),
+                creation_date: date,
+                discount: DiscountValue::Absolute(0),
+                order_type: OrderType::Direct,
+            }],
+            order_total: total.round() as i64,
+            payment,
+            order_date: date,
+            order_notes,
+            salesperson: employee.map_or(String::new(), |employee| employee.id),
+            created_at: date,
+            updated_at: Utc::now(),
+        })
+    }
+}
@@ -0,0 +1,356 @@
+//! eBay File Exchange listings.
+//!
+//! Listings with variations are followed by a row for each variation, whose
+//! `Relationship` is `Variation`. The listing names its options and their
+//! values in its `RelationshipDetails`, as `Color=Red;Blue|Size=S;M`, and each
+//! variation its own values, as `Color=Red|Size=M`.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{add_variant_option, identify_listing, resolve_store},
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::Reader;
+use open_stock::{
+    DiscountValue, Location, Product, ProductVisibility, Quantity, Stock, StockInformation, Store,
+    VariantInformation,
+};
+use phf::{phf_map, Map};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "*Action(SiteID=US|Country=US|Currency=USD|Version=1193),ItemID,*Title,CustomLabel,*Category,*StartPrice,*Quantity,*ConditionID,*Format,*Duration,PicURL,*Description,Product:UPC,Product:EAN,Relationship,RelationshipDetails",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "ZZZZ",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "ZZZZ",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The conditions named by the `*ConditionID` column.
+static CONDITIONS: Map<&'static str, &'static str> = phf_map! {
+    "1000" => "New",
+    "1500" => "New other",
+    "1750" => "New with defects",
+    "2000" => "Certified refurbished",
+    "2500" => "Seller refurbished",
+    "3000" => "Used",
+    "7000" => "For parts or not working",
+};
+
+/// The store standing for the seller, which stocks all of its listings.
+const SELLER: &str = "eBay";
+
+/// Separates the options (and the values of each option) of `RelationshipDetails`.
+const OPTION_SEPARATOR: char = '|';
+const VALUE_SEPARATOR: char = ';';
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "ItemID")]
+    item_id: String,
+
+    #[serde(rename = "*Title")]
+    title: String,
+
+    #[serde(rename = "CustomLabel")]
+    custom_label: String,
+
+    #[serde(rename = "*Category")]
+    category: String,
+
+    #[serde(rename = "*StartPrice")]
+    start_price: String,
+
+    #[serde(rename = "*Quantity")]
+    quantity: String,
+
+    #[serde(rename = "*ConditionID")]
+    condition_id: String,
+
+    /// `FixedPrice` or `Auction`.
+    #[serde(rename = "*Format")]
+    format: String,
+
+    /// The listing's pictures, separated by `|`.
+    #[serde(rename = "PicURL")]
+    pictures: String,
+
+    #[serde(rename = "*Description")]
+    description: String,
+
+    #[serde(rename = "Product:UPC")]
+    upc: String,
+
+    #[serde(rename = "Product:EAN")]
+    ean: String,
+
+    #[serde(rename = "Relationship")]
+    relationship: String,
+
+    #[serde(rename = "RelationshipDetails")]
+    relationship_details: String,
+}
+
+impl ProductRecord {
+    fn is_variation(&self) -> bool {
+        self.relationship.eq_ignore_ascii_case("Variation")
+    }
+
+    /// eBay exports `Does not apply` for listings without a barcode.
+    fn barcode(&self) -> &str {
+        [&self.upc, &self.ean]
+            .into_iter()
+            .find(|code| !code.is_empty() && !code.eq_ignore_ascii_case("Does not apply"))
+            .map_or("", |code| code.as_str())
+    }
+}
+
+/// Reads the options of `RelationshipDetails`, such as `Color=Red;Blue|Size=S;M`.
+fn parse_details(value: &str) -> Vec<(String, Vec<String>)> {
+    value
+        .split(OPTION_SEPARATOR)
+        .filter_map(|option| option.split_once('='))
+        .map(|(option, values)| {
+            (
+                option.trim().to_string(),
+                values
+                    .split(VALUE_SEPARATOR)
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+/// Listings are stocked at the seller's store.
+fn seller_store(db: &mut InlineDatabase) -> Option<Store> {
+    resolve_store(SELLER, db)
+}
+
+fn build_variant(
+    record: &ProductRecord,
+    listing: &ProductRecord,
+    name: String,
+    store: &Option<Store>,
+) -> VariantInformation {
+    let price = record.start_price.parse::<f32>().unwrap_or(0.0);
+
+    VariantInformation {
+        name,
+        stock: match store {
+            Some(store) => vec![Stock {
+                store: Location {
+                    store_code: store.code.clone(),
+                    store_id: store.id.clone(),
+                    contact: store.contact.clone(),
+                },
+                quantity: Quantity {
+                    quantity_sellable: record.quantity.parse::<f32>().unwrap_or(0.0),
+                    quantity_unsellable: 0.0,
+                    quantity_on_order: 0.0,
+                    quantity_allocated: 0.0,
+                },
+            }],
+            None => vec![],
+        },
+        images: vec![],
+        retail_price: price,
+        marginal_price: price,
+        loyalty_discount: DiscountValue::Absolute(0),
+        variant_code: if record.custom_label.is_empty() {
+            vec![]
+        } else {
+            vec![record.custom_label.clone()]
+        },
+        order_history: vec![],
+        stock_information: StockInformation {
+            stock_group: String::new(),
+            sales_group: listing.category.clone(),
+            value_stream: String::new(),
+            brand: String::new(),
+            tax_code: String::new(),
+            weight: "0".to_string(),
+            volume: "0.00".to_string(),
+            max_volume: "0.00".to_string(),
+            back_order: false,
+            discontinued: false,
+            non_diminishing: false,
+            shippable: true,
+            size_override_unit: String::new(),
+            size_x_unit: String::new(),
+            size_y_unit: String::new(),
+            size_z_unit: String::new(),
+            size_x: 0.0,
+            size_y: 0.0,
+            size_z: 0.0,
+            min_stock_before_alert: 0.0,
+            min_stock_level: 0.0,
+            colli: String::new(),
+        },
+        barcode: record.barcode().to_string(),
+        id: Uuid::new_v4().to_string(),
+        buy_max: -1.0,
+        buy_min: 1.0,
+        identification: identify_listing(&record.custom_label, record.barcode(), &listing.item_id),
+        stock_tracking: true,
+    }
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let first = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        if first.is_variation() {
+            return Err(ParseFailure::ReadFailure(format!(
+                "Variation {} does not follow a listing.",
+                first.custom_label
+            )));
+        }
+
+        let variations: Vec<&ProductRecord> = reader[*line..]
+            .iter()
+            .map_while(|row| row.as_ref().ok())
+            .take_while(|row| row.is_variation())
+            .collect();
+
+        *line += variations.len();
+
+        let store = seller_store(db);
+
+        let mut product = Product {
+            name: first.title.clone(),
+            company: String::new(),
+            variant_groups: vec![],
+            variants: vec![],
+            sku: first.custom_label.clone(),
+            images: first
+                .pictures
+                .split('|')
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty())
+                .collect(),
+            tags: vec![],
+            description: first.description.clone(),
+            specifications: vec![],
+            name_long: first.title.clone(),
+            identification: identify_listing(&first.custom_label, first.barcode(), &first.item_id),
+            description_long: first.description.clone(),
+            visible: ProductVisibility::ShowWhenInStock,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        if let Some(condition) = CONDITIONS.get(first.condition_id.trim()) {
+            product
+                .specifications
+                .push(("Condition".to_string(), condition.to_string()));
+        }
+
+        if !first.format.is_empty() && first.format != "FixedPrice" {
+            product
+                .specifications
+                .push(("Listing Format".to_string(), first.format.clone()));
+        }
+
+        // The listing names its options in the order they are offered.
+        for (option, values) in parse_details(&first.relationship_details) {
+            for value in values {
+                add_variant_option(&mut product, &option, &value);
+            }
+        }
+
+        if variations.is_empty() {
+            product
+                .variants
+                .push(build_variant(first, first, first.title.clone(), &store));
+
+            return Ok(product);
+        }
+
+        for variation in variations {
+            let values: Vec<(String, String)> = parse_details(&variation.relationship_details)
+                .into_iter()
+                .filter_map(|(option, values)| {
+                    values.into_iter().next().map(|value| (option, value))
+                })
+                .collect();
+
+            for (option, value) in &values {
+                add_variant_option(&mut product, option, value);
+            }
+
+            let name = values
+                .iter()
+                .map(|(_, value)| value.as_str())
+                .collect::<Vec<&str>>()
+                .join(" ");
+
+            product
+                .variants
+                .push(build_variant(variation, first, name, &store));
+        }
+
+        Ok(product)
+    }
+}
@@ -0,0 +1,896 @@
+//! Ecwid exports.
+//!
+//! The catalog is exported as a single file, whose `type` column marks each
+//! row as a `product`, one of its `product_option` values or one of its
+//! `product_variation`s. Rows of a product share its `product_sku`, and are
+//! exported together. Variations name their options in a column for each
+//! option, such as `product_variation_option_Size`.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_variant_option, apportion_payment, identify, lowercase_tags, named_contact, parse_flag,
+        parse_tags, resolve_store, split_payment_methods, weight_in_kilograms, weight_unit,
+        PaymentKind, PaymentMethodTable,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::{Reader, StringRecord};
+use open_stock::{
+    Address, ContactInformation, Customer, CustomerType, DiscountValue, Email, FulfillmentStatus,
+    Location, MobileNumber, Note, Order, OrderStatus, OrderStatusAssignment, OrderType, Payment,
+    PaymentAction, PaymentMethod, PaymentProcessor, PaymentStatus, PickStatus, Price, Processable,
+    Product, ProductIdentification, ProductInstance, ProductPurchase, ProductVisibility, Quantity,
+    Stock, StockInformation, Store, Transaction, TransactionCustomer, TransactionType,
+    TransitInformation, VariantInformation,
+};
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "type,product_internal_id,product_sku,product_name,product_price,product_compare_to_price,product_is_inventory_tracked,product_quantity,product_is_available,product_description,product_category_1,product_media_main_image_url,product_weight,product_brand,product_upc,product_option_name,product_option_type,product_option_is_required,product_option_value,product_option_markup,product_variation_sku,product_variation_option_Size",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "customer_internal_id,customer_email,customer_name,customer_phone,customer_group,customer_accepts_marketing,customer_billing_address_company,customer_billing_address_line_1,customer_billing_address_line_2,customer_billing_address_city,customer_billing_address_state,customer_billing_address_postal_code,customer_billing_address_country,customer_registration_date",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "order_number,order_date,order_status,payment_status,payment_method,order_total,order_currency,customer_email,customer_name,item_sku,item_name,item_quantity,item_price,item_options,shipping_method,shipping_address_line_1,shipping_city,shipping_postal_code,shipping_country",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The unit weights are exported in unless set otherwise with
+/// `ConversionOptions::set_weight_unit`,
+/// being the unit Ecwid stores are created with.
+pub const DEFAULT_WEIGHT_UNIT: &str = "kg";
+
+/// The store standing for the Ecwid store, which holds all of its stock.
+const STORE: &str = "Ecwid";
+
+/// The most variants built from the options of a product without variations.
+const MAX_VARIANTS: usize = 250;
+
+/// The prefix of the columns naming a variation's options.
+const VARIATION_OPTION_PREFIX: &str = "product_variation_option_";
+
+pub static PAYMENT_METHODS: PaymentMethodTable = phf_map! {
+    "credit card" => PaymentKind::Card,
+    "card" => PaymentKind::Card,
+    "cash" => PaymentKind::Cash,
+    "cash on delivery" => PaymentKind::Cash,
+    "pay in store" => PaymentKind::Cash,
+    "bank transfer" => PaymentKind::Transfer,
+    "gift card" => PaymentKind::GiftCard,
+};
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "ECWID-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Reads the timestamps of exports, such as `2023-05-01 10:00:00 +0000`.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+
+    DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S %z")
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|timestamp| timestamp.and_utc())
+        })
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|timestamp| timestamp.and_utc())
+        })
+}
+
+/// Applies an option's markup to a price. Markups are either
+/// an absolute amount, such as `5`, or a percentage, such as `10%`.
+fn apply_markup(price: f32, markup: &str) -> f32 {
+    let markup = markup.trim();
+
+    match markup.strip_suffix('%') {
+        Some(percentage) => {
+            let scale = 1.0 + percentage.trim().parse::<f32>().unwrap_or(0.0) / 100.0;
+
+            (price * scale * 100.0).round() / 100.0
+        }
+        None => price + markup.parse::<f32>().unwrap_or(0.0),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    /// `product`, `product_option` or `product_variation`.
+    #[serde(rename = "type")]
+    row_type: String,
+
+    #[serde(rename = "product_internal_id")]
+    internal_id: String,
+
+    #[serde(rename = "product_sku")]
+    sku: String,
+
+    #[serde(rename = "product_name")]
+    name: String,
+
+    #[serde(rename = "product_price")]
+    price: String,
+
+    #[serde(rename = "product_compare_to_price")]
+    compare_to_price: String,
+
+    #[serde(rename = "product_is_inventory_tracked")]
+    inventory_tracked: String,
+
+    #[serde(rename = "product_quantity")]
+    quantity: String,
+
+    #[serde(rename = "product_is_available")]
+    available: String,
+
+    #[serde(rename = "product_description")]
+    description: String,
+
+    #[serde(rename = "product_category_1")]
+    category: String,
+
+    #[serde(rename = "product_media_main_image_url")]
+    image: String,
+
+    #[serde(rename = "product_weight")]
+    weight: String,
+
+    #[serde(rename = "product_brand")]
+    brand: String,
+
+    #[serde(rename = "product_upc")]
+    upc: String,
+
+    #[serde(rename = "product_option_name")]
+    option_name: String,
+
+    #[serde(rename = "product_option_value")]
+    option_value: String,
+
+    #[serde(rename = "product_option_markup")]
+    option_markup: String,
+
+    #[serde(rename = "product_variation_sku")]
+    variation_sku: String,
+
+    /// Read from the columns of each option, as `(option, value)`.
+    #[serde(skip)]
+    variation_options: Vec<(String, String)>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerRecord {
+    #[serde(rename = "customer_internal_id")]
+    internal_id: String,
+
+    #[serde(rename = "customer_email")]
+    email: String,
+
+    #[serde(rename = "customer_name")]
+    name: String,
+
+    #[serde(rename = "customer_phone")]
+    phone: String,
+
+    #[serde(rename = "customer_group")]
+    group: String,
+
+    #[serde(rename = "customer_accepts_marketing")]
+    accepts_marketing: String,
+
+    #[serde(rename = "customer_billing_address_company")]
+    company: String,
+
+    #[serde(rename = "customer_billing_address_line_1")]
+    address1: String,
+
+    #[serde(rename = "customer_billing_address_line_2")]
+    address2: String,
+
+    #[serde(rename = "customer_billing_address_city")]
+    city: String,
+
+    #[serde(rename = "customer_billing_address_state")]
+    state: String,
+
+    #[serde(rename = "customer_billing_address_postal_code")]
+    postal_code: String,
+
+    #[serde(rename = "customer_billing_address_country")]
+    country: String,
+
+    #[serde(rename = "customer_registration_date")]
+    registration_date: String,
+}
+
+/// A line item of an order, whose other columns are repeated on each item.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionRecord {
+    #[serde(rename = "order_number")]
+    order_number: String,
+
+    #[serde(rename = "order_date")]
+    order_date: String,
+
+    /// The fulfillment status, such as `AWAITING_PROCESSING` or `SHIPPED`.
+    #[serde(rename = "order_status")]
+    order_status: String,
+
+    /// Such as `PAID`, `AWAITING_PAYMENT` or `REFUNDED`.
+    #[serde(rename = "payment_status")]
+    payment_status: String,
+
+    #[serde(rename = "payment_method")]
+    payment_method: String,
+
+    #[serde(rename = "order_total")]
+    order_total: String,
+
+    #[serde(rename = "order_currency")]
+    currency: String,
+
+    #[serde(rename = "customer_email")]
+    customer_email: String,
+
+    #[serde(rename = "customer_name")]
+    customer_name: String,
+
+    #[serde(rename = "item_sku")]
+    item_sku: String,
+
+    #[serde(rename = "item_name")]
+    item_name: String,
+
+    #[serde(rename = "item_quantity")]
+    item_quantity: String,
+
+    #[serde(rename = "item_price")]
+    item_price: String,
+
+    /// The chosen options, such as `Size: M, Color: Red`.
+    #[serde(rename = "item_options")]
+    item_options: String,
+
+    #[serde(rename = "shipping_method")]
+    shipping_method: String,
+
+    #[serde(rename = "shipping_address_line_1")]
+    shipping_address: String,
+
+    #[serde(rename = "shipping_city")]
+    shipping_city: String,
+
+    #[serde(rename = "shipping_postal_code")]
+    shipping_postal_code: String,
+
+    #[serde(rename = "shipping_country")]
+    shipping_country: String,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+/// Parses a catalog export, whose variations carry
+/// a column for each of the product's options.
+pub fn parse_products(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(error) => return Err(ParseFailure::ReadFailure(error.to_string())),
+    };
+
+    let collected: Vec<Result<ProductRecord, csv::Error>> = reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let mut product: ProductRecord = record.deserialize(Some(&headers))?;
+            product.variation_options = read_variation_options(&headers, &record);
+            Ok(product)
+        })
+        .collect();
+
+    parse_collected(collected, db)
+}
+
+fn read_variation_options(headers: &StringRecord, record: &StringRecord) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .zip(record.iter())
+        .filter_map(|(header, value)| {
+            header
+                .strip_prefix(VARIATION_OPTION_PREFIX)
+                .filter(|_| !value.trim().is_empty())
+                .map(|option| (option.trim().to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+fn location(store: &Store) -> Location {
+    Location {
+        store_code: store.code.clone(),
+        store_id: store.id.clone(),
+        contact: store.contact.clone(),
+    }
+}
+
+/// Stock is exported for the store as a whole.
+fn shop_store(db: &mut InlineDatabase) -> Option<Store> {
+    resolve_store(STORE, db)
+}
+
+/// An option of a product, with the markup of each of its values.
+struct ProductOption {
+    name: String,
+    values: Vec<(String, String)>,
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let first = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        let rows: Vec<&ProductRecord> = reader[*line..]
+            .iter()
+            .map_while(|row| row.as_ref().ok())
+            .take_while(|row| row.sku == first.sku)
+            .collect();
+
+        *line += rows.len();
+
+        if first.row_type != "product" {
+            return Err(ParseFailure::ReadFailure(format!(
+                "The {} row of {} precedes its product.",
+                first.row_type, first.sku
+            )));
+        }
+
+        let price = first.price.parse::<f32>().unwrap_or(0.0);
+        let available = first.available.is_empty() || parse_flag(&first.available);
+        let store = shop_store(db);
+
+        let mut product = Product {
+            name: first.name.clone(),
+            company: first.brand.clone(),
+            variant_groups: vec![],
+            variants: vec![],
+            sku: first.sku.clone(),
+            images: if first.image.is_empty() {
+                vec![]
+            } else {
+                vec![first.image.clone()]
+            },
+            tags: parse_tags(&first.category, '/', lowercase_tags()),
+            description: first.description.clone(),
+            specifications: vec![],
+            name_long: first.name.clone(),
+            identification: ProductIdentification {
+                sku: first.sku.clone(),
+                ..Default::default()
+            },
+            description_long: first.description.clone(),
+            visible: if available {
+                ProductVisibility::ShowWhenInStock
+            } else {
+                ProductVisibility::AlwaysHidden
+            },
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        if let Ok(compare_to_price) = first.compare_to_price.parse::<f32>() {
+            product.specifications.push((
+                "Compare At Price".to_string(),
+                format!("{:.2}", compare_to_price),
+            ));
+        }
+
+        product
+            .specifications
+            .push(("Ecwid Product ID".to_string(), first.internal_id.clone()));
+
+        // Options list one row for each of their values.
+        let mut options: Vec<ProductOption> = vec![];
+
+        for row in rows.iter().filter(|row| row.row_type == "product_option") {
+            if row.option_name.is_empty() || row.option_value.is_empty() {
+                continue;
+            }
+
+            add_variant_option(&mut product, &row.option_name, &row.option_value);
+
+            let value = (row.option_value.clone(), row.option_markup.clone());
+
+            match options
+                .iter_mut()
+                .find(|option| option.name == row.option_name)
+            {
+                Some(option) => option.values.push(value),
+                None => options.push(ProductOption {
+                    name: row.option_name.clone(),
+                    values: vec![value],
+                }),
+            }
+        }
+
+        let variant = |name: String, sku: &str, barcode: &str, price: f32, quantity: &str| {
+            let stock_tracking = parse_flag(&first.inventory_tracked);
+
+            VariantInformation {
+                name,
+                stock: match (&store, stock_tracking) {
+                    (Some(store), true) => vec![Stock {
+                        store: location(store),
+                        quantity: Quantity {
+                            quantity_sellable: quantity.parse::<f32>().unwrap_or(0.0),
+                            quantity_unsellable: 0.0,
+                            quantity_on_order: 0.0,
+                            quantity_allocated: 0.0,
+                        },
+                    }],
+                    _ => vec![],
+                },
+                images: vec![],
+                retail_price: price,
+                marginal_price: price,
+                loyalty_discount: DiscountValue::Absolute(0),
+                variant_code: vec![sku.to_string()],
+                order_history: vec![],
+                stock_information: StockInformation {
+                    stock_group: String::new(),
+                    sales_group: first.category.clone(),
+                    value_stream: String::new(),
+                    brand: first.brand.clone(),
+                    tax_code: String::new(),
+                    weight: weight_in_kilograms(
+                        &first.weight,
+                        &weight_unit("ecwid", DEFAULT_WEIGHT_UNIT),
+                    )
+                    .unwrap_or(0.0)
+                    .to_string(),
+                    volume: "0.00".to_string(),
+                    max_volume: "0.00".to_string(),
+                    back_order: false,
+                    discontinued: !available,
+                    non_diminishing: false,
+                    shippable: true,
+                    size_override_unit: String::new(),
+                    size_x_unit: String::new(),
+                    size_y_unit: String::new(),
+                    size_z_unit: String::new(),
+                    size_x: 0.0,
+                    size_y: 0.0,
+                    size_z: 0.0,
+                    min_stock_before_alert: 0.0,
+                    min_stock_level: 0.0,
+                    colli: String::new(),
+                },
+                barcode: barcode.to_string(),
+                id: Uuid::new_v4().to_string(),
+                buy_max: -1.0,
+                buy_min: 1.0,
+                identification: identify(sku, barcode),
+                stock_tracking,
+            }
+        };
+
+        let variations: Vec<&&ProductRecord> = rows
+            .iter()
+            .filter(|row| row.row_type == "product_variation")
+            .collect();
+
+        if !variations.is_empty() {
+            for row in variations {
+                // Variations may name values of options not listed by the product.
+                for (option, value) in &row.variation_options {
+                    add_variant_option(&mut product, option, value);
+                }
+
+                let name = row
+                    .variation_options
+                    .iter()
+                    .map(|(_, value)| value.as_str())
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+
+                let sku = if row.variation_sku.is_empty() {
+                    &first.sku
+                } else {
+                    &row.variation_sku
+                };
+
+                product.variants.push(variant(
+                    name,
+                    sku,
+                    &row.upc,
+                    row.price.parse::<f32>().unwrap_or(price),
+                    &row.quantity,
+                ));
+            }
+        } else if !options.is_empty() {
+            // Without variations, each combination of options is sold at the
+            // product's price with the markups of its values, sharing its stock.
+            let mut combinations: Vec<(Vec<String>, f32)> = vec![(vec![], price)];
+
+            for option in &options {
+                combinations = combinations
+                    .iter()
+                    .flat_map(|(names, price)| {
+                        option.values.iter().map(move |(value, markup)| {
+                            let mut names = names.clone();
+                            names.push(value.clone());
+                            (names, apply_markup(*price, markup))
+                        })
+                    })
+                    .take(MAX_VARIANTS)
+                    .collect();
+            }
+
+            for (index, (names, price)) in combinations.into_iter().enumerate() {
+                product.variants.push(variant(
+                    names.join(" "),
+                    &first.sku,
+                    &first.upc,
+                    price,
+                    if index == 0 { &first.quantity } else { "0" },
+                ));
+            }
+        } else {
+            product.variants.push(variant(
+                first.name.clone(),
+                &first.sku,
+                &first.upc,
+                price,
+                &first.quantity,
+            ));
+        }
+
+        Ok(product)
+    }
+}
+
+impl Parsable<CustomerRecord> for Customer {
+    fn parse_individual(
+        reader: &[Result<CustomerRecord, csv::Error>],
+        line: &mut usize,
+        _db: &mut InlineDatabase,
+    ) -> Result<Customer, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let city = match (cloned.city.is_empty(), cloned.state.is_empty()) {
+            (_, true) => cloned.city.clone(),
+            (true, false) => cloned.state.clone(),
+            (false, false) => format!("{}, {}", cloned.city, cloned.state),
+        };
+
+        let mut customer_notes = vec![import_note(format!(
+            "Ecwid customer ID: {}",
+            cloned.internal_id
+        ))];
+
+        if !cloned.group.is_empty() {
+            customer_notes.push(import_note(format!("Customer group: {}", cloned.group)));
+        }
+
+        if !cloned.company.is_empty() {
+            customer_notes.push(import_note(format!("Company: {}", cloned.company)));
+        }
+
+        Ok(Customer {
+            id: Uuid::new_v4().to_string(),
+            name: cloned.name.clone(),
+            contact: ContactInformation {
+                name: cloned.name.clone(),
+                mobile: MobileNumber::from(cloned.phone.clone()),
+                email: Email::from(cloned.email.clone()),
+                landline: String::new(),
+                address: Address {
+                    street: cloned.address1.clone(),
+                    street2: cloned.address2.clone(),
+                    city,
+                    country: cloned.country.clone(),
+                    po_code: cloned.postal_code.clone(),
+                    lat: 0.0,
+                    lon: 0.0,
+                },
+            },
+            customer_notes,
+            balance: 0,
+            special_pricing: String::new(),
+            accepts_marketing: parse_flag(&cloned.accepts_marketing),
+            created_at: parse_timestamp(&cloned.registration_date).unwrap_or(Utc::now()),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+impl Parsable<TransactionRecord> for Transaction {
+    fn parse_individual(
+        reader: &[Result<TransactionRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Transaction, ParseFailure> {
+        let order = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        let items: Vec<&TransactionRecord> = reader[*line..]
+            .iter()
+            .map_while(|row| row.as_ref().ok())
+            .take_while(|row| row.order_number == order.order_number)
+            .collect();
+
+        *line += items.len();
+
+        let date = parse_timestamp(&order.order_date).unwrap_or(Utc::now());
+        let store = shop_store(db);
+        let customer =
+            db.1.iter()
+                .find(|customer| {
+                    !order.customer_email.is_empty()
+                        && customer
+                            .contact
+                            .email
+                            .full
+                            .eq_ignore_ascii_case(&order.customer_email)
+                })
+                .cloned();
+
+        let origin = match &store {
+            Some(store) => location(store),
+            None => Location {
+                contact: named_contact("Ecwid"),
+                store_code: "000".to_string(),
+                store_id: "000".to_string(),
+            },
+        };
+
+        let payment_status = order.payment_status.to_uppercase();
+        let paid = matches!(payment_status.as_str(), "PAID" | "PARTIALLY_REFUNDED");
+
+        let status = match (
+            order.order_status.to_uppercase().as_str(),
+            payment_status.as_str(),
+        ) {
+            (_, "CANCELLED" | "REFUNDED") | ("WILL_NOT_DELIVER" | "RETURNED", _) => {
+                OrderStatus::Failed(format!("{} ({})", order.order_status, order.payment_status))
+            }
+            ("DELIVERED", _) => OrderStatus::Fulfilled(date),
+            ("SHIPPED" | "OUT_FOR_DELIVERY", _) => {
+                OrderStatus::Transit(Box::new(TransitInformation {
+                    shipping_company: named_contact(&order.shipping_method),
+                    query_url: String::new(),
+                    tracking_code: String::new(),
+                    assigned_products: vec![],
+                }))
+            }
+            ("AWAITING_PROCESSING", _) if !paid => OrderStatus::Queued(date),
+            _ => OrderStatus::Processing(date),
+        };
+
+        let products = items
+            .iter()
+            .map(|item| {
+                let quantity = item.item_quantity.parse::<f32>().unwrap_or(1.0);
+
+                ProductPurchase {
+                    id: Uuid::new_v4().to_string(),
+                    product_code: item.item_sku.clone(),
+                    product_name: item.item_name.clone(),
+                    product_sku: item.item_sku.clone(),
+                    product_cost: item.item_price.parse::<f32>().unwrap_or(0.0),
+                    discount: DiscountValue::Absolute(0),
+                    product_variant_name: if item.item_options.is_empty() {
+                        item.item_name.clone()
+                    } else {
+                        format!("{} ({})", item.item_name, item.item_options)
+                    },
+                    quantity,
+                    tags: vec![],
+                    transaction_type: TransactionType::Out,
+                    instances: (0..quantity.ceil() as u32)
+                        .map(|_| ProductInstance {
+                            id: Uuid::new_v4().to_string(),
+                            fulfillment_status: FulfillmentStatus {
+                                pick_status: PickStatus::Pending,
+                                pick_history: vec![],
+                                last_updated: date,
+                                notes: vec![],
+                            },
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let total = order.order_total.parse::<f32>().unwrap_or(0.0);
+
+        // Unpaid orders carry no payment.
+        let tenders = if !paid {
+            vec![]
+        } else {
+            match apportion_payment(
+                &split_payment_methods(&order.payment_method, &PAYMENT_METHODS),
+                total,
+            ) {
+                Some(tenders) if !tenders.is_empty() => tenders,
+                _ => vec![(PaymentMethod::Other(order.payment_method.clone()), total)],
+            }
+        };
+
+        let payment = tenders
+            .into_iter()
+            .map(|(payment_method, amount)| Payment {
+                id: Uuid::new_v4().to_string(),
+                payment_method,
+                fulfillment_date: date,
+                amount: Price {
+                    quantity: amount,
+                    currency: order.currency.clone(),
+                },
+                processing_fee: Price {
+                    quantity: 0.0,
+                    currency: order.currency.clone(),
+                },
+                status: PaymentStatus::Complete(Processable::Anonymous(String::from("ecwid"))),
+                processor: PaymentProcessor::anonymous(String::from("ecwid")),
+                order_ids: vec![],
+                delay_action: PaymentAction::Complete,
+                delay_duration: String::new(),
+            })
+            .collect();
+
+        let shipped = !order.shipping_address.is_empty();
+
+        let mut destination = customer
+            .as_ref()
+            .map_or(origin.contact.clone(), |customer| customer.contact.clone());
+
+        if shipped {
+            destination.address = Address {
+                street: order.shipping_address.clone(),
+                street2: String::new(),
+                city: order.shipping_city.clone(),
+                country: order.shipping_country.clone(),
+                po_code: order.shipping_postal_code.clone(),
+                lat: 0.0,
+                lon: 0.0,
+            };
+        }
+
+        if customer.is_none() && !order.customer_name.is_empty() {
+            destination.name = order.customer_name.clone();
+            destination.email = Email::from(order.customer_email.clone());
+        }
+
+        let order_notes: Vec<Note> = if order.shipping_method.is_empty() {
+            vec![]
+        } else {
+            vec![import_note(format!("Shipping: {}", order.shipping_method))]
+        };
+
+        Ok(Transaction {
+            id: Uuid::new_v4().to_string(),
+            customer: TransactionCustomer {
+                customer_type: CustomerType::Individual,
+                customer_id: customer
+                    .as_ref()
+                    .map_or(String::new(), |customer| customer.id.clone()),
+            },
+            kiosk: String::new(),
+            transaction_type: TransactionType::Out,
+            products: vec![Order {
+                id: Uuid::new_v4().to_string(),
+                destination: Location {
+                    contact: destination,
+                    store_code: origin.store_code.clone(),
+                    store_id: origin.store_id.clone(),
+                },
+                origin,
+                products,
+                status: OrderStatusAssignment {
+                    status,
+                    assigned_products: vec![],
+                    timestamp: date,
+                },
+                status_history: vec![],
+                order_history: vec![],
+                previous_failed_fulfillment_attempts: vec![],
+                order_notes: order_notes.clone(),
+                reference: order.order_number.clone(),
+                creation_date: date,
+                discount: DiscountValue::Absolute(0),
+                order_type: if shipped {
+                    OrderType::Shipment
+                } else {
+                    OrderType::Pickup
+                },
+            }],
+            order_total: total.round() as i64,
+            payment,
+            order_date: date,
+            order_notes,
+            salesperson: String::new(),
+            created_at: date,
+            updated_at: Utc::now(),
+        })
+    }
+}
@@ -0,0 +1,771 @@
+//! Etsy shop exports.
+//!
+//! Sold orders and their items are exported as separate files, the orders
+//! are parsed into transactions first and the items are then added to them.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_variant_option, identify, lowercase_tags, merge_tags, named_contact, parse_tags,
+        resolve_store, split_payment_methods, PaymentKind, PaymentMethodTable,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::{Reader, StringRecord};
+use open_stock::{
+    Address, ContactInformation, CustomerType, DiscountValue, Email, FulfillmentStatus, Location,
+    MobileNumber, Note, Order, OrderStatus, OrderStatusAssignment, OrderType, Payment,
+    PaymentAction, PaymentMethod, PaymentProcessor, PaymentStatus, PickStatus, Price, Processable,
+    Product, ProductIdentification, ProductInstance, ProductPurchase, ProductVisibility, Quantity,
+    Stock, StockInformation, Store, Transaction, TransactionCustomer, TransactionType,
+    VariantInformation,
+};
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "TITLE,DESCRIPTION,PRICE,CURRENCY_CODE,QUANTITY,TAGS,MATERIALS,IMAGE1,IMAGE2,IMAGE3,IMAGE4,IMAGE5,IMAGE6,IMAGE7,IMAGE8,IMAGE9,IMAGE10,VARIATION 1 TYPE,VARIATION 1 NAME,VARIATION 1 VALUES,VARIATION 2 TYPE,VARIATION 2 NAME,VARIATION 2 VALUES,SKU",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "ZZZZ",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "Sale Date,Order ID,Buyer User ID,Full Name,First Name,Last Name,Number of Items,Payment Method,Date Shipped,Street 1,Street 2,Ship City,Ship State,Ship Zipcode,Ship Country,Currency,Order Value,Coupon Code,Coupon Details,Discount Amount,Shipping Discount,Shipping,Sales Tax,Order Total,Status,Card Processing Fees,Order Net,Adjusted Order Total,Adjusted Card Processing Fees,Adjusted Net Order Amount,Buyer,Order Type,Payment Type,InPerson Discount,InPerson Location,SKU",
+        ParseType::TransactionItem => "Sale Date,Item Name,Buyer,Quantity,Price,Coupon Code,Coupon Details,Discount Amount,Shipping Discount,Order Shipping,Order Sales Tax,Item Total,Currency,Transaction ID,Listing ID,Date Paid,Date Shipped,Ship Name,Ship Address1,Ship Address2,Ship City,Ship State,Ship Zipcode,Ship Country,Order ID,Variations,Order Type,Listings Type,Payment Type,InPerson Discount,InPerson Location,VAT Paid by Buyer,SKU",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The prefix of the numbered image columns of a listings export.
+const IMAGE_PREFIX: &str = "IMAGE";
+
+/// The number of variation column sets of a listings export.
+const VARIATIONS: usize = 2;
+
+/// The store standing for the shop, which holds all of its stock.
+const SHOP: &str = "Etsy";
+
+/// The specification the quantity of a listing with variations is kept in.
+pub const LISTING_QUANTITY: &str = "Listing Quantity";
+
+pub static PAYMENT_METHODS: PaymentMethodTable = phf_map! {
+    "cc" => PaymentKind::Card,
+    "online_cc" => PaymentKind::Card,
+    "credit card" => PaymentKind::Card,
+    "etsy payments" => PaymentKind::Card,
+    "cash" => PaymentKind::Cash,
+    "gift card" => PaymentKind::GiftCard,
+    "etsy gift card" => PaymentKind::GiftCard,
+};
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "ETSY-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Reads the dates of exports, which are given as `05/01/23` (month first).
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    ["%m/%d/%y", "%m/%d/%Y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value.trim(), format).ok())
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|timestamp| timestamp.and_utc())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "TITLE")]
+    title: String,
+
+    #[serde(rename = "DESCRIPTION")]
+    description: String,
+
+    #[serde(rename = "PRICE")]
+    price: String,
+
+    #[serde(rename = "QUANTITY")]
+    quantity: String,
+
+    #[serde(rename = "TAGS")]
+    tags: String,
+
+    #[serde(rename = "MATERIALS")]
+    materials: String,
+
+    #[serde(rename = "VARIATION 1 TYPE")]
+    variation_one_type: String,
+
+    #[serde(rename = "VARIATION 1 NAME")]
+    variation_one_name: String,
+
+    #[serde(rename = "VARIATION 1 VALUES")]
+    variation_one_values: String,
+
+    #[serde(rename = "VARIATION 2 TYPE")]
+    variation_two_type: String,
+
+    #[serde(rename = "VARIATION 2 NAME")]
+    variation_two_name: String,
+
+    #[serde(rename = "VARIATION 2 VALUES")]
+    variation_two_values: String,
+
+    /// The SKUs of the listing, one for each of its variants.
+    #[serde(rename = "SKU")]
+    sku: String,
+
+    /// Read from the `IMAGE1` to `IMAGE10` columns.
+    #[serde(skip)]
+    images: Vec<String>,
+}
+
+impl ProductRecord {
+    /// The variations of the listing, named by their custom
+    /// name when they have one, otherwise by their type.
+    fn variations(&self) -> Vec<(String, Vec<String>)> {
+        let variations: [(&str, &str, &str); VARIATIONS] = [
+            (
+                &self.variation_one_type,
+                &self.variation_one_name,
+                &self.variation_one_values,
+            ),
+            (
+                &self.variation_two_type,
+                &self.variation_two_name,
+                &self.variation_two_values,
+            ),
+        ];
+
+        variations
+            .into_iter()
+            .filter(|(_, _, values)| !values.trim().is_empty())
+            .map(|(kind, name, values)| {
+                let name = if name.trim().is_empty() { kind } else { name };
+
+                (
+                    name.trim().to_string(),
+                    values
+                        .split(',')
+                        .map(|value| value.trim().to_string())
+                        .filter(|value| !value.is_empty())
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionRecord {
+    #[serde(rename = "Sale Date")]
+    sale_date: String,
+
+    #[serde(rename = "Order ID")]
+    order_id: String,
+
+    #[serde(rename = "Full Name")]
+    full_name: String,
+
+    #[serde(rename = "Payment Method")]
+    payment_method: String,
+
+    #[serde(rename = "Date Shipped")]
+    date_shipped: String,
+
+    #[serde(rename = "Street 1")]
+    street: String,
+
+    #[serde(rename = "Street 2")]
+    street2: String,
+
+    #[serde(rename = "Ship City")]
+    city: String,
+
+    #[serde(rename = "Ship State")]
+    state: String,
+
+    #[serde(rename = "Ship Zipcode")]
+    zipcode: String,
+
+    #[serde(rename = "Ship Country")]
+    country: String,
+
+    #[serde(rename = "Currency")]
+    currency: String,
+
+    #[serde(rename = "Coupon Code")]
+    coupon_code: String,
+
+    #[serde(rename = "Discount Amount")]
+    discount_amount: String,
+
+    #[serde(rename = "Order Total")]
+    order_total: String,
+
+    #[serde(rename = "Card Processing Fees")]
+    processing_fees: String,
+
+    #[serde(rename = "Adjusted Order Total")]
+    adjusted_order_total: String,
+
+    #[serde(rename = "Payment Type")]
+    payment_type: String,
+
+    #[serde(rename = "InPerson Location")]
+    in_person_location: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionItemRecord {
+    #[serde(rename = "Item Name")]
+    item_name: String,
+
+    #[serde(rename = "Quantity")]
+    quantity: String,
+
+    #[serde(rename = "Price")]
+    price: String,
+
+    #[serde(rename = "Discount Amount")]
+    discount_amount: String,
+
+    #[serde(rename = "Date Paid")]
+    date_paid: String,
+
+    #[serde(rename = "Listing ID")]
+    listing_id: String,
+
+    #[serde(rename = "Order ID")]
+    order_id: String,
+
+    /// The chosen variations, such as `Size:Large,Finish:Oak`.
+    #[serde(rename = "Variations")]
+    variations: String,
+
+    #[serde(rename = "SKU")]
+    sku: String,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+/// Parses a listings export, which carries a
+/// numbered set of columns for each listing image.
+pub fn parse_products(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(error) => return Err(ParseFailure::ReadFailure(error.to_string())),
+    };
+
+    let collected: Vec<Result<ProductRecord, csv::Error>> = reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let mut product: ProductRecord = record.deserialize(Some(&headers))?;
+            product.images = read_images(&headers, &record);
+            Ok(product)
+        })
+        .collect();
+
+    parse_collected(collected, db)
+}
+
+fn read_images(headers: &StringRecord, record: &StringRecord) -> Vec<String> {
+    let mut images: Vec<(u32, String)> = headers
+        .iter()
+        .zip(record.iter())
+        .filter(|(_, value)| !value.trim().is_empty())
+        .filter_map(|(header, value)| {
+            header
+                .strip_prefix(IMAGE_PREFIX)
+                .and_then(|index| index.trim().parse::<u32>().ok())
+                .map(|index| (index, value.trim().to_string()))
+        })
+        .collect();
+
+    images.sort_by_key(|(index, _)| *index);
+    images.into_iter().map(|(_, image)| image).collect()
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+fn location(store: &Store) -> Location {
+    Location {
+        store_code: store.code.clone(),
+        store_id: store.id.clone(),
+        contact: store.contact.clone(),
+    }
+}
+
+/// Etsy holds the stock of a shop in one place.
+fn shop_store(db: &mut InlineDatabase) -> Option<Store> {
+    resolve_store(SHOP, db)
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let price = cloned.price.parse::<f32>().unwrap_or(0.0);
+        let quantity = cloned.quantity.parse::<f32>().unwrap_or(0.0);
+        let skus: Vec<String> = cloned
+            .sku
+            .split(',')
+            .map(|sku| sku.trim().to_string())
+            .filter(|sku| !sku.is_empty())
+            .collect();
+        let sku = skus.first().cloned().unwrap_or_default();
+        let store = shop_store(db);
+
+        let mut product = Product {
+            name: cloned.title.clone(),
+            company: String::new(),
+            variant_groups: vec![],
+            variants: vec![],
+            sku: sku.clone(),
+            images: cloned.images.clone(),
+            tags: vec![],
+            description: cloned.description.clone(),
+            specifications: vec![],
+            name_long: cloned.title.clone(),
+            identification: ProductIdentification {
+                sku: sku.clone(),
+                ..Default::default()
+            },
+            description_long: cloned.description.clone(),
+            visible: ProductVisibility::ShowWhenInStock,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        merge_tags(
+            &mut product.tags,
+            parse_tags(&cloned.tags, ',', lowercase_tags()),
+            lowercase_tags(),
+        );
+
+        if !cloned.materials.is_empty() {
+            product
+                .specifications
+                .push(("Materials".to_string(), cloned.materials.clone()));
+        }
+
+        // Every combination of one value from each variation.
+        let mut combinations: Vec<Vec<String>> = vec![vec![]];
+
+        for (name, values) in cloned.variations() {
+            for value in &values {
+                add_variant_option(&mut product, &name, value);
+            }
+
+            combinations = combinations
+                .iter()
+                .flat_map(|chosen| {
+                    values.iter().map(move |value| {
+                        let mut chosen = chosen.clone();
+                        chosen.push(value.clone());
+                        chosen
+                    })
+                })
+                .collect();
+        }
+
+        // A SKU is listed for each variant when the seller has set one on all
+        // of them, otherwise each variant is known by the listing's SKU.
+        let per_variant = skus.len() == combinations.len();
+
+        // The quantity of a listing is that of all its variants together, which
+        // the export does not divide between them. It is kept on the product,
+        // and the stock of each variant is left untracked.
+        let divided = combinations.len() > 1;
+
+        if divided {
+            product
+                .specifications
+                .push((LISTING_QUANTITY.to_string(), cloned.quantity.clone()));
+        }
+
+        product.variants = combinations
+            .into_iter()
+            .enumerate()
+            .map(|(index, chosen)| {
+                let variant_sku = if per_variant {
+                    skus[index].clone()
+                } else {
+                    sku.clone()
+                };
+
+                let stock = match &store {
+                    Some(store) if !divided => vec![Stock {
+                        store: location(store),
+                        quantity: Quantity {
+                            quantity_sellable: quantity,
+                            quantity_unsellable: 0.0,
+                            quantity_on_order: 0.0,
+                            quantity_allocated: 0.0,
+                        },
+                    }],
+                    _ => vec![],
+                };
+
+                VariantInformation {
+                    name: if chosen.is_empty() {
+                        cloned.title.clone()
+                    } else {
+                        chosen.join(" ")
+                    },
+                    stock,
+                    images: vec![],
+                    retail_price: price,
+                    marginal_price: price,
+                    loyalty_discount: DiscountValue::Absolute(0),
+                    variant_code: vec![variant_sku.clone()],
+                    order_history: vec![],
+                    stock_information: StockInformation {
+                        stock_group: String::new(),
+                        sales_group: String::new(),
+                        value_stream: String::new(),
+                        brand: String::new(),
+                        tax_code: String::new(),
+                        weight: "0".to_string(),
+                        volume: "0.00".to_string(),
+                        max_volume: "0.00".to_string(),
+                        back_order: false,
+                        discontinued: false,
+                        non_diminishing: false,
+                        shippable: true,
+                        size_override_unit: String::new(),
+                        size_x_unit: String::new(),
+                        size_y_unit: String::new(),
+                        size_z_unit: String::new(),
+                        size_x: 0.0,
+                        size_y: 0.0,
+                        size_z: 0.0,
+                        min_stock_before_alert: 0.0,
+                        min_stock_level: 0.0,
+                        colli: String::new(),
+                    },
+                    barcode: String::new(),
+                    id: Uuid::new_v4().to_string(),
+                    buy_max: -1.0,
+                    buy_min: 1.0,
+                    identification: identify(&variant_sku, ""),
+                    stock_tracking: !divided,
+                }
+            })
+            .collect();
+
+        Ok(product)
+    }
+}
+
+impl Parsable<TransactionRecord> for Transaction {
+    fn parse_individual(
+        reader: &[Result<TransactionRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Transaction, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let date = parse_timestamp(&cloned.sale_date).unwrap_or(Utc::now());
+
+        // Sales made in person through Etsy's POS
+        // integration name the location of the sale.
+        let store = if cloned.in_person_location.is_empty() {
+            shop_store(db)
+        } else {
+            resolve_store(&cloned.in_person_location, db)
+        };
+
+        let origin = match &store {
+            Some(store) => location(store),
+            None => Location {
+                contact: named_contact("Etsy"),
+                store_code: "000".to_string(),
+                store_id: "000".to_string(),
+            },
+        };
+
+        let customer =
+            db.1.iter()
+                .find(|customer| !cloned.full_name.is_empty() && customer.name == cloned.full_name)
+                .cloned();
+
+        // Refunds and other adjustments leave their total
+        // in a column of its own, which is otherwise empty.
+        let total = if cloned.adjusted_order_total.is_empty() {
+            cloned.order_total.parse::<f32>().unwrap_or(0.0)
+        } else {
+            cloned.adjusted_order_total.parse::<f32>().unwrap_or(0.0)
+        };
+
+        let method = if cloned.payment_type.is_empty() {
+            &cloned.payment_method
+        } else {
+            &cloned.payment_type
+        };
+
+        let payment = Payment {
+            id: Uuid::new_v4().to_string(),
+            payment_method: split_payment_methods(method, &PAYMENT_METHODS)
+                .into_iter()
+                .next()
+                .map_or(PaymentMethod::Other(method.clone()), |split| split.method),
+            fulfillment_date: date,
+            amount: Price {
+                quantity: total,
+                currency: cloned.currency.clone(),
+            },
+            processing_fee: Price {
+                quantity: cloned.processing_fees.parse::<f32>().unwrap_or(0.0),
+                currency: cloned.currency.clone(),
+            },
+            status: PaymentStatus::Complete(Processable::Anonymous(String::from("etsy"))),
+            processor: PaymentProcessor::anonymous(String::from("etsy")),
+            order_ids: vec![],
+            delay_action: PaymentAction::Complete,
+            delay_duration: String::new(),
+        };
+
+        let status = match parse_timestamp(&cloned.date_shipped) {
+            Some(shipped) => OrderStatus::Fulfilled(shipped),
+            None => OrderStatus::Processing(date),
+        };
+
+        let mut order_notes = vec![];
+
+        if !cloned.coupon_code.is_empty() {
+            order_notes.push(import_note(format!(
+                "Coupon {} redeemed",
+                cloned.coupon_code
+            )));
+        }
+
+        Ok(Transaction {
+            id: Uuid::new_v4().to_string(),
+            customer: TransactionCustomer {
+                customer_type: CustomerType::Individual,
+                customer_id: customer
+                    .as_ref()
+                    .map_or(String::new(), |customer| customer.id.clone()),
+            },
+            kiosk: String::new(),
+            transaction_type: TransactionType::Out,
+            products: vec![Order {
+                id: Uuid::new_v4().to_string(),
+                destination: Location {
+                    contact: ContactInformation {
+                        name: cloned.full_name.clone(),
+                        mobile: MobileNumber::from(String::new()),
+                        email: customer
+                            .as_ref()
+                            .map_or(Email::from(String::new()), |customer| {
+                                customer.contact.email.clone()
+                            }),
+                        landline: String::new(),
+                        address: Address {
+                            street: cloned.street.clone(),
+                            street2: cloned.street2.clone(),
+                            city: match (cloned.city.is_empty(), cloned.state.is_empty()) {
+                                (_, true) => cloned.city.clone(),
+                                (true, false) => cloned.state.clone(),
+                                (false, false) => format!("{}, {}", cloned.city, cloned.state),
+                            },
+                            country: cloned.country.clone(),
+                            po_code: cloned.zipcode.clone(),
+                            lat: 0.0,
+                            lon: 0.0,
+                        },
+                    },
+                    store_code: origin.store_code.clone(),
+                    store_id: origin.store_id.clone(),
+                },
+                origin,
+                // Filled in from the sold order items.
+                products: vec![],
+                status: OrderStatusAssignment {
+                    status,
+                    assigned_products: vec![],
+                    timestamp: date,
+                },
+                status_history: vec![],
+                order_history: vec![],
+                previous_failed_fulfillment_attempts: vec![],
+                order_notes: order_notes.clone(),
+                reference: cloned.order_id.clone(),
+                creation_date: date,
+                discount: DiscountValue::Absolute(
+                    cloned.discount_amount.parse::<f32>().unwrap_or(0.0).round() as u32,
+                ),
+                order_type: if cloned.in_person_location.is_empty() {
+                    OrderType::Shipment
+                } else {
+                    OrderType::Direct
+                },
+            }],
+            order_total: total.round() as i64,
+            payment: vec![payment],
+            order_date: date,
+            order_notes,
+            salesperson: String::new(),
+            created_at: date,
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+/// Parses a sold order items export, adding each item to
+/// the transaction of its order as a line item.
+///
+/// No transactions are created, so the returned list is always empty.
+pub fn parse_order_items(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Transaction>, ParseFailure> {
+    for record in reader.deserialize::<TransactionItemRecord>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        // Variations are exported as `Name:Value` pairs.
+        let chosen: Vec<String> = record
+            .variations
+            .split(',')
+            .filter_map(|pair| pair.split_once(':'))
+            .map(|(_, value)| value.trim().to_string())
+            .collect();
+
+        let product = db.0.iter().find(|product| product.name == record.item_name);
+        let variant = product.and_then(|product| {
+            product
+                .variants
+                .iter()
+                .find(|variant| chosen.is_empty() || variant.name == chosen.join(" "))
+        });
+
+        let code = if !record.sku.is_empty() {
+            record.sku.clone()
+        } else {
+            variant
+                .and_then(|variant| variant.variant_code.first().cloned())
+                .unwrap_or(record.listing_id.clone())
+        };
+
+        let order = match db
+            .2
+            .iter_mut()
+            .flat_map(|transaction| transaction.products.iter_mut())
+            .find(|order| order.reference == record.order_id)
+        {
+            Some(order) => order,
+            None => {
+                println!(
+                    "[warn]: Order {} of item {} is not in the export.",
+                    record.order_id, record.item_name
+                );
+                continue;
+            }
+        };
+
+        let date = parse_timestamp(&record.date_paid).unwrap_or(order.creation_date);
+        let quantity = record.quantity.parse::<f32>().unwrap_or(1.0);
+
+        order.products.push(ProductPurchase {
+            id: Uuid::new_v4().to_string(),
+            product_code: code.clone(),
+            product_name: record.item_name.clone(),
+            product_sku: product.map_or(code, |product| product.sku.clone()),
+            product_cost: record.price.parse::<f32>().unwrap_or(0.0),
+            discount: DiscountValue::Absolute(
+                record.discount_amount.parse::<f32>().unwrap_or(0.0).round() as u32,
+            ),
+            product_variant_name: variant
+                .map_or(record.item_name.clone(), |variant| variant.name.clone()),
+            quantity,
+            tags: vec![],
+            transaction_type: TransactionType::Out,
+            instances: (0..quantity as u32)
+                .map(|_| ProductInstance {
+                    id: Uuid::new_v4().to_string(),
+                    fulfillment_status: FulfillmentStatus {
+                        pick_status: PickStatus::Picked,
+                        pick_history: vec![],
+                        last_updated: date,
+                        notes: vec![],
+                    },
+                })
+                .collect(),
+        });
+    }
+
+    Ok(vec![])
+}
@@ -0,0 +1,403 @@
+//! Hike POS exports.
+//!
+//! Product exports list a row for each variant, sharing the product's handle,
+//! and carry a set of columns for each outlet, such as `stock_Main Store`.
+//! A store is created for every outlet named by these columns.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_store_price, add_variant_option, identify, lowercase_tags, parse_flag, parse_tags,
+        resolve_store, ProductStatus,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::{Reader, StringRecord};
+use open_stock::{
+    DiscountValue, Location, Product, ProductIdentification, Quantity, Stock, StockInformation,
+    VariantInformation,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "handle,sku,name,description,product_type,brand,supplier,tags,variant_option_one_name,variant_option_one_value,variant_option_two_name,variant_option_two_value,variant_option_three_name,variant_option_three_value,supply_price,retail_price,tax_name,barcode,track_inventory,active,stock_Main Store,reorder_level_Main Store,price_Main Store",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "ZZZZ",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "ZZZZ",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The prefixes of the columns held for each outlet, such as `stock_Main Store`.
+const STOCK_PREFIX: &str = "stock_";
+const REORDER_LEVEL_PREFIX: &str = "reorder_level_";
+const PRICE_PREFIX: &str = "price_";
+
+/// The columns of a single outlet.
+#[derive(Debug, Default, Clone)]
+struct OutletColumns {
+    outlet: String,
+    stock: String,
+    reorder_level: String,
+    price: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "handle")]
+    handle: String,
+
+    #[serde(rename = "sku")]
+    sku: String,
+
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "description")]
+    description: String,
+
+    #[serde(rename = "product_type")]
+    product_type: String,
+
+    #[serde(rename = "brand")]
+    brand: String,
+
+    #[serde(rename = "supplier")]
+    supplier: String,
+
+    #[serde(rename = "tags")]
+    tags: String,
+
+    #[serde(rename = "variant_option_one_name")]
+    option_1_name: String,
+
+    #[serde(rename = "variant_option_one_value")]
+    option_1_value: String,
+
+    #[serde(rename = "variant_option_two_name")]
+    option_2_name: String,
+
+    #[serde(rename = "variant_option_two_value")]
+    option_2_value: String,
+
+    #[serde(rename = "variant_option_three_name")]
+    option_3_name: String,
+
+    #[serde(rename = "variant_option_three_value")]
+    option_3_value: String,
+
+    #[serde(rename = "supply_price")]
+    supply_price: String,
+
+    #[serde(rename = "retail_price")]
+    retail_price: String,
+
+    #[serde(rename = "tax_name")]
+    tax_name: String,
+
+    #[serde(rename = "barcode")]
+    barcode: String,
+
+    #[serde(rename = "track_inventory")]
+    track_inventory: String,
+
+    #[serde(rename = "active")]
+    active: String,
+
+    /// Read from the columns of each outlet.
+    #[serde(skip)]
+    outlets: Vec<OutletColumns>,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+/// Parses a product export, which carries a set of columns
+/// for each outlet. A store is created for every outlet.
+pub fn parse_products(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(error) => return Err(ParseFailure::ReadFailure(error.to_string())),
+    };
+
+    for (_, outlet) in headers.iter().filter_map(outlet_column) {
+        resolve_store(&outlet, db);
+    }
+
+    let collected: Vec<Result<ProductRecord, csv::Error>> = reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let mut product: ProductRecord = record.deserialize(Some(&headers))?;
+            product.outlets = read_outlets(&headers, &record);
+            Ok(product)
+        })
+        .collect();
+
+    parse_collected(collected, db)
+}
+
+/// Splits an outlet's column, such as `stock_Main Store`,
+/// into the column's prefix and the name of the outlet.
+fn outlet_column(header: &str) -> Option<(&'static str, String)> {
+    [STOCK_PREFIX, REORDER_LEVEL_PREFIX, PRICE_PREFIX]
+        .into_iter()
+        .find_map(|prefix| {
+            header
+                .strip_prefix(prefix)
+                .filter(|outlet| !outlet.trim().is_empty())
+                .map(|outlet| (prefix, outlet.trim().to_string()))
+        })
+}
+
+fn read_outlets(headers: &StringRecord, record: &StringRecord) -> Vec<OutletColumns> {
+    let mut outlets: Vec<OutletColumns> = vec![];
+
+    for (header, value) in headers.iter().zip(record.iter()) {
+        let (prefix, outlet) = match outlet_column(header) {
+            Some(column) => column,
+            None => continue,
+        };
+
+        let index = match outlets
+            .iter()
+            .position(|existing| existing.outlet == outlet)
+        {
+            Some(index) => index,
+            None => {
+                outlets.push(OutletColumns {
+                    outlet,
+                    ..Default::default()
+                });
+
+                outlets.len() - 1
+            }
+        };
+
+        let value = value.trim().to_string();
+
+        match prefix {
+            STOCK_PREFIX => outlets[index].stock = value,
+            REORDER_LEVEL_PREFIX => outlets[index].reorder_level = value,
+            _ => outlets[index].price = value,
+        }
+    }
+
+    outlets
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let first = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        let rows: Vec<&ProductRecord> = reader[*line..]
+            .iter()
+            .map_while(|row| row.as_ref().ok())
+            .take_while(|row| row.handle == first.handle)
+            .collect();
+
+        *line += rows.len();
+
+        // Inactive products can no longer be sold.
+        let status = if parse_flag(&first.active) {
+            ProductStatus::Active
+        } else {
+            ProductStatus::Archived
+        };
+
+        let mut product = Product {
+            name: first.name.clone(),
+            company: if first.brand.is_empty() {
+                first.supplier.clone()
+            } else {
+                first.brand.clone()
+            },
+            variant_groups: vec![],
+            variants: vec![],
+            sku: first.handle.clone(),
+            images: vec![],
+            tags: parse_tags(&first.tags, ',', lowercase_tags()),
+            description: first.description.clone(),
+            specifications: vec![],
+            name_long: first.name.clone(),
+            identification: ProductIdentification {
+                sku: first.handle.clone(),
+                ..Default::default()
+            },
+            description_long: first.description.clone(),
+            visible: status.visibility(true),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        if !first.supplier.is_empty() {
+            product
+                .specifications
+                .push(("Supplier".to_string(), first.supplier.clone()));
+        }
+
+        for row in rows {
+            let options = [
+                (&row.option_1_name, &row.option_1_value),
+                (&row.option_2_name, &row.option_2_value),
+                (&row.option_3_name, &row.option_3_value),
+            ];
+
+            for (category, value) in options {
+                add_variant_option(&mut product, category, value);
+            }
+
+            let name = options
+                .iter()
+                .map(|(_, value)| value.as_str())
+                .filter(|value| !value.is_empty())
+                .collect::<Vec<&str>>()
+                .join(" ");
+
+            let name = if name.is_empty() {
+                product.name.clone()
+            } else {
+                name
+            };
+
+            let price = row.retail_price.parse::<f32>().unwrap_or(0.0);
+            let stock_tracking = parse_flag(&row.track_inventory);
+            let mut stock = vec![];
+
+            for columns in &row.outlets {
+                let store = resolve_store(&columns.outlet, db);
+
+                // Outlets without a price of their own sell at the retail price.
+                if let (Some(store), Ok(outlet_price)) = (&store, columns.price.parse::<f32>()) {
+                    add_store_price(&mut product, &row.sku, price, &store.code, outlet_price);
+                }
+
+                if !stock_tracking {
+                    continue;
+                }
+
+                if let Some(store) = store {
+                    stock.push(Stock {
+                        store: Location {
+                            store_code: store.code.clone(),
+                            store_id: store.id.clone(),
+                            contact: store.contact.clone(),
+                        },
+                        quantity: Quantity {
+                            quantity_sellable: columns.stock.parse::<f32>().unwrap_or(0.0),
+                            quantity_unsellable: 0.0,
+                            quantity_on_order: 0.0,
+                            quantity_allocated: 0.0,
+                        },
+                    });
+                }
+            }
+
+            product.variants.push(VariantInformation {
+                name,
+                stock,
+                images: vec![],
+                retail_price: price,
+                marginal_price: row.supply_price.parse::<f32>().unwrap_or(price),
+                loyalty_discount: DiscountValue::Absolute(0),
+                variant_code: vec![row.sku.clone()],
+                order_history: vec![],
+                stock_information: StockInformation {
+                    stock_group: row.product_type.clone(),
+                    sales_group: String::new(),
+                    value_stream: String::new(),
+                    brand: row.brand.clone(),
+                    tax_code: row.tax_name.clone(),
+                    weight: "0".to_string(),
+                    volume: "0.00".to_string(),
+                    max_volume: "0.00".to_string(),
+                    back_order: false,
+                    discontinued: status.discontinued(),
+                    non_diminishing: false,
+                    shippable: true,
+                    size_override_unit: String::new(),
+                    size_x_unit: String::new(),
+                    size_y_unit: String::new(),
+                    size_z_unit: String::new(),
+                    size_x: 0.0,
+                    size_y: 0.0,
+                    size_z: 0.0,
+                    // Reorder levels are set for each outlet,
+                    // the first outlet's level is carried over.
+                    min_stock_before_alert: row
+                        .outlets
+                        .iter()
+                        .find_map(|outlet| outlet.reorder_level.parse::<f64>().ok())
+                        .unwrap_or(0.0),
+                    min_stock_level: 0.0,
+                    colli: String::new(),
+                },
+                barcode: row.barcode.clone(),
+                id: Uuid::new_v4().to_string(),
+                buy_max: -1.0,
+                buy_min: 1.0,
+                identification: identify(&row.sku, &row.barcode),
+                stock_tracking,
+            });
+        }
+
+        Ok(product)
+    }
+}
@@ -1,9 +1,9 @@
-use crate::{parser::ParseFailure, InlineDatabase};
+use crate::{parser::ParseFailure, parser::SpecialPricing, InlineDatabase};
 use chrono::prelude::*;
 use csv::Reader;
 use open_stock::{
-    Address, ContactInformation, Customer, Email, Kiosk, MobileNumber, Note, Product, Store,
-    Transaction,
+    Address, ContactInformation, Customer, Email, Kiosk, MobileNumber, Note, Product, Promotion,
+    Store, Transaction,
 };
 use serde::{Deserialize, Serialize};
 use std::{fs::File, str::FromStr};
@@ -15,8 +15,14 @@ pub fn match_self(parse_type: ParseType) -> String {
         ParseType::Store => "ZZZZ",
         ParseType::Kiosk => "ZZZZ",
         ParseType::Product => "ZZZZ",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
         ParseType::Customer => "ZZZZ",
+        ParseType::CustomerAddress => "ZZZZ",
         ParseType::Transaction => "ZZZZ",
+        ParseType::TransactionItem => "ZZZZ",
         ParseType::Invalid => "ZZZZZ",
     };
 
@@ -33,6 +39,11 @@ pub struct KioskRecord {
     // Empty
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PromotionRecord {
+    // Empty
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ProductRecord {
     #[serde(rename = "Handle")]
@@ -513,11 +524,7 @@ impl Parsable<CustomerRecord> for Customer {
                     }]
                 },
                 balance: 0,
-                special_pricing: if cloned.tax_exempt == "yes" {
-                    "TAX-EXEMPT".to_string()
-                } else {
-                    "".to_string()
-                },
+                special_pricing: SpecialPricing::tax_exempt(cloned.tax_exempt == "yes").to_field(),
                 accepts_marketing: cloned.accepts_marketing == "yes",
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
@@ -550,6 +557,19 @@ impl Parsable<ProductRecord> for Product {
     }
 }
 
+impl Parsable<PromotionRecord> for Promotion {
+    fn parse_individual(
+        _reader: &[Result<PromotionRecord, csv::Error>],
+        _line: &mut usize,
+        _db: &mut InlineDatabase,
+    ) -> Result<Self, ParseFailure>
+    where
+        Self: Sized,
+    {
+        Err(ParseFailure::EOFException)
+    }
+}
+
 impl Parsable<KioskRecord> for Kiosk {
     fn parse_individual(
         _reader: &[Result<KioskRecord, csv::Error>],
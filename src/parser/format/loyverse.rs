@@ -0,0 +1,459 @@
+//! Loyverse POS exports.
+//!
+//! Item exports list a row for each variant, sharing the item's handle, and
+//! carry a set of columns for each store, such as `In stock [Main Store]`.
+//! A store is created for every store named by these columns.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_store_price, add_variant_option, identify, lowercase_tags, merge_tags, parse_flag,
+        resolve_store,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::{Reader, StringRecord};
+use open_stock::{
+    DiscountValue, Location, Product, ProductIdentification, ProductVisibility, Quantity, Stock,
+    StockInformation, VariantInformation,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "Handle,SKU,Name,Category,Description,Sold by weight,Option 1 name,Option 1 value,Option 2 name,Option 2 value,Option 3 name,Option 3 value,Default price,Cost,Barcode,SKU of included item,Quantity of included item,Track stock,Available for sale [Main Store],Price [Main Store],In stock [Main Store],Low stock [Main Store],Tax - VAT (20%)",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "ZZZZ",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "ZZZZ",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The prefix of the tax columns, such as `Tax - VAT (20%)`,
+/// which hold whether the tax applies to the item.
+const TAX_PREFIX: &str = "Tax - ";
+
+/// The columns held for each store, such as `In stock [Main Store]`.
+const AVAILABLE: &str = "Available for sale";
+const PRICE: &str = "Price";
+const IN_STOCK: &str = "In stock";
+const LOW_STOCK: &str = "Low stock";
+
+/// The columns of a single store.
+#[derive(Debug, Default, Clone)]
+struct StoreColumns {
+    store: String,
+    available: String,
+    price: String,
+    in_stock: String,
+    low_stock: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "Handle")]
+    handle: String,
+
+    #[serde(rename = "SKU")]
+    sku: String,
+
+    #[serde(rename = "Name")]
+    name: String,
+
+    #[serde(rename = "Category")]
+    category: String,
+
+    #[serde(rename = "Description")]
+    description: String,
+
+    #[serde(rename = "Sold by weight")]
+    sold_by_weight: String,
+
+    #[serde(rename = "Option 1 name")]
+    option_1_name: String,
+
+    #[serde(rename = "Option 1 value")]
+    option_1_value: String,
+
+    #[serde(rename = "Option 2 name")]
+    option_2_name: String,
+
+    #[serde(rename = "Option 2 value")]
+    option_2_value: String,
+
+    #[serde(rename = "Option 3 name")]
+    option_3_name: String,
+
+    #[serde(rename = "Option 3 value")]
+    option_3_value: String,
+
+    #[serde(rename = "Default price")]
+    default_price: String,
+
+    #[serde(rename = "Cost")]
+    cost: String,
+
+    #[serde(rename = "Barcode")]
+    barcode: String,
+
+    /// Set on the rows of composite items, listing one of their components.
+    #[serde(rename = "SKU of included item")]
+    included_sku: String,
+
+    #[serde(rename = "Quantity of included item")]
+    included_quantity: String,
+
+    #[serde(rename = "Track stock")]
+    track_stock: String,
+
+    /// Read from the columns of each store.
+    #[serde(skip)]
+    stores: Vec<StoreColumns>,
+
+    /// The names of the taxes which apply, read from the tax columns.
+    #[serde(skip)]
+    taxes: Vec<String>,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+/// Parses an item export, which carries a set of columns
+/// for each store. A store is created for every store.
+pub fn parse_products(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(error) => return Err(ParseFailure::ReadFailure(error.to_string())),
+    };
+
+    for (_, store) in headers.iter().filter_map(store_column) {
+        resolve_store(&store, db);
+    }
+
+    let collected: Vec<Result<ProductRecord, csv::Error>> = reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let mut product: ProductRecord = record.deserialize(Some(&headers))?;
+            product.stores = read_stores(&headers, &record);
+            product.taxes = read_taxes(&headers, &record);
+            Ok(product)
+        })
+        .collect();
+
+    parse_collected(collected, db)
+}
+
+/// Splits a store's column, such as `In stock [Main Store]`,
+/// into the kind of column and the name of the store.
+fn store_column(header: &str) -> Option<(&str, String)> {
+    let (column, store) = header.strip_suffix(']')?.split_once(" [")?;
+
+    match column {
+        AVAILABLE | PRICE | IN_STOCK | LOW_STOCK => Some((column, store.trim().to_string())),
+        _ => None,
+    }
+}
+
+fn read_stores(headers: &StringRecord, record: &StringRecord) -> Vec<StoreColumns> {
+    let mut stores: Vec<StoreColumns> = vec![];
+
+    for (header, value) in headers.iter().zip(record.iter()) {
+        let (column, store) = match store_column(header) {
+            Some(column) => column,
+            None => continue,
+        };
+
+        let index = match stores.iter().position(|existing| existing.store == store) {
+            Some(index) => index,
+            None => {
+                stores.push(StoreColumns {
+                    store,
+                    ..Default::default()
+                });
+
+                stores.len() - 1
+            }
+        };
+
+        let value = value.trim().to_string();
+
+        match column {
+            AVAILABLE => stores[index].available = value,
+            PRICE => stores[index].price = value,
+            IN_STOCK => stores[index].in_stock = value,
+            _ => stores[index].low_stock = value,
+        }
+    }
+
+    stores
+}
+
+fn read_taxes(headers: &StringRecord, record: &StringRecord) -> Vec<String> {
+    headers
+        .iter()
+        .zip(record.iter())
+        .filter(|(_, value)| parse_flag(value))
+        .filter_map(|(header, _)| header.strip_prefix(TAX_PREFIX))
+        .map(|tax| tax.trim().to_string())
+        .collect()
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let first = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        let rows: Vec<&ProductRecord> = reader[*line..]
+            .iter()
+            .map_while(|row| row.as_ref().ok())
+            .take_while(|row| row.handle == first.handle)
+            .collect();
+
+        *line += rows.len();
+
+        // The item's category is kept as its only tag.
+        let mut tags = vec![];
+        merge_tags(
+            &mut tags,
+            [first.category.trim().to_string()],
+            lowercase_tags(),
+        );
+
+        // An item not available for sale at any store is no longer sold.
+        let available = first
+            .stores
+            .iter()
+            .any(|store| store.available.is_empty() || parse_flag(&store.available));
+
+        let mut product = Product {
+            name: first.name.clone(),
+            company: String::new(),
+            variant_groups: vec![],
+            variants: vec![],
+            sku: first.handle.clone(),
+            images: vec![],
+            tags,
+            description: first.description.clone(),
+            specifications: vec![],
+            name_long: first.name.clone(),
+            identification: ProductIdentification {
+                sku: first.handle.clone(),
+                ..Default::default()
+            },
+            description_long: first.description.clone(),
+            visible: if available {
+                ProductVisibility::ShowWhenInStock
+            } else {
+                ProductVisibility::AlwaysHidden
+            },
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        // Composite items list each component on a row of their own,
+        // which leaves empty or repeats the SKU of the variant it belongs to.
+        // Each is kept with the quantity included, such as `0.25 x 10010`.
+        let components: Vec<String> = rows
+            .iter()
+            .filter(|row| !row.included_sku.is_empty())
+            .map(|row| {
+                let quantity = match row.included_quantity.trim() {
+                    "" => "1",
+                    quantity => quantity,
+                };
+
+                format!("{} x {}", quantity, row.included_sku)
+            })
+            .collect();
+
+        if !components.is_empty() {
+            product
+                .specifications
+                .push(("Included Items".to_string(), components.join(", ")));
+        }
+
+        for row in rows {
+            let component = row.sku.is_empty() && !row.included_sku.is_empty();
+
+            if component
+                || product
+                    .variants
+                    .iter()
+                    .any(|variant| variant.variant_code.contains(&row.sku))
+            {
+                continue;
+            }
+
+            let options = [
+                (&row.option_1_name, &row.option_1_value),
+                (&row.option_2_name, &row.option_2_value),
+                (&row.option_3_name, &row.option_3_value),
+            ];
+
+            for (category, value) in options {
+                add_variant_option(&mut product, category, value);
+            }
+
+            let name = options
+                .iter()
+                .map(|(_, value)| value.as_str())
+                .filter(|value| !value.is_empty())
+                .collect::<Vec<&str>>()
+                .join(" ");
+
+            let name = if name.is_empty() {
+                product.name.clone()
+            } else {
+                name
+            };
+
+            // Items without a default price are priced by each store.
+            let price = match row.default_price.parse::<f32>() {
+                Ok(price) => price,
+                Err(_) => row
+                    .stores
+                    .iter()
+                    .find_map(|store| store.price.parse::<f32>().ok())
+                    .unwrap_or(0.0),
+            };
+
+            let stock_tracking = parse_flag(&row.track_stock);
+            let mut stock = vec![];
+
+            for columns in &row.stores {
+                let store = resolve_store(&columns.store, db);
+
+                if let (Some(store), Ok(store_price)) = (&store, columns.price.parse::<f32>()) {
+                    add_store_price(&mut product, &row.sku, price, &store.code, store_price);
+                }
+
+                if !stock_tracking {
+                    continue;
+                }
+
+                if let Some(store) = store {
+                    stock.push(Stock {
+                        store: Location {
+                            store_code: store.code.clone(),
+                            store_id: store.id.clone(),
+                            contact: store.contact.clone(),
+                        },
+                        quantity: Quantity {
+                            quantity_sellable: columns.in_stock.parse::<f32>().unwrap_or(0.0),
+                            quantity_unsellable: 0.0,
+                            quantity_on_order: 0.0,
+                            quantity_allocated: 0.0,
+                        },
+                    });
+                }
+            }
+
+            let sold_by_weight = parse_flag(&row.sold_by_weight);
+
+            product.variants.push(VariantInformation {
+                name,
+                stock,
+                images: vec![],
+                retail_price: price,
+                marginal_price: row.cost.parse::<f32>().unwrap_or(price),
+                loyalty_discount: DiscountValue::Absolute(0),
+                variant_code: vec![row.sku.clone()],
+                order_history: vec![],
+                stock_information: StockInformation {
+                    stock_group: String::new(),
+                    sales_group: first.category.clone(),
+                    value_stream: String::new(),
+                    brand: String::new(),
+                    tax_code: row.taxes.join(", "),
+                    weight: "0".to_string(),
+                    volume: "0.00".to_string(),
+                    max_volume: "0.00".to_string(),
+                    back_order: false,
+                    discontinued: !available,
+                    non_diminishing: false,
+                    shippable: true,
+                    size_override_unit: String::new(),
+                    size_x_unit: String::new(),
+                    size_y_unit: String::new(),
+                    size_z_unit: String::new(),
+                    size_x: 0.0,
+                    size_y: 0.0,
+                    size_z: 0.0,
+                    // Alerts are set for each store, the
+                    // first store's level is carried over.
+                    min_stock_before_alert: row
+                        .stores
+                        .iter()
+                        .find_map(|store| store.low_stock.parse::<f64>().ok())
+                        .unwrap_or(0.0),
+                    min_stock_level: 0.0,
+                    colli: String::new(),
+                },
+                barcode: row.barcode.clone(),
+                id: Uuid::new_v4().to_string(),
+                buy_max: -1.0,
+                buy_min: if sold_by_weight { 0.0 } else { 1.0 },
+                identification: identify(&row.sku, &row.barcode),
+                stock_tracking,
+            });
+        }
+
+        Ok(product)
+    }
+}
@@ -0,0 +1,704 @@
+//! Magento (Adobe Commerce) exports.
+//!
+//! A configurable product's variants are exported as simple products of
+//! their own, which its `configurable_variations` column refers to by SKU.
+//! The catalog is therefore read as a whole, rather than row by row.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_variant_option, identify, lowercase_tags, merge_tags, parse_flag, resolve_store,
+        weight_in_kilograms, weight_unit, ProductStatus, SpecialPricing,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::Reader;
+use open_stock::{
+    Address, ContactInformation, Customer, DiscountValue, Email, Location, MobileNumber, Note,
+    Product, ProductIdentification, ProductVisibility, Quantity, Stock, StockInformation, Store,
+    VariantInformation,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::ParseType;
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "sku,store_view_code,attribute_set_code,product_type,categories,product_websites,name,description,short_description,weight,product_online,tax_class_name,visibility,price,special_price,url_key,meta_title,meta_keywords,meta_description,base_image,base_image_label,created_at,updated_at,country_of_manufacture,additional_attributes,qty,out_of_stock_qty,allow_backorders,is_in_stock,notify_on_stock_below,manage_stock,additional_images,additional_image_labels,configurable_variations,configurable_variation_labels,associated_skus",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "email,_website,_store,confirmation,created_at,created_in,disable_auto_group_change,dob,firstname,gender,group_id,lastname,middlename,password_hash,prefix,rp_token,rp_token_created_at,store_id,suffix,taxvat,website_id,password",
+        ParseType::CustomerAddress => "_website,_email,_entity_id,city,company,country_id,fax,firstname,lastname,middlename,postcode,prefix,region,region_id,street,suffix,telephone,vat_id,vat_is_valid,vat_request_date,vat_request_id,vat_request_success,_address_default_billing_,_address_default_shipping_",
+        ParseType::Transaction => "ZZZZ",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The unit weights are exported in unless set otherwise with
+/// `ConversionOptions::set_weight_unit`,
+/// being Magento's default locale weight unit.
+pub const DEFAULT_WEIGHT_UNIT: &str = "lbs";
+
+/// The store standing for Magento's default stock source.
+const DEFAULT_SOURCE: &str = "Magento";
+
+/// Magento's default for tax exempt customers' `group_id`.
+const TAX_EXEMPT_GROUP: &str = "3";
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "MAGENTO-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Reads the timestamps of exports, such as `5/7/23, 10:02 AM` or `2023-05-07 10:02:00`.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    ["%Y-%m-%d %H:%M:%S", "%m/%d/%y, %I:%M %p"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value.trim(), format).ok())
+        .map(|timestamp| timestamp.and_utc())
+}
+
+/// Unpacks a compound column of `key=value` pairs, such as
+/// `additional_attributes` (`color=Black,material=Cotton`). Values may hold
+/// the separator themselves, so a pair without a key continues the last value.
+fn unpack(value: &str, separator: char) -> Vec<(String, String)> {
+    let mut pairs: Vec<(String, String)> = vec![];
+
+    for segment in value.split(separator) {
+        match (segment.split_once('='), pairs.last_mut()) {
+            (Some((key, value)), _) => pairs.push((key.trim().to_string(), value.to_string())),
+            (None, Some((_, last))) => {
+                last.push(separator);
+                last.push_str(segment);
+            }
+            (None, None) => {}
+        }
+    }
+
+    pairs
+        .into_iter()
+        .map(|(key, value)| (key, value.trim().to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .collect()
+}
+
+fn value_of<'a>(pairs: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    pairs
+        .iter()
+        .find(|(name, _)| name == key)
+        .map(|(_, value)| value.as_str())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProductRecord {
+    #[serde(rename = "sku")]
+    sku: String,
+
+    /// Rows with a store view hold translations of the default row.
+    #[serde(rename = "store_view_code")]
+    store_view_code: String,
+
+    #[serde(rename = "attribute_set_code")]
+    attribute_set: String,
+
+    #[serde(rename = "product_type")]
+    product_type: String,
+
+    #[serde(rename = "categories")]
+    categories: String,
+
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "description")]
+    description: String,
+
+    #[serde(rename = "short_description")]
+    short_description: String,
+
+    #[serde(rename = "weight")]
+    weight: String,
+
+    #[serde(rename = "product_online")]
+    product_online: String,
+
+    #[serde(rename = "tax_class_name")]
+    tax_class: String,
+
+    #[serde(rename = "visibility")]
+    visibility: String,
+
+    #[serde(rename = "price")]
+    price: String,
+
+    #[serde(rename = "meta_title")]
+    meta_title: String,
+
+    #[serde(rename = "meta_description")]
+    meta_description: String,
+
+    #[serde(rename = "base_image")]
+    base_image: String,
+
+    #[serde(rename = "country_of_manufacture")]
+    country_of_manufacture: String,
+
+    #[serde(rename = "additional_attributes")]
+    additional_attributes: String,
+
+    #[serde(rename = "qty")]
+    qty: String,
+
+    #[serde(rename = "allow_backorders")]
+    allow_backorders: String,
+
+    #[serde(rename = "notify_on_stock_below")]
+    notify_on_stock_below: String,
+
+    #[serde(rename = "manage_stock")]
+    manage_stock: String,
+
+    #[serde(rename = "additional_images")]
+    additional_images: String,
+
+    #[serde(rename = "configurable_variations")]
+    configurable_variations: String,
+
+    #[serde(rename = "configurable_variation_labels")]
+    configurable_variation_labels: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerRecord {
+    #[serde(rename = "email")]
+    email: String,
+
+    #[serde(rename = "_website")]
+    website: String,
+
+    #[serde(rename = "created_at")]
+    created_at: String,
+
+    #[serde(rename = "firstname")]
+    first_name: String,
+
+    #[serde(rename = "middlename")]
+    middle_name: String,
+
+    #[serde(rename = "lastname")]
+    last_name: String,
+
+    #[serde(rename = "group_id")]
+    group_id: String,
+
+    #[serde(rename = "taxvat")]
+    tax_vat: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerAddressRecord {
+    #[serde(rename = "_email")]
+    email: String,
+
+    #[serde(rename = "city")]
+    city: String,
+
+    #[serde(rename = "company")]
+    company: String,
+
+    #[serde(rename = "country_id")]
+    country: String,
+
+    #[serde(rename = "postcode")]
+    postcode: String,
+
+    #[serde(rename = "region")]
+    region: String,
+
+    /// Each line of the street address is on a line of its own.
+    #[serde(rename = "street")]
+    street: String,
+
+    #[serde(rename = "telephone")]
+    telephone: String,
+
+    #[serde(rename = "_address_default_billing_")]
+    default_billing: String,
+}
+
+/// Parses a `catalog_product` export. Each configurable product becomes a
+/// product with a variant for each of its `configurable_variations`, and
+/// every simple product which is not such a variant becomes a product of
+/// its own.
+pub fn parse_products(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let mut records: Vec<ProductRecord> = vec![];
+
+    for record in reader.deserialize::<ProductRecord>() {
+        match record {
+            // Translations are not carried over.
+            Ok(record) if record.store_view_code.is_empty() => records.push(record),
+            Ok(_) => {}
+            Err(error) => println!("[warn]: Parser Warning: {:?}", error),
+        }
+    }
+
+    // Magento keeps a single stock source by default.
+    let store = resolve_store(DEFAULT_SOURCE, db);
+
+    let mut variant_skus: HashSet<String> = HashSet::new();
+    let mut products = vec![];
+
+    for parent in records
+        .iter()
+        .filter(|record| record.product_type == "configurable")
+    {
+        let labels = unpack(&parent.configurable_variation_labels, ',');
+        let mut product = build_product(parent);
+
+        for variation in parent.configurable_variations.split('|') {
+            let options = unpack(variation, ',');
+
+            let sku = match value_of(&options, "sku") {
+                Some(sku) => sku,
+                None => continue,
+            };
+
+            let child = match records.iter().find(|record| record.sku == sku) {
+                Some(child) => child,
+                None => {
+                    println!(
+                        "[warn]: Variation {} of {} is not in the export.",
+                        sku, parent.sku
+                    );
+                    continue;
+                }
+            };
+
+            variant_skus.insert(sku.to_string());
+
+            let mut values = vec![];
+
+            for (code, value) in options.iter().filter(|(code, _)| code != "sku") {
+                let category = value_of(&labels, code).unwrap_or(code);
+                add_variant_option(&mut product, category, value);
+                values.push(value.as_str());
+            }
+
+            for image in images(child) {
+                if !product.images.contains(&image) {
+                    product.images.push(image);
+                }
+            }
+
+            product
+                .variants
+                .push(build_variant(child, parent, values.join(" "), &store));
+        }
+
+        if product.variants.is_empty() {
+            println!(
+                "[warn]: Configurable product {} has no variations.",
+                parent.sku
+            );
+            continue;
+        }
+
+        products.push(product);
+    }
+
+    for record in records.iter().filter(|record| {
+        !variant_skus.contains(&record.sku)
+            && matches!(
+                record.product_type.as_str(),
+                "simple" | "virtual" | "downloadable"
+            )
+    }) {
+        let mut product = build_product(record);
+
+        product
+            .variants
+            .push(build_variant(record, record, record.name.clone(), &store));
+        products.push(product);
+    }
+
+    for record in records
+        .iter()
+        .filter(|record| matches!(record.product_type.as_str(), "bundle" | "grouped"))
+    {
+        println!(
+            "[warn]: Skipping {} product {}, which cannot be imported.",
+            record.product_type, record.sku
+        );
+    }
+
+    Ok(products)
+}
+
+fn images(record: &ProductRecord) -> Vec<String> {
+    let mut images: Vec<String> = vec![];
+
+    for image in [record.base_image.as_str()]
+        .into_iter()
+        .chain(record.additional_images.split(','))
+    {
+        let image = image.trim();
+
+        if !image.is_empty() && !images.iter().any(|existing| existing == image) {
+            images.push(image.to_string());
+        }
+    }
+
+    images
+}
+
+fn build_product(record: &ProductRecord) -> Product {
+    // `product_online` is `1` for enabled products, and `2` for disabled ones.
+    let status = if record.product_online == "2" {
+        ProductStatus::Archived
+    } else {
+        ProductStatus::Active
+    };
+
+    let mut specifications = vec![];
+
+    for (name, value) in [
+        ("SEO Title", &record.meta_title),
+        ("SEO Description", &record.meta_description),
+        ("Country of Manufacture", &record.country_of_manufacture),
+    ] {
+        if !value.is_empty() {
+            specifications.push((name.to_string(), value.to_string()));
+        }
+    }
+
+    // Variant options are carried by the variant groups instead.
+    let option_codes: Vec<(String, String)> = unpack(&record.configurable_variation_labels, ',');
+
+    let attributes = unpack(&record.additional_attributes, ',');
+
+    for (key, value) in attributes.iter().cloned() {
+        if value_of(&option_codes, &key).is_none() && !value.is_empty() {
+            specifications.push((key, value));
+        }
+    }
+
+    // Categories are exported as paths, such as `Default Category/Men/Tops`.
+    let mut tags: Vec<String> = vec![];
+
+    merge_tags(
+        &mut tags,
+        record
+            .categories
+            .split(',')
+            .filter_map(|category| category.rsplit('/').next())
+            .map(|leaf| leaf.trim().to_string()),
+        lowercase_tags(),
+    );
+
+    Product {
+        name: record.name.clone(),
+        company: value_of(&attributes, "manufacturer")
+            .unwrap_or_default()
+            .to_string(),
+        variant_groups: vec![],
+        variants: vec![],
+        sku: record.sku.clone(),
+        images: images(record),
+        tags,
+        description: if record.short_description.is_empty() {
+            record.description.clone()
+        } else {
+            record.short_description.clone()
+        },
+        specifications,
+        name_long: record.name.clone(),
+        identification: ProductIdentification {
+            sku: record.sku.clone(),
+            ..Default::default()
+        },
+        description_long: record.description.clone(),
+        visible: match record.visibility.as_str() {
+            "Not Visible Individually" => ProductVisibility::AlwaysHidden,
+            _ => status.visibility(true),
+        },
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    }
+}
+
+/// Builds a variant from a simple product, inheriting anything
+/// it leaves empty from the configurable product it belongs to.
+fn build_variant(
+    record: &ProductRecord,
+    parent: &ProductRecord,
+    name: String,
+    store: &Option<Store>,
+) -> VariantInformation {
+    let inherit = |value: &str, fallback: &str| -> String {
+        if value.trim().is_empty() {
+            fallback.to_string()
+        } else {
+            value.to_string()
+        }
+    };
+
+    let price = inherit(&record.price, &parent.price)
+        .parse::<f32>()
+        .unwrap_or(0.0);
+    let stock_tracking = record.manage_stock != "0";
+    let attributes = unpack(&record.additional_attributes, ',');
+    let barcode = ["ean", "upc", "gtin", "barcode"]
+        .iter()
+        .find_map(|code| value_of(&attributes, code))
+        .unwrap_or_default()
+        .to_string();
+
+    let stock = match store {
+        Some(store) if stock_tracking => vec![Stock {
+            store: Location {
+                store_code: store.code.clone(),
+                store_id: store.id.clone(),
+                contact: store.contact.clone(),
+            },
+            quantity: Quantity {
+                quantity_sellable: record.qty.parse::<f32>().unwrap_or(0.0),
+                quantity_unsellable: 0.0,
+                quantity_on_order: 0.0,
+                quantity_allocated: 0.0,
+            },
+        }],
+        _ => vec![],
+    };
+
+    let physical = record.product_type == "simple";
+
+    VariantInformation {
+        name,
+        stock,
+        images: images(record),
+        retail_price: price,
+        marginal_price: price,
+        loyalty_discount: DiscountValue::Absolute(0),
+        variant_code: vec![record.sku.clone()],
+        order_history: vec![],
+        stock_information: StockInformation {
+            stock_group: parent.attribute_set.clone(),
+            sales_group: parent
+                .categories
+                .split(',')
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            value_stream: String::new(),
+            brand: value_of(&attributes, "manufacturer")
+                .or(value_of(&attributes, "brand"))
+                .unwrap_or_default()
+                .to_string(),
+            tax_code: inherit(&record.tax_class, &parent.tax_class),
+            // Weights are in the store's unit, which is not part of the export.
+            weight: weight_in_kilograms(
+                &inherit(&record.weight, &parent.weight),
+                &weight_unit("magento", DEFAULT_WEIGHT_UNIT),
+            )
+            .unwrap_or(0.0)
+            .to_string(),
+            volume: "0.00".to_string(),
+            max_volume: "0.00".to_string(),
+            back_order: !matches!(record.allow_backorders.as_str(), "" | "0" | "No Backorders"),
+            discontinued: record.product_online == "2",
+            non_diminishing: !physical,
+            shippable: physical,
+            size_override_unit: String::new(),
+            size_x_unit: String::new(),
+            size_y_unit: String::new(),
+            size_z_unit: String::new(),
+            size_x: 0.0,
+            size_y: 0.0,
+            size_z: 0.0,
+            min_stock_before_alert: record.notify_on_stock_below.parse::<f64>().unwrap_or(0.0),
+            min_stock_level: 0.0,
+            colli: String::new(),
+        },
+        barcode: barcode.clone(),
+        id: Uuid::new_v4().to_string(),
+        buy_max: -1.0,
+        buy_min: 0.0,
+        identification: identify(&record.sku, &barcode),
+        stock_tracking,
+    }
+}
+
+/// Parses a `customer_main` export. Addresses are exported
+/// separately, and attached by `parse_customer_addresses`.
+pub fn parse_customers(
+    mut reader: Reader<File>,
+    _db: &mut InlineDatabase,
+) -> Result<Vec<Customer>, ParseFailure> {
+    let mut customers: Vec<Customer> = vec![];
+
+    for record in reader.deserialize::<CustomerRecord>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        // Customers of several websites are exported once for each.
+        if customers.iter().any(|customer| {
+            customer
+                .contact
+                .email
+                .full
+                .eq_ignore_ascii_case(&record.email)
+        }) {
+            continue;
+        }
+
+        let name = [&record.first_name, &record.middle_name, &record.last_name]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .map(|part| part.as_str())
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        let mut customer_notes = vec![];
+
+        if !record.website.is_empty() {
+            customer_notes.push(import_note(format!("Website: {}", record.website)));
+        }
+
+        if !record.tax_vat.is_empty() {
+            customer_notes.push(import_note(format!("Tax/VAT number: {}", record.tax_vat)));
+        }
+
+        customers.push(Customer {
+            id: Uuid::new_v4().to_string(),
+            name: name.clone(),
+            contact: ContactInformation {
+                name,
+                mobile: MobileNumber::from(String::new()),
+                email: Email::from(record.email.clone()),
+                landline: String::new(),
+                address: Address {
+                    street: String::new(),
+                    street2: String::new(),
+                    city: String::new(),
+                    country: String::new(),
+                    po_code: String::new(),
+                    lat: 0.0,
+                    lon: 0.0,
+                },
+            },
+            customer_notes,
+            balance: 0,
+            special_pricing: SpecialPricing::tax_exempt(record.group_id == TAX_EXEMPT_GROUP)
+                .to_field(),
+            // Newsletter subscriptions are not part of the export.
+            accepts_marketing: false,
+            created_at: parse_timestamp(&record.created_at).unwrap_or(Utc::now()),
+            updated_at: Utc::now(),
+        });
+    }
+
+    Ok(customers)
+}
+
+/// Parses a `customer_address` export, attaching each address to the
+/// customer with the same email. The default billing address becomes
+/// the customer's contact address, and any others are kept as notes.
+///
+/// No customers are created, so the returned list is always empty.
+pub fn parse_customer_addresses(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Customer>, ParseFailure> {
+    for record in reader.deserialize::<CustomerAddressRecord>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        let customer = match db.1.iter_mut().find(|customer| {
+            customer
+                .contact
+                .email
+                .full
+                .eq_ignore_ascii_case(&record.email)
+        }) {
+            Some(customer) => customer,
+            None => {
+                println!(
+                    "[warn]: No customer with email {} for address {}.",
+                    record.email, record.street
+                );
+                continue;
+            }
+        };
+
+        let mut street = record.street.lines().map(str::trim);
+
+        let address = Address {
+            street: street.next().unwrap_or_default().to_string(),
+            street2: street.collect::<Vec<&str>>().join(", "),
+            city: match (record.city.is_empty(), record.region.is_empty()) {
+                (_, true) => record.city.clone(),
+                (true, false) => record.region.clone(),
+                (false, false) => format!("{}, {}", record.city, record.region),
+            },
+            country: record.country.clone(),
+            po_code: record.postcode.clone(),
+            lat: 0.0,
+            lon: 0.0,
+        };
+
+        if parse_flag(&record.default_billing) {
+            let previous = std::mem::replace(&mut customer.contact.address, address);
+
+            if !record.telephone.is_empty() {
+                customer.contact.mobile = MobileNumber::from(record.telephone.clone());
+                customer.contact.landline = record.telephone.clone();
+            }
+
+            if !record.company.is_empty() && !customer.contact.name.contains(&record.company) {
+                customer.contact.name = format!("{} ({})", customer.name, record.company);
+            }
+
+            if previous.street.is_empty() && previous.city.is_empty() {
+                continue;
+            }
+
+            customer.customer_notes.push(import_note(format!(
+                "Address: {}, {}, {}",
+                previous.street, previous.city, previous.country
+            )));
+        } else {
+            customer.customer_notes.push(import_note(format!(
+                "Address: {}, {}, {}",
+                address.street, address.city, address.country
+            )));
+        }
+    }
+
+    Ok(vec![])
+}
@@ -1,12 +1,29 @@
 #![allow(ambiguous_glob_reexports)]
 
+pub mod amazon;
+pub mod bigcommerce;
+pub mod clover;
+pub mod ebay;
+pub mod ecwid;
+pub mod etsy;
+pub mod hike;
 pub mod lightrail;
+pub mod loyverse;
+pub mod magento;
+pub mod odoo;
+pub mod paypal;
+pub mod prestashop;
+pub mod quickbooks;
 pub mod shopify;
+pub mod stripe;
+pub mod sumup;
+pub mod vend;
+pub mod xero;
+pub mod zettle;
 
 pub use lightrail::*;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-pub use shopify::*;
 
 use crate::{parser::ParseFailure, InlineDatabase};
 
@@ -14,18 +31,30 @@ use strum_macros::{Display, EnumIter};
 
 /// **Linking Hierarchy**
 ///
-/// A store must be present for a product (stock information)
+/// A store must be present for a product (stock information),
+/// and products must be present for the modifiers and categories
+/// which extend them.
 /// For a transaction to take place, there must be customers
-/// to link to, hence the following hierarchy.
+/// to link to, and line items exported apart from their
+/// transactions follow them (as do the payment processor
+/// exports which settle them), hence the following hierarchy.
 ///
-#[derive(Debug, EnumIter, Copy, Clone, Display, Serialize, Deserialize, JsonSchema)]
+#[derive(
+    Debug, EnumIter, Copy, Clone, PartialEq, Eq, Display, Serialize, Deserialize, JsonSchema,
+)]
 pub enum ParseType {
     Store = 0,
     Kiosk = 1,
     Product = 2,
-    Customer = 3,
-    Transaction = 4,
-    Invalid = 5,
+    ProductModifier = 3,
+    ProductCategory = 4,
+    Promotion = 5,
+    Customer = 6,
+    CustomerAddress = 7,
+    GiftCard = 8,
+    Transaction = 9,
+    TransactionItem = 10,
+    Invalid = 11,
 }
 
 pub trait Parsable<R> {
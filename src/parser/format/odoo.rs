@@ -0,0 +1,925 @@
+//! Odoo Point of Sale exports.
+//!
+//! Records are exported with their external IDs, such as
+//! `__export__.product_product_12`, which relational columns (ending in
+//! `/id`) refer to. Product variants (`product.product`) are joined to their
+//! template (`product.template`), and order lines (`pos.order.line`) to their
+//! order (`pos.order`), by these IDs. The IDs are kept on the records they
+//! were read from, so an import can be traced back to its source.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_variant_option, apportion_payment, identify, lowercase_tags, merge_tags, named_contact,
+        parse_flag, parse_tags, resolve_employee, resolve_kiosk, resolve_store,
+        weight_in_kilograms, weight_unit, PaymentKind, PaymentMethodTable, PaymentSplit,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::Reader;
+use open_stock::{
+    Address, ContactInformation, Customer, CustomerType, DiscountValue, Email, FulfillmentStatus,
+    Location, MobileNumber, Note, Order, OrderStatus, OrderStatusAssignment, OrderType, Payment,
+    PaymentAction, PaymentMethod, PaymentProcessor, PaymentStatus, PickStatus, Price, Processable,
+    Product, ProductIdentification, ProductInstance, ProductPurchase, ProductVisibility, Quantity,
+    Stock, StockInformation, Store, Transaction, TransactionCustomer, TransactionType,
+    VariantInformation,
+};
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "id,name,default_code,barcode,categ_id,pos_categ_id,detailed_type,list_price,standard_price,taxes_id,qty_available,weight,description_sale,available_in_pos,active",
+        ParseType::ProductModifier => "id,product_tmpl_id/id,name,default_code,barcode,product_template_attribute_value_ids,lst_price,standard_price,qty_available,weight,active",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "id,name,is_company,email,phone,mobile,street,street2,city,state_id,zip,country_id,vat,category_id,create_date",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "id,name,pos_reference,date_order,partner_id/id,config_id,user_id,amount_tax,amount_total,amount_paid,amount_return,state,payment_ids/payment_method_id",
+        ParseType::TransactionItem => "id,order_id/id,product_id/id,full_product_name,qty,price_unit,discount,price_subtotal_incl",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The specification holding the external ID of a product's template.
+const EXTERNAL_ID: &str = "External ID";
+
+/// The unit weights are exported in unless set otherwise with
+/// `ConversionOptions::set_weight_unit`,
+/// being the unit Odoo databases are created with.
+pub const DEFAULT_WEIGHT_UNIT: &str = "kg";
+
+/// The store standing for the company, whose stock is exported as a whole.
+const COMPANY: &str = "Odoo";
+
+pub static PAYMENT_METHODS: PaymentMethodTable = phf_map! {
+    "cash" => PaymentKind::Cash,
+    "bank" => PaymentKind::Card,
+    "card" => PaymentKind::Card,
+    "customer account" => PaymentKind::StoreCredit,
+    "gift card" => PaymentKind::GiftCard,
+};
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "ODOO-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Records an external ID on the notes of a customer or order.
+fn external_id_note(id: &str) -> Note {
+    import_note(format!("Odoo external ID: {}", id))
+}
+
+fn has_external_id(notes: &[Note], id: &str) -> bool {
+    !id.is_empty()
+        && notes
+            .iter()
+            .any(|note| note.message == external_id_note(id).message)
+}
+
+/// Reads the timestamps of exports, given in UTC as `2023-05-01 10:00:00`.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|timestamp| timestamp.and_utc())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "id")]
+    id: String,
+
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "default_code")]
+    default_code: String,
+
+    #[serde(rename = "barcode")]
+    barcode: String,
+
+    /// The category's full name, such as `All / Saleable / Drinks`.
+    #[serde(rename = "categ_id")]
+    category: String,
+
+    #[serde(rename = "pos_categ_id")]
+    pos_category: String,
+
+    /// `product` (storable), `consu` (consumable) or `service`.
+    #[serde(rename = "detailed_type")]
+    detailed_type: String,
+
+    #[serde(rename = "list_price")]
+    list_price: String,
+
+    #[serde(rename = "standard_price")]
+    standard_price: String,
+
+    #[serde(rename = "taxes_id")]
+    taxes: String,
+
+    #[serde(rename = "qty_available")]
+    qty_available: String,
+
+    #[serde(rename = "weight")]
+    weight: String,
+
+    #[serde(rename = "description_sale")]
+    description: String,
+
+    #[serde(rename = "available_in_pos")]
+    available_in_pos: String,
+
+    #[serde(rename = "active")]
+    active: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VariantRecord {
+    #[serde(rename = "id")]
+    id: String,
+
+    #[serde(rename = "product_tmpl_id/id")]
+    template_id: String,
+
+    #[serde(rename = "default_code")]
+    default_code: String,
+
+    #[serde(rename = "barcode")]
+    barcode: String,
+
+    /// The variant's values, such as `Color: Red,Size: M`.
+    #[serde(rename = "product_template_attribute_value_ids")]
+    attribute_values: String,
+
+    /// The template's price with the variant's extra price.
+    #[serde(rename = "lst_price")]
+    price: String,
+
+    #[serde(rename = "standard_price")]
+    standard_price: String,
+
+    #[serde(rename = "qty_available")]
+    qty_available: String,
+
+    #[serde(rename = "weight")]
+    weight: String,
+
+    #[serde(rename = "active")]
+    active: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerRecord {
+    #[serde(rename = "id")]
+    id: String,
+
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "is_company")]
+    is_company: String,
+
+    #[serde(rename = "email")]
+    email: String,
+
+    #[serde(rename = "phone")]
+    phone: String,
+
+    #[serde(rename = "mobile")]
+    mobile: String,
+
+    #[serde(rename = "street")]
+    street: String,
+
+    #[serde(rename = "street2")]
+    street2: String,
+
+    #[serde(rename = "city")]
+    city: String,
+
+    #[serde(rename = "state_id")]
+    state: String,
+
+    #[serde(rename = "zip")]
+    zip: String,
+
+    #[serde(rename = "country_id")]
+    country: String,
+
+    #[serde(rename = "vat")]
+    vat: String,
+
+    /// The partner's tags.
+    #[serde(rename = "category_id")]
+    tags: String,
+
+    #[serde(rename = "create_date")]
+    create_date: String,
+}
+
+/// An order, whose payments beyond the first are exported
+/// on the rows which follow it, leaving its other columns empty.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionRecord {
+    #[serde(rename = "id")]
+    id: String,
+
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "pos_reference")]
+    pos_reference: String,
+
+    #[serde(rename = "date_order")]
+    date_order: String,
+
+    #[serde(rename = "partner_id/id")]
+    partner_id: String,
+
+    /// The point of sale the order was taken at.
+    #[serde(rename = "config_id")]
+    config: String,
+
+    #[serde(rename = "user_id")]
+    user: String,
+
+    #[serde(rename = "amount_tax")]
+    amount_tax: String,
+
+    #[serde(rename = "amount_total")]
+    amount_total: String,
+
+    #[serde(rename = "amount_paid")]
+    amount_paid: String,
+
+    /// The change given back.
+    #[serde(rename = "amount_return")]
+    amount_return: String,
+
+    /// `draft`, `cancel`, `paid`, `done` or `invoiced`.
+    #[serde(rename = "state")]
+    state: String,
+
+    #[serde(rename = "payment_ids/payment_method_id")]
+    payment_method: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionItemRecord {
+    #[serde(rename = "id")]
+    id: String,
+
+    #[serde(rename = "order_id/id")]
+    order_id: String,
+
+    #[serde(rename = "product_id/id")]
+    product_id: String,
+
+    #[serde(rename = "full_product_name")]
+    product_name: String,
+
+    #[serde(rename = "qty")]
+    quantity: String,
+
+    #[serde(rename = "price_unit")]
+    price_unit: String,
+
+    /// A percentage of the line's price.
+    #[serde(rename = "discount")]
+    discount: String,
+
+    #[serde(rename = "price_subtotal_incl")]
+    subtotal: String,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+fn location(store: &Store) -> Location {
+    Location {
+        store_code: store.code.clone(),
+        store_id: store.id.clone(),
+        contact: store.contact.clone(),
+    }
+}
+
+/// Stock is exported for the company as a whole.
+fn company_store(db: &mut InlineDatabase) -> Option<Store> {
+    resolve_store(COMPANY, db)
+}
+
+fn company_stock(store: &Option<Store>, quantity: &str) -> Vec<Stock> {
+    match store {
+        Some(store) => vec![Stock {
+            store: location(store),
+            quantity: Quantity {
+                quantity_sellable: quantity.parse::<f32>().unwrap_or(0.0),
+                quantity_unsellable: 0.0,
+                quantity_on_order: 0.0,
+                quantity_allocated: 0.0,
+            },
+        }],
+        None => vec![],
+    }
+}
+
+/// The codes of a variant, its internal reference followed by its external ID.
+fn variant_codes(default_code: &str, id: &str) -> Vec<String> {
+    [default_code, id]
+        .into_iter()
+        .filter(|code| !code.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        // Archived templates export their `active` column as false,
+        // those which are sold at the point of sale are shown.
+        let active = cloned.active.is_empty() || parse_flag(&cloned.active);
+        let published = parse_flag(&cloned.available_in_pos);
+        let price = cloned.list_price.parse::<f32>().unwrap_or(0.0);
+        let storable = cloned.detailed_type == "product";
+        let store = company_store(db);
+
+        // Categories are named by their path, such as `All / Saleable`.
+        let mut tags = parse_tags(&cloned.category, '/', lowercase_tags());
+        tags.retain(|tag| !tag.eq_ignore_ascii_case("All"));
+        merge_tags(
+            &mut tags,
+            [cloned.pos_category.trim().to_string()],
+            lowercase_tags(),
+        );
+
+        Ok(Product {
+            name: cloned.name.clone(),
+            company: String::new(),
+            variant_groups: vec![],
+            variants: vec![VariantInformation {
+                name: cloned.name.clone(),
+                stock: if storable {
+                    company_stock(&store, &cloned.qty_available)
+                } else {
+                    vec![]
+                },
+                images: vec![],
+                retail_price: price,
+                marginal_price: cloned.standard_price.parse::<f32>().unwrap_or(price),
+                loyalty_discount: DiscountValue::Absolute(0),
+                variant_code: variant_codes(&cloned.default_code, ""),
+                order_history: vec![],
+                stock_information: StockInformation {
+                    stock_group: cloned.detailed_type.clone(),
+                    sales_group: cloned.pos_category.clone(),
+                    value_stream: String::new(),
+                    brand: String::new(),
+                    tax_code: cloned.taxes.clone(),
+                    weight: weight_in_kilograms(
+                        &cloned.weight,
+                        &weight_unit("odoo", DEFAULT_WEIGHT_UNIT),
+                    )
+                    .unwrap_or(0.0)
+                    .to_string(),
+                    volume: "0.00".to_string(),
+                    max_volume: "0.00".to_string(),
+                    back_order: false,
+                    discontinued: !active,
+                    non_diminishing: cloned.detailed_type == "service",
+                    shippable: cloned.detailed_type != "service",
+                    size_override_unit: String::new(),
+                    size_x_unit: String::new(),
+                    size_y_unit: String::new(),
+                    size_z_unit: String::new(),
+                    size_x: 0.0,
+                    size_y: 0.0,
+                    size_z: 0.0,
+                    min_stock_before_alert: 0.0,
+                    min_stock_level: 0.0,
+                    colli: String::new(),
+                },
+                barcode: cloned.barcode.clone(),
+                id: Uuid::new_v4().to_string(),
+                buy_max: -1.0,
+                buy_min: 1.0,
+                identification: identify(&cloned.default_code, &cloned.barcode),
+                stock_tracking: storable,
+            }],
+            sku: cloned.default_code.clone(),
+            images: vec![],
+            tags,
+            description: cloned.description.clone(),
+            specifications: vec![(EXTERNAL_ID.to_string(), cloned.id.clone())],
+            name_long: cloned.name.clone(),
+            identification: ProductIdentification {
+                sku: cloned.default_code.clone(),
+                ..Default::default()
+            },
+            description_long: cloned.description.clone(),
+            visible: if active && published {
+                ProductVisibility::ShowWhenInStock
+            } else {
+                ProductVisibility::AlwaysHidden
+            },
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+/// Parses a variants export, replacing the variant of each template
+/// with the variants which refer to it by its external ID.
+///
+/// No products are created, so the returned list is always empty.
+pub fn parse_variants(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let store = company_store(db);
+
+    // The templates whose own variant has been replaced.
+    let mut joined: Vec<String> = vec![];
+
+    for record in reader.deserialize::<VariantRecord>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        let product = match db.0.iter_mut().find(|product| {
+            product
+                .specifications
+                .iter()
+                .any(|(name, value)| name == EXTERNAL_ID && *value == record.template_id)
+        }) {
+            Some(product) => product,
+            None => {
+                println!(
+                    "[warn]: Template {} of variant {} is not in the export.",
+                    record.template_id, record.id
+                );
+                continue;
+            }
+        };
+
+        let base = match product.variants.first() {
+            Some(variant) => variant.clone(),
+            None => continue,
+        };
+
+        if !joined.contains(&record.template_id) {
+            joined.push(record.template_id.clone());
+            product.variants.clear();
+        }
+
+        let values: Vec<(String, String)> = record
+            .attribute_values
+            .split(',')
+            .filter_map(|value| value.split_once(':'))
+            .map(|(attribute, value)| (attribute.trim().to_string(), value.trim().to_string()))
+            .collect();
+
+        for (attribute, value) in &values {
+            add_variant_option(product, attribute, value);
+        }
+
+        let name = values
+            .iter()
+            .map(|(_, value)| value.as_str())
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        let mut variant = base.clone();
+
+        variant.id = Uuid::new_v4().to_string();
+        variant.name = if name.is_empty() {
+            product.name.clone()
+        } else {
+            name
+        };
+        variant.retail_price = record.price.parse::<f32>().unwrap_or(base.retail_price);
+        variant.marginal_price = record
+            .standard_price
+            .parse::<f32>()
+            .unwrap_or(base.marginal_price);
+        variant.variant_code = variant_codes(&record.default_code, &record.id);
+        variant.barcode = record.barcode.clone();
+        variant.identification = identify(&record.default_code, &record.barcode);
+
+        if base.stock_tracking {
+            variant.stock = company_stock(&store, &record.qty_available);
+        }
+
+        if let Some(weight) =
+            weight_in_kilograms(&record.weight, &weight_unit("odoo", DEFAULT_WEIGHT_UNIT))
+        {
+            variant.stock_information.weight = weight.to_string();
+        }
+
+        if !record.active.is_empty() && !parse_flag(&record.active) {
+            variant.stock_information.discontinued = true;
+        }
+
+        product.variants.push(variant);
+    }
+
+    Ok(vec![])
+}
+
+impl Parsable<CustomerRecord> for Customer {
+    fn parse_individual(
+        reader: &[Result<CustomerRecord, csv::Error>],
+        line: &mut usize,
+        _db: &mut InlineDatabase,
+    ) -> Result<Customer, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let city = match (cloned.city.is_empty(), cloned.state.is_empty()) {
+            (_, true) => cloned.city.clone(),
+            (true, false) => cloned.state.clone(),
+            (false, false) => format!("{}, {}", cloned.city, cloned.state),
+        };
+
+        let mut customer_notes = vec![external_id_note(&cloned.id)];
+
+        if parse_flag(&cloned.is_company) {
+            customer_notes.push(import_note("Company".to_string()));
+        }
+
+        if !cloned.vat.is_empty() {
+            customer_notes.push(import_note(format!("Tax ID: {}", cloned.vat)));
+        }
+
+        if !cloned.tags.is_empty() {
+            customer_notes.push(import_note(format!("Tags: {}", cloned.tags)));
+        }
+
+        Ok(Customer {
+            id: Uuid::new_v4().to_string(),
+            name: cloned.name.clone(),
+            contact: ContactInformation {
+                name: cloned.name.clone(),
+                mobile: MobileNumber::from(if cloned.mobile.is_empty() {
+                    cloned.phone.clone()
+                } else {
+                    cloned.mobile.clone()
+                }),
+                email: Email::from(cloned.email.clone()),
+                landline: if cloned.mobile.is_empty() {
+                    String::new()
+                } else {
+                    cloned.phone.clone()
+                },
+                address: Address {
+                    street: cloned.street.clone(),
+                    street2: cloned.street2.clone(),
+                    city,
+                    country: cloned.country.clone(),
+                    po_code: cloned.zip.clone(),
+                    lat: 0.0,
+                    lon: 0.0,
+                },
+            },
+            customer_notes,
+            balance: 0,
+            special_pricing: String::new(),
+            accepts_marketing: false,
+            created_at: parse_timestamp(&cloned.create_date).unwrap_or(Utc::now()),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+impl Parsable<TransactionRecord> for Transaction {
+    fn parse_individual(
+        reader: &[Result<TransactionRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Transaction, ParseFailure> {
+        let order = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        // The rows of further payments follow the order, without an ID.
+        let methods: Vec<String> = std::iter::once(order)
+            .chain(
+                reader[*line..]
+                    .iter()
+                    .map_while(|row| row.as_ref().ok())
+                    .take_while(|row| row.id.is_empty()),
+            )
+            .map(|row| row.payment_method.trim().to_string())
+            .filter(|method| !method.is_empty())
+            .collect();
+
+        *line += reader[*line..]
+            .iter()
+            .map_while(|row| row.as_ref().ok())
+            .take_while(|row| row.id.is_empty())
+            .count();
+
+        if order.id.is_empty() {
+            return Err(ParseFailure::ReadFailure(format!(
+                "Order {} has no external ID.",
+                order.name
+            )));
+        }
+
+        let date = parse_timestamp(&order.date_order).unwrap_or(Utc::now());
+        let store = company_store(db);
+        let kiosk = store
+            .as_ref()
+            .and_then(|store| resolve_kiosk(&order.config, store, db));
+        let employee = resolve_employee(&order.user, db);
+        let customer =
+            db.1.iter()
+                .find(|customer| has_external_id(&customer.customer_notes, &order.partner_id))
+                .cloned();
+
+        let origin = match &store {
+            Some(store) => location(store),
+            None => Location {
+                contact: named_contact(&order.config),
+                store_code: "000".to_string(),
+                store_id: "000".to_string(),
+            },
+        };
+
+        let total = order.amount_total.parse::<f32>().unwrap_or(0.0);
+        let paid = matches!(order.state.as_str(), "paid" | "done" | "invoiced");
+
+        // The amount paid includes the change given back.
+        let tendered = order.amount_paid.parse::<f32>().unwrap_or(total)
+            - order.amount_return.parse::<f32>().unwrap_or(0.0);
+
+        let splits: Vec<PaymentSplit> = methods
+            .iter()
+            .map(|method| PaymentSplit::new(method, None, &PAYMENT_METHODS))
+            .collect();
+
+        // Unpaid orders carry no payment.
+        let tenders = if !paid {
+            vec![]
+        } else {
+            match apportion_payment(&splits, tendered) {
+                Some(tenders) if !tenders.is_empty() => tenders,
+                _ => vec![(PaymentMethod::Other(methods.join(", ")), tendered)],
+            }
+        };
+
+        let payment = tenders
+            .into_iter()
+            .map(|(payment_method, amount)| Payment {
+                id: Uuid::new_v4().to_string(),
+                payment_method,
+                fulfillment_date: date,
+                amount: Price {
+                    quantity: amount,
+                    currency: String::new(),
+                },
+                processing_fee: Price {
+                    quantity: 0.0,
+                    currency: String::new(),
+                },
+                status: PaymentStatus::Complete(Processable::Anonymous(String::from("odoo"))),
+                processor: PaymentProcessor::anonymous(String::from("odoo")),
+                order_ids: vec![],
+                delay_action: PaymentAction::Complete,
+                delay_duration: String::new(),
+            })
+            .collect();
+
+        let mut order_notes = vec![external_id_note(&order.id)];
+
+        if !order.pos_reference.is_empty() {
+            order_notes.push(import_note(format!("Receipt: {}", order.pos_reference)));
+        }
+
+        Ok(Transaction {
+            id: Uuid::new_v4().to_string(),
+            customer: TransactionCustomer {
+                customer_type: CustomerType::Individual,
+                customer_id: customer
+                    .as_ref()
+                    .map_or(String::new(), |customer| customer.id.clone()),
+            },
+            kiosk: kiosk.map_or(String::new(), |kiosk| kiosk.id),
+            // Refunds are exported as orders of a negative total.
+            transaction_type: if total < 0.0 {
+                TransactionType::In
+            } else {
+                TransactionType::Out
+            },
+            products: vec![Order {
+                id: Uuid::new_v4().to_string(),
+                destination: Location {
+                    contact: customer
+                        .as_ref()
+                        .map_or(origin.contact.clone(), |customer| customer.contact.clone()),
+                    store_code: origin.store_code.clone(),
+                    store_id: origin.store_id.clone(),
+                },
+                origin,
+                products: vec![],
+                status: OrderStatusAssignment {
+                    status: match order.state.as_str() {
+                        "cancel" => OrderStatus::Failed("Cancelled".to_string()),
+                        "draft" => OrderStatus::Queued(date),
+                        _ => OrderStatus::Fulfilled(date),
+                    },
+                    assigned_products: vec![],
+                    timestamp: date,
+                },
+                status_history: vec![],
+                order_history: vec![],
+                previous_failed_fulfillment_attempts: vec![],
+                order_notes: order_notes.clone(),
+                reference: order.name.clone(),
+                creation_date: date,
+                discount: DiscountValue::Absolute(0),
+                order_type: OrderType::Direct,
+            }],
+            order_total: total.round() as i64,
+            payment,
+            order_date: date,
+            order_notes,
+            salesperson: employee.map_or(String::new(), |employee| employee.id),
+            created_at: date,
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+/// Parses an order lines export, adding each line to the order
+/// which it refers to by its external ID.
+///
+/// No transactions are created, so the returned list is always empty.
+pub fn parse_order_lines(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Transaction>, ParseFailure> {
+    for record in reader.deserialize::<TransactionItemRecord>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        // Lines refer to the variant sold, whose external ID is kept
+        // as one of its codes. Templates exported without their variants
+        // are found by name instead.
+        let (product, variant) = match db.0.iter().find_map(|product| {
+            product
+                .variants
+                .iter()
+                .find(|variant| variant.variant_code.contains(&record.product_id))
+                .map(|variant| (product, variant))
+        }) {
+            Some((product, variant)) => (Some(product), Some(variant)),
+            None => {
+                let product =
+                    db.0.iter()
+                        .find(|product| product.name == record.product_name);
+
+                (
+                    product,
+                    product.and_then(|product| product.variants.first()),
+                )
+            }
+        };
+
+        let code = variant
+            .and_then(|variant| variant.variant_code.first().cloned())
+            .unwrap_or(record.product_id.clone());
+
+        let order = match db
+            .2
+            .iter_mut()
+            .flat_map(|transaction| transaction.products.iter_mut())
+            .find(|order| has_external_id(&order.order_notes, &record.order_id))
+        {
+            Some(order) => order,
+            None => {
+                println!(
+                    "[warn]: Order {} of line {} is not in the export.",
+                    record.order_id, record.id
+                );
+                continue;
+            }
+        };
+
+        let quantity = record.quantity.parse::<f32>().unwrap_or(1.0);
+
+        order.products.push(ProductPurchase {
+            id: Uuid::new_v4().to_string(),
+            product_code: code.clone(),
+            product_name: product
+                .map_or(record.product_name.clone(), |product| product.name.clone()),
+            product_sku: product.map_or(code, |product| product.sku.clone()),
+            product_cost: record.price_unit.parse::<f32>().unwrap_or(0.0),
+            discount: DiscountValue::Percentage(
+                record.discount.parse::<f32>().unwrap_or(0.0).round() as u32,
+            ),
+            product_variant_name: record.product_name.clone(),
+            quantity: quantity.abs(),
+            tags: vec![],
+            // Returns are exported as lines of a negative quantity.
+            transaction_type: if quantity < 0.0 {
+                TransactionType::In
+            } else {
+                TransactionType::Out
+            },
+            instances: (0..quantity.abs().ceil() as u32)
+                .map(|_| ProductInstance {
+                    id: Uuid::new_v4().to_string(),
+                    fulfillment_status: FulfillmentStatus {
+                        pick_status: PickStatus::Picked,
+                        pick_history: vec![],
+                        last_updated: order.creation_date,
+                        notes: vec![],
+                    },
+                })
+                .collect(),
+        });
+    }
+
+    Ok(vec![])
+}
@@ -0,0 +1,245 @@
+//! PayPal activity downloads.
+//!
+//! The activity of a PayPal business account holds every movement of its
+//! balance, of which only payments received for an order (by its invoice
+//! number) and their refunds are read. Payments which were denied, reversed
+//! or refunded are kept without settling the order. Times are read as given,
+//! in the time zone the account is set to.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{add_payment, find_transaction, resolve_payment},
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::Reader;
+use open_stock::{
+    Note, PaymentAction, PaymentMethod, PaymentProcessor, PaymentStatus, Processable, Transaction,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+
+use super::ParseType;
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "ZZZZ",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "ZZZZ",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "ZZZZ",
+        ParseType::TransactionItem => "\"Date\",\"Time\",\"TimeZone\",\"Name\",\"Type\",\"Status\",\"Currency\",\"Gross\",\"Fee\",\"Net\",\"From Email Address\",\"To Email Address\",\"Transaction ID\",\"Shipping Address\",\"Address Status\",\"Item Title\",\"Item ID\",\"Shipping and Handling Amount\",\"Insurance Amount\",\"Sales Tax\",\"Reference Txn ID\",\"Invoice Number\",\"Custom Number\",\"Quantity\",\"Receipt ID\",\"Balance\",\"Contact Phone Number\",\"Subject\",\"Note\",\"Balance Impact\"",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "PAYPAL-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Reads the date and time of an activity, given as `01/15/2024` and `10:32:05`.
+fn parse_timestamp(date: &str, time: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(
+        &format!("{} {}", date.trim(), time.trim()),
+        "%m/%d/%Y %H:%M:%S",
+    )
+    .ok()
+    .map(|timestamp| timestamp.and_utc())
+}
+
+/// Reads an amount, as exported with thousands separators such as `1,250.00`.
+fn parse_amount(value: &str) -> f32 {
+    value.trim().replace(',', "").parse::<f32>().unwrap_or(0.0)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ActivityRecord {
+    #[serde(rename = "Date")]
+    date: String,
+
+    #[serde(rename = "Time")]
+    time: String,
+
+    /// Such as `Express Checkout Payment` or `Payment Refund`.
+    #[serde(rename = "Type")]
+    activity_type: String,
+
+    /// `Completed`, `Pending`, `Denied`, `Reversed` or `Refunded`.
+    #[serde(rename = "Status")]
+    status: String,
+
+    #[serde(rename = "Currency")]
+    currency: String,
+
+    #[serde(rename = "Gross")]
+    gross: String,
+
+    /// Taken from the balance, hence negative.
+    #[serde(rename = "Fee")]
+    fee: String,
+
+    #[serde(rename = "Transaction ID")]
+    transaction_id: String,
+
+    /// The payment a refund returns.
+    #[serde(rename = "Reference Txn ID")]
+    reference_transaction_id: String,
+
+    /// The order reference the store sends PayPal.
+    #[serde(rename = "Invoice Number")]
+    invoice_number: String,
+
+    /// `Credit` or `Debit`.
+    #[serde(rename = "Balance Impact")]
+    balance_impact: String,
+}
+
+impl ActivityRecord {
+    fn is_refund(&self) -> bool {
+        self.activity_type.to_lowercase().contains("refund")
+    }
+
+    fn is_payment(&self) -> bool {
+        self.activity_type.to_lowercase().contains("payment")
+            && self.balance_impact.eq_ignore_ascii_case("Credit")
+            && !self.is_refund()
+    }
+}
+
+/// Settles the payments of the transactions already imported with the
+/// payments received for them, and notes their refunds. Payments which were
+/// denied, reversed or refunded are added to their transaction without
+/// settling it.
+pub fn parse_activity(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Transaction>, ParseFailure> {
+    for record in reader.deserialize::<ActivityRecord>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        // Transfers, conversions and holds do not belong to an order.
+        if !record.is_payment() && !record.is_refund() {
+            continue;
+        }
+
+        let found = if record.invoice_number.is_empty() {
+            // Refunds may only name the payment they return.
+            db.2.iter_mut().find(|transaction| {
+                !record.reference_transaction_id.is_empty()
+                    && transaction
+                        .payment
+                        .iter()
+                        .any(|payment| payment.processor.token == record.reference_transaction_id)
+            })
+        } else {
+            find_transaction(&record.invoice_number, db)
+        };
+
+        let transaction = match found {
+            Some(transaction) => transaction,
+            None => {
+                println!(
+                    "[warn]: Order {} of PayPal transaction {} is not in the export.",
+                    record.invoice_number, record.transaction_id
+                );
+                continue;
+            }
+        };
+
+        let status = record.status.to_lowercase();
+        let currency = record.currency.to_uppercase();
+        let gross = parse_amount(&record.gross).abs();
+
+        if record.is_refund() {
+            transaction.order_notes.push(import_note(format!(
+                "Refunded {:.2} {} in PayPal transaction {}",
+                gross, currency, record.transaction_id
+            )));
+            continue;
+        }
+
+        let failed = status == "denied" || status == "reversed" || status == "failed";
+
+        if failed {
+            transaction.order_notes.push(import_note(format!(
+                "PayPal transaction {} of {:.2} {} was {}",
+                record.transaction_id, gross, currency, status
+            )));
+        }
+
+        // Payments which never paid for the order settle none of its payments.
+        let payment = if failed || status == "refunded" {
+            add_payment(
+                transaction,
+                PaymentMethod::Transfer,
+                gross,
+                &currency,
+                "paypal",
+            )
+        } else {
+            resolve_payment(
+                transaction,
+                PaymentMethod::Transfer,
+                gross,
+                &currency,
+                "paypal",
+            )
+        };
+
+        if let Some(paid) = parse_timestamp(&record.date, &record.time) {
+            payment.fulfillment_date = paid;
+        }
+
+        if payment.amount.currency.is_empty() {
+            payment.amount.currency = currency.clone();
+        }
+
+        payment.processing_fee.quantity = parse_amount(&record.fee).abs();
+        payment.processing_fee.currency = currency.clone();
+        payment.processor = PaymentProcessor {
+            token: record.transaction_id.clone(),
+            ..PaymentProcessor::anonymous(String::from("paypal"))
+        };
+
+        (payment.status, payment.delay_action) = if status == "pending" {
+            (
+                PaymentStatus::Pending(String::from("paypal")),
+                PaymentAction::RequireFurtherAction,
+            )
+        } else if failed {
+            (
+                PaymentStatus::Failed(Processable::Anonymous(String::from("paypal"))),
+                PaymentAction::Cancel,
+            )
+        } else if status == "refunded" {
+            (
+                PaymentStatus::Unfulfilled(String::from("paypal")),
+                PaymentAction::Cancel,
+            )
+        } else {
+            (
+                PaymentStatus::Complete(Processable::Anonymous(String::from("paypal"))),
+                PaymentAction::Complete,
+            )
+        };
+    }
+
+    Ok(vec![])
+}
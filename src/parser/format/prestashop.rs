@@ -0,0 +1,962 @@
+//! PrestaShop exports, which are separated by semicolons.
+//!
+//! Products and their combinations are exported as separate files in the
+//! layout of PrestaShop's CSV import. Combinations are joined to their
+//! product by its reference, replacing the product's own variant.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_variant_option, identify, lowercase_tags, match_payment_method, merge_tags,
+        named_contact, parse_flag, parse_tags, resolve_store, weight_in_kilograms, weight_unit,
+        PaymentKind, PaymentMethodTable, ProductStatus,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::Reader;
+use open_stock::{
+    Address, ContactInformation, Customer, CustomerType, DiscountValue, Email, Location,
+    MobileNumber, Note, Order, OrderStatus, OrderStatusAssignment, OrderType, Payment,
+    PaymentAction, PaymentProcessor, PaymentStatus, Price, Processable, Product,
+    ProductIdentification, Quantity, Stock, StockInformation, Store, Transaction,
+    TransactionCustomer, TransactionType, TransitInformation, VariantInformation,
+};
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "Product ID;Active (0/1);Name *;Categories (x,y,z...);Price tax excluded;Tax rules ID;Wholesale price;On sale (0/1);Reference #;Supplier reference #;Supplier;Manufacturer;EAN13;UPC;Width;Height;Depth;Weight;Quantity;Minimal quantity;Low stock level;Visibility;Summary;Description;Tags (x,y,z...);Image URLs (x,y,z...);Available for order (0/1);Condition",
+        ParseType::ProductModifier => "Product ID*;Product Reference;Attribute (Name:Type:Position)*;Value (Value:Position)*;Supplier reference;Reference;EAN13;UPC;Wholesale price;Impact on price;Quantity;Minimal quantity;Low stock level;Impact on weight;Default (0 = No, 1 = Yes);Image URLs (x,y,z...)",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "Customer ID;Active (0/1);Titles ID (Mr = 1, Ms = 2, else 0);Email *;Password *;Birthday (yyyy-mm-dd);Last Name *;First Name *;Newsletter (0/1);Opt-in (0/1);Registration date (yyyy-mm-dd);Groups (x,y,z...);Default group ID",
+        ParseType::CustomerAddress => "Address ID;Alias *;Active (0/1);Customer e-mail *;Customer ID;Manufacturer;Supplier;Company;Lastname *;Firstname *;Address 1 *;Address 2;Zipcode*;City *;Country *;State;Other;Phone;Mobile Phone;VAT number;DNI",
+        ParseType::Transaction => "ID;Reference;New client;Delivery;Customer;Total;Payment;Status;Date",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The unit weights are exported in unless set otherwise with
+/// `ConversionOptions::set_weight_unit`,
+/// being the unit PrestaShop shops are installed with.
+pub const DEFAULT_WEIGHT_UNIT: &str = "kg";
+
+/// The store standing for the shop, whose stock is exported as a whole.
+const SHOP: &str = "PrestaShop";
+
+/// Keyed by the names of PrestaShop's bundled payment modules.
+pub static PAYMENT_METHODS: PaymentMethodTable = phf_map! {
+    "bank wire" => PaymentKind::Transfer,
+    "bank transfer" => PaymentKind::Transfer,
+    "pay by check" => PaymentKind::Transfer,
+    "cash on delivery" => PaymentKind::Cash,
+    "cash on delivery (cod)" => PaymentKind::Cash,
+    "payments by card" => PaymentKind::Card,
+    "card" => PaymentKind::Card,
+    "stripe" => PaymentKind::Card,
+    "paypal" => PaymentKind::Transfer,
+};
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "PRESTASHOP-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Reads the timestamps of exports, given as `2023-05-01 10:00:00`
+/// or as a date alone, in the shop's time zone.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .map(|timestamp| timestamp.and_utc())
+}
+
+/// Reads the comma separated image URLs of a product or combination.
+fn parse_images(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+        .collect()
+}
+
+/// Reads a list of `Name:Position` (or `Name:Type:Position`) entries,
+/// as the attributes and values of a combination are exported, keeping
+/// only the names.
+fn parse_positioned(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|entry| {
+            entry
+                .split(':')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        })
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "Product ID")]
+    product_id: String,
+
+    #[serde(rename = "Active (0/1)")]
+    active: String,
+
+    #[serde(rename = "Name *")]
+    name: String,
+
+    #[serde(rename = "Categories (x,y,z...)")]
+    categories: String,
+
+    #[serde(rename = "Price tax excluded")]
+    price: String,
+
+    #[serde(rename = "Tax rules ID")]
+    tax_rules_id: String,
+
+    #[serde(rename = "Wholesale price")]
+    wholesale_price: String,
+
+    #[serde(rename = "Reference #")]
+    reference: String,
+
+    #[serde(rename = "Supplier")]
+    supplier: String,
+
+    #[serde(rename = "Manufacturer")]
+    manufacturer: String,
+
+    #[serde(rename = "EAN13")]
+    ean13: String,
+
+    #[serde(rename = "UPC")]
+    upc: String,
+
+    #[serde(rename = "Width")]
+    width: String,
+
+    #[serde(rename = "Height")]
+    height: String,
+
+    #[serde(rename = "Depth")]
+    depth: String,
+
+    #[serde(rename = "Weight")]
+    weight: String,
+
+    #[serde(rename = "Quantity")]
+    quantity: String,
+
+    #[serde(rename = "Minimal quantity")]
+    minimal_quantity: String,
+
+    #[serde(rename = "Low stock level")]
+    low_stock_level: String,
+
+    /// `both`, `catalog`, `search` or `none`.
+    #[serde(rename = "Visibility")]
+    visibility: String,
+
+    #[serde(rename = "Summary")]
+    summary: String,
+
+    #[serde(rename = "Description")]
+    description: String,
+
+    #[serde(rename = "Tags (x,y,z...)")]
+    tags: String,
+
+    #[serde(rename = "Image URLs (x,y,z...)")]
+    images: String,
+
+    #[serde(rename = "Available for order (0/1)")]
+    available_for_order: String,
+
+    #[serde(rename = "Condition")]
+    condition: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CombinationRecord {
+    #[serde(rename = "Product ID*")]
+    product_id: String,
+
+    #[serde(rename = "Product Reference")]
+    product_reference: String,
+
+    #[serde(rename = "Attribute (Name:Type:Position)*")]
+    attributes: String,
+
+    #[serde(rename = "Value (Value:Position)*")]
+    values: String,
+
+    #[serde(rename = "Reference")]
+    reference: String,
+
+    #[serde(rename = "EAN13")]
+    ean13: String,
+
+    #[serde(rename = "UPC")]
+    upc: String,
+
+    #[serde(rename = "Wholesale price")]
+    wholesale_price: String,
+
+    /// The amount added to (or taken from) the product's price.
+    #[serde(rename = "Impact on price")]
+    impact_on_price: String,
+
+    #[serde(rename = "Quantity")]
+    quantity: String,
+
+    #[serde(rename = "Minimal quantity")]
+    minimal_quantity: String,
+
+    #[serde(rename = "Low stock level")]
+    low_stock_level: String,
+
+    #[serde(rename = "Impact on weight")]
+    impact_on_weight: String,
+
+    #[serde(rename = "Image URLs (x,y,z...)")]
+    images: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerRecord {
+    #[serde(rename = "Customer ID")]
+    customer_id: String,
+
+    #[serde(rename = "Email *")]
+    email: String,
+
+    #[serde(rename = "Birthday (yyyy-mm-dd)")]
+    birthday: String,
+
+    #[serde(rename = "Last Name *")]
+    last_name: String,
+
+    #[serde(rename = "First Name *")]
+    first_name: String,
+
+    #[serde(rename = "Newsletter (0/1)")]
+    newsletter: String,
+
+    #[serde(rename = "Opt-in (0/1)")]
+    opt_in: String,
+
+    #[serde(rename = "Registration date (yyyy-mm-dd)")]
+    registration_date: String,
+
+    #[serde(rename = "Groups (x,y,z...)")]
+    groups: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerAddressRecord {
+    #[serde(rename = "Alias *")]
+    alias: String,
+
+    #[serde(rename = "Active (0/1)")]
+    active: String,
+
+    #[serde(rename = "Customer e-mail *")]
+    email: String,
+
+    #[serde(rename = "Company")]
+    company: String,
+
+    #[serde(rename = "Address 1 *")]
+    address1: String,
+
+    #[serde(rename = "Address 2")]
+    address2: String,
+
+    #[serde(rename = "Zipcode*")]
+    zipcode: String,
+
+    #[serde(rename = "City *")]
+    city: String,
+
+    #[serde(rename = "Country *")]
+    country: String,
+
+    #[serde(rename = "State")]
+    state: String,
+
+    #[serde(rename = "Phone")]
+    phone: String,
+
+    #[serde(rename = "Mobile Phone")]
+    mobile_phone: String,
+}
+
+/// A row of the orders list, which holds no line items.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionRecord {
+    #[serde(rename = "ID")]
+    id: String,
+
+    #[serde(rename = "Reference")]
+    reference: String,
+
+    /// The country the order is delivered to.
+    #[serde(rename = "Delivery")]
+    delivery: String,
+
+    #[serde(rename = "Customer")]
+    customer: String,
+
+    #[serde(rename = "Total")]
+    total: String,
+
+    #[serde(rename = "Payment")]
+    payment: String,
+
+    #[serde(rename = "Status")]
+    status: String,
+
+    #[serde(rename = "Date")]
+    date: String,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+fn location(store: &Store) -> Location {
+    Location {
+        store_code: store.code.clone(),
+        store_id: store.id.clone(),
+        contact: store.contact.clone(),
+    }
+}
+
+/// Stock is exported for the shop as a whole.
+fn shop_store(db: &mut InlineDatabase) -> Option<Store> {
+    resolve_store(SHOP, db)
+}
+
+fn shop_stock(store: &Option<Store>, quantity: &str) -> Vec<Stock> {
+    match store {
+        Some(store) => vec![Stock {
+            store: location(store),
+            quantity: Quantity {
+                quantity_sellable: quantity.parse::<f32>().unwrap_or(0.0),
+                quantity_unsellable: 0.0,
+                quantity_on_order: 0.0,
+                quantity_allocated: 0.0,
+            },
+        }],
+        None => vec![],
+    }
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let status = if parse_flag(&cloned.active) {
+            ProductStatus::Active
+        } else {
+            ProductStatus::Archived
+        };
+
+        let price = cloned.price.parse::<f32>().unwrap_or(0.0);
+        let barcode = if cloned.ean13.is_empty() {
+            cloned.upc.clone()
+        } else {
+            cloned.ean13.clone()
+        };
+
+        let mut tags = parse_tags(&cloned.categories, ',', lowercase_tags());
+        merge_tags(
+            &mut tags,
+            parse_tags(&cloned.tags, ',', lowercase_tags()),
+            lowercase_tags(),
+        );
+
+        let mut specifications = vec![];
+
+        for (name, value) in [
+            ("Supplier", &cloned.supplier),
+            ("Condition", &cloned.condition),
+        ] {
+            if !value.is_empty() {
+                specifications.push((name.to_string(), value.clone()));
+            }
+        }
+
+        // Products which are available to view but not to order are being
+        // phased out, as are those hidden from both the catalog and search.
+        let orderable =
+            cloned.available_for_order.is_empty() || parse_flag(&cloned.available_for_order);
+        let published = cloned.visibility != "none";
+
+        let store = shop_store(db);
+        let dimension = |value: &str| value.parse::<f64>().unwrap_or(0.0);
+
+        Ok(Product {
+            name: cloned.name.clone(),
+            company: cloned.manufacturer.clone(),
+            variant_groups: vec![],
+            variants: vec![VariantInformation {
+                name: cloned.name.clone(),
+                stock: shop_stock(&store, &cloned.quantity),
+                images: vec![],
+                retail_price: price,
+                marginal_price: cloned.wholesale_price.parse::<f32>().unwrap_or(price),
+                loyalty_discount: DiscountValue::Absolute(0),
+                variant_code: vec![cloned.reference.clone()],
+                order_history: vec![],
+                stock_information: StockInformation {
+                    stock_group: String::new(),
+                    sales_group: cloned
+                        .categories
+                        .split(',')
+                        .map(str::trim)
+                        .find(|category| !category.is_empty())
+                        .unwrap_or_default()
+                        .to_string(),
+                    value_stream: String::new(),
+                    brand: cloned.manufacturer.clone(),
+                    tax_code: cloned.tax_rules_id.clone(),
+                    weight: weight_in_kilograms(
+                        &cloned.weight,
+                        &weight_unit("prestashop", DEFAULT_WEIGHT_UNIT),
+                    )
+                    .unwrap_or(0.0)
+                    .to_string(),
+                    volume: "0.00".to_string(),
+                    max_volume: "0.00".to_string(),
+                    back_order: false,
+                    discontinued: status.discontinued() || !orderable,
+                    non_diminishing: false,
+                    shippable: true,
+                    size_override_unit: String::new(),
+                    size_x_unit: String::new(),
+                    size_y_unit: String::new(),
+                    size_z_unit: String::new(),
+                    size_x: dimension(&cloned.width),
+                    size_y: dimension(&cloned.height),
+                    size_z: dimension(&cloned.depth),
+                    min_stock_before_alert: dimension(&cloned.low_stock_level),
+                    min_stock_level: 0.0,
+                    colli: String::new(),
+                },
+                barcode: barcode.clone(),
+                id: Uuid::new_v4().to_string(),
+                buy_max: -1.0,
+                buy_min: cloned.minimal_quantity.parse::<f64>().unwrap_or(1.0),
+                identification: identify(&cloned.reference, &barcode),
+                stock_tracking: true,
+            }],
+            sku: cloned.reference.clone(),
+            images: parse_images(&cloned.images),
+            tags,
+            description: cloned.summary.clone(),
+            specifications,
+            name_long: cloned.name.clone(),
+            identification: ProductIdentification {
+                sku: cloned.reference.clone(),
+                ..Default::default()
+            },
+            description_long: cloned.description.clone(),
+            visible: status.visibility(published),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+/// Parses a combinations export, replacing the variant of each product
+/// which has combinations with a variant for each of them. Combinations
+/// name their product by its reference.
+///
+/// No products are created, so the returned list is always empty.
+pub fn parse_combinations(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let store = shop_store(db);
+
+    // The products whose own variant has been replaced.
+    let mut combined: Vec<String> = vec![];
+
+    for record in reader.deserialize::<CombinationRecord>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        let product = match db
+            .0
+            .iter_mut()
+            .find(|product| !product.sku.is_empty() && product.sku == record.product_reference)
+        {
+            Some(product) => product,
+            None => {
+                println!(
+                    "[warn]: Product {} of combination {} is not in the export.",
+                    if record.product_reference.is_empty() {
+                        &record.product_id
+                    } else {
+                        &record.product_reference
+                    },
+                    record.reference
+                );
+                continue;
+            }
+        };
+
+        let base = match product.variants.first() {
+            Some(variant) => variant.clone(),
+            None => continue,
+        };
+
+        if !combined.contains(&product.sku) {
+            combined.push(product.sku.clone());
+            product.variants.clear();
+        }
+
+        let attributes = parse_positioned(&record.attributes);
+        let values = parse_positioned(&record.values);
+
+        for (attribute, value) in attributes.iter().zip(values.iter()) {
+            add_variant_option(product, attribute, value);
+        }
+
+        let barcode = if record.ean13.is_empty() {
+            record.upc.clone()
+        } else {
+            record.ean13.clone()
+        };
+
+        let mut variant = base.clone();
+
+        variant.id = Uuid::new_v4().to_string();
+        variant.name = values.join(" ");
+        variant.retail_price =
+            base.retail_price + record.impact_on_price.parse::<f32>().unwrap_or(0.0);
+        variant.marginal_price = record
+            .wholesale_price
+            .parse::<f32>()
+            .unwrap_or(base.marginal_price);
+        variant.variant_code = vec![record.reference.clone()];
+        variant.barcode = barcode.clone();
+        variant.identification = identify(&record.reference, &barcode);
+        variant.stock = shop_stock(&store, &record.quantity);
+        variant.images = parse_images(&record.images);
+        variant.buy_min = record
+            .minimal_quantity
+            .parse::<f64>()
+            .unwrap_or(base.buy_min);
+        variant.stock_information.min_stock_before_alert = record
+            .low_stock_level
+            .parse::<f64>()
+            .unwrap_or(base.stock_information.min_stock_before_alert);
+
+        // The base weight is in kilograms, its impact in the shop's unit.
+        if let (Ok(weight), Some(impact)) = (
+            base.stock_information.weight.parse::<f64>(),
+            weight_in_kilograms(
+                &record.impact_on_weight,
+                &weight_unit("prestashop", DEFAULT_WEIGHT_UNIT),
+            ),
+        ) {
+            variant.stock_information.weight = (weight + impact).to_string();
+        }
+
+        product.variants.push(variant);
+    }
+
+    Ok(vec![])
+}
+
+impl Parsable<CustomerRecord> for Customer {
+    fn parse_individual(
+        reader: &[Result<CustomerRecord, csv::Error>],
+        line: &mut usize,
+        _db: &mut InlineDatabase,
+    ) -> Result<Customer, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let name = format!("{} {}", cloned.first_name, cloned.last_name)
+            .trim()
+            .to_string();
+
+        let mut customer_notes = vec![import_note(format!(
+            "PrestaShop customer ID: {}",
+            cloned.customer_id
+        ))];
+
+        if !cloned.birthday.is_empty() && cloned.birthday != "0000-00-00" {
+            customer_notes.push(import_note(format!("Birthday: {}", cloned.birthday)));
+        }
+
+        if !cloned.groups.is_empty() {
+            customer_notes.push(import_note(format!("Groups: {}", cloned.groups)));
+        }
+
+        Ok(Customer {
+            id: Uuid::new_v4().to_string(),
+            name: name.clone(),
+            contact: ContactInformation {
+                name,
+                mobile: MobileNumber::from(String::new()),
+                email: Email::from(cloned.email.clone()),
+                landline: String::new(),
+                address: Address {
+                    street: String::new(),
+                    street2: String::new(),
+                    city: String::new(),
+                    country: String::new(),
+                    po_code: String::new(),
+                    lat: 0.0,
+                    lon: 0.0,
+                },
+            },
+            customer_notes,
+            balance: 0,
+            special_pricing: String::new(),
+            accepts_marketing: parse_flag(&cloned.newsletter) || parse_flag(&cloned.opt_in),
+            created_at: parse_timestamp(&cloned.registration_date).unwrap_or(Utc::now()),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+/// Parses an addresses export, giving each customer the first of their
+/// active addresses. Any further addresses are kept as notes.
+///
+/// No customers are created, so the returned list is always empty.
+pub fn parse_customer_addresses(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Customer>, ParseFailure> {
+    // The customers which have been given an address.
+    let mut addressed: Vec<String> = vec![];
+
+    for record in reader.deserialize::<CustomerAddressRecord>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        if !record.active.is_empty() && !parse_flag(&record.active) {
+            continue;
+        }
+
+        let customer = match db.1.iter_mut().find(|customer| {
+            customer
+                .contact
+                .email
+                .full
+                .eq_ignore_ascii_case(&record.email)
+        }) {
+            Some(customer) => customer,
+            None => {
+                println!(
+                    "[warn]: Customer {} of address {} is not in the export.",
+                    record.email, record.alias
+                );
+                continue;
+            }
+        };
+
+        let city = match (record.city.is_empty(), record.state.is_empty()) {
+            (_, true) => record.city.clone(),
+            (true, false) => record.state.clone(),
+            (false, false) => format!("{}, {}", record.city, record.state),
+        };
+
+        if addressed.contains(&customer.id) {
+            customer.customer_notes.push(import_note(format!(
+                "Address {}: {}, {}, {} {}, {}",
+                record.alias, record.address1, city, record.zipcode, record.country, record.phone
+            )));
+            continue;
+        }
+
+        addressed.push(customer.id.clone());
+
+        customer.contact.address = Address {
+            street: record.address1.clone(),
+            street2: record.address2.clone(),
+            city,
+            country: record.country.clone(),
+            po_code: record.zipcode.clone(),
+            lat: 0.0,
+            lon: 0.0,
+        };
+
+        customer.contact.mobile = MobileNumber::from(if record.mobile_phone.is_empty() {
+            record.phone.clone()
+        } else {
+            record.mobile_phone.clone()
+        });
+
+        if !record.mobile_phone.is_empty() {
+            customer.contact.landline = record.phone.clone();
+        }
+
+        if !record.company.is_empty() {
+            customer
+                .customer_notes
+                .push(import_note(format!("Company: {}", record.company)));
+        }
+    }
+
+    Ok(vec![])
+}
+
+/// Orders name their customer with an initial, such as `J. Doe`.
+fn matches_customer(customer: &Customer, name: &str) -> bool {
+    let name = name.trim().to_lowercase();
+    let full = customer.name.to_lowercase();
+
+    if name.is_empty() {
+        return false;
+    }
+
+    if full == name {
+        return true;
+    }
+
+    match full.split_once(' ') {
+        Some((first, last)) => match first.chars().next() {
+            Some(initial) => format!("{}. {}", initial, last) == name,
+            None => false,
+        },
+        None => false,
+    }
+}
+
+impl Parsable<TransactionRecord> for Transaction {
+    fn parse_individual(
+        reader: &[Result<TransactionRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Transaction, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let date = parse_timestamp(&cloned.date).unwrap_or(Utc::now());
+
+        // Totals are formatted for display, such as `€45.60`.
+        let total = cloned
+            .total
+            .chars()
+            .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+            .collect::<String>()
+            .parse::<f32>()
+            .unwrap_or(0.0);
+
+        let status = cloned.status.to_lowercase();
+        let order_status = match status.as_str() {
+            "delivered" => OrderStatus::Fulfilled(date),
+            "shipped" => OrderStatus::Transit(Box::new(TransitInformation {
+                // The orders list does not name the carrier.
+                shipping_company: named_contact(""),
+                query_url: String::new(),
+                tracking_code: String::new(),
+                assigned_products: vec![],
+            })),
+            "canceled" | "cancelled" | "refunded" | "payment error" => {
+                OrderStatus::Failed(cloned.status.clone())
+            }
+            _ => OrderStatus::Processing(date),
+        };
+
+        let paid = !matches!(
+            status.as_str(),
+            "canceled"
+                | "cancelled"
+                | "payment error"
+                | "awaiting bank wire payment"
+                | "awaiting check payment"
+                | "awaiting cash on delivery validation"
+        );
+
+        let store = shop_store(db);
+        let origin = match &store {
+            Some(store) => location(store),
+            None => Location {
+                contact: named_contact("PrestaShop"),
+                store_code: "000".to_string(),
+                store_id: "000".to_string(),
+            },
+        };
+
+        let customer =
+            db.1.iter()
+                .find(|customer| matches_customer(customer, &cloned.customer))
+                .cloned();
+
+        let payment = if paid {
+            vec![Payment {
+                id: Uuid::new_v4().to_string(),
+                payment_method: match_payment_method(&cloned.payment, &PAYMENT_METHODS),
+                fulfillment_date: date,
+                amount: Price {
+                    quantity: total,
+                    currency: String::new(),
+                },
+                processing_fee: Price {
+                    quantity: 0.0,
+                    currency: String::new(),
+                },
+                status: PaymentStatus::Complete(Processable::Anonymous(String::from("prestashop"))),
+                processor: PaymentProcessor::anonymous(String::from("prestashop")),
+                order_ids: vec![],
+                delay_action: PaymentAction::Complete,
+                delay_duration: String::new(),
+            }]
+        } else {
+            vec![]
+        };
+
+        let order_notes = vec![import_note(format!(
+            "PrestaShop order {} ({}), the orders list holds no line items",
+            cloned.id, cloned.status
+        ))];
+
+        let mut destination = customer
+            .as_ref()
+            .map_or(origin.contact.clone(), |customer| customer.contact.clone());
+
+        if destination.address.country.is_empty() {
+            destination.address.country = cloned.delivery.clone();
+        }
+
+        Ok(Transaction {
+            id: Uuid::new_v4().to_string(),
+            customer: TransactionCustomer {
+                customer_type: CustomerType::Individual,
+                customer_id: customer
+                    .as_ref()
+                    .map_or(String::new(), |customer| customer.id.clone()),
+            },
+            kiosk: String::new(),
+            transaction_type: TransactionType::Out,
+            products: vec![Order {
+                id: Uuid::new_v4().to_string(),
+                destination: Location {
+                    contact: destination,
+                    store_code: origin.store_code.clone(),
+                    store_id: origin.store_id.clone(),
+                },
+                origin,
+                products: vec![],
+                status: OrderStatusAssignment {
+                    status: order_status,
+                    assigned_products: vec![],
+                    timestamp: date,
+                },
+                status_history: vec![],
+                order_history: vec![],
+                previous_failed_fulfillment_attempts: vec![],
+                order_notes: order_notes.clone(),
+                reference: cloned.reference.clone(),
+                creation_date: date,
+                discount: DiscountValue::Absolute(0),
+                order_type: OrderType::Shipment,
+            }],
+            order_total: total.round() as i64,
+            payment,
+            order_date: date,
+            order_notes,
+            salesperson: String::new(),
+            created_at: date,
+            updated_at: Utc::now(),
+        })
+    }
+}
@@ -0,0 +1,472 @@
+//! QuickBooks Online customer and products and services exports.
+//!
+//! Items carry the accounts they are booked against, which are kept in their
+//! stock information: the income account as the sales group, the inventory
+//! asset account as the stock group and the expense account as the value
+//! stream. Sub-items are named by their parent, as `Parent:Item`.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        identify, lowercase_tags, merge_tags, parse_flag, parse_tags, resolve_store, SpecialPricing,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::Reader;
+use open_stock::{
+    Address, ContactInformation, Customer, DiscountValue, Email, Location, MobileNumber, Note,
+    Product, ProductIdentification, ProductVisibility, Quantity, Stock, StockInformation,
+    VariantInformation,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "Product/Service Name,Sales Description,SKU,Type,Sales Price / Rate,Taxable,Sales Tax Code,Income Account,Purchase Description,Purchase Cost,Purchase Tax Code,Expense Account,Quantity On Hand,Reorder Point,Inventory Asset Account,Quantity as-of Date,Category",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "Customer,Company,First Name,Last Name,Email,Phone,Mobile,Street Address,City,State,ZIP,Country,Taxable,Resale No.,Open Balance,Notes,Created",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "ZZZZ",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// Separates a sub-item (or sub-category) from its parent.
+const HIERARCHY_SEPARATOR: char = ':';
+
+/// The store standing for the company, which counts all of its inventory.
+const COMPANY: &str = "QuickBooks";
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "QUICKBOOKS-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Reads the dates of exports, given as `05/01/2023`.
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDate::parse_from_str(value.trim(), "%m/%d/%Y")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|timestamp| timestamp.and_utc())
+}
+
+/// Reads an amount, as exported with thousands separators such as `1,250.00`.
+fn parse_amount(value: &str) -> Option<f32> {
+    value.trim().replace(',', "").parse::<f32>().ok()
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "Product/Service Name")]
+    name: String,
+
+    #[serde(rename = "Sales Description")]
+    sales_description: String,
+
+    #[serde(rename = "SKU")]
+    sku: String,
+
+    /// `Inventory`, `Non-inventory`, `Service` or `Bundle`.
+    #[serde(rename = "Type")]
+    item_type: String,
+
+    #[serde(rename = "Sales Price / Rate")]
+    sales_price: String,
+
+    #[serde(rename = "Taxable")]
+    taxable: String,
+
+    /// Used in place of `Taxable` outside the US, such as `20.0% S`.
+    #[serde(rename = "Sales Tax Code")]
+    sales_tax_code: String,
+
+    #[serde(rename = "Income Account")]
+    income_account: String,
+
+    #[serde(rename = "Purchase Description")]
+    purchase_description: String,
+
+    #[serde(rename = "Purchase Cost")]
+    purchase_cost: String,
+
+    #[serde(rename = "Purchase Tax Code")]
+    purchase_tax_code: String,
+
+    #[serde(rename = "Expense Account")]
+    expense_account: String,
+
+    #[serde(rename = "Quantity On Hand")]
+    quantity_on_hand: String,
+
+    #[serde(rename = "Reorder Point")]
+    reorder_point: String,
+
+    #[serde(rename = "Inventory Asset Account")]
+    inventory_asset_account: String,
+
+    #[serde(rename = "Category")]
+    category: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerRecord {
+    /// The customer's display name.
+    #[serde(rename = "Customer")]
+    customer: String,
+
+    #[serde(rename = "Company")]
+    company: String,
+
+    #[serde(rename = "First Name")]
+    first_name: String,
+
+    #[serde(rename = "Last Name")]
+    last_name: String,
+
+    #[serde(rename = "Email")]
+    email: String,
+
+    #[serde(rename = "Phone")]
+    phone: String,
+
+    #[serde(rename = "Mobile")]
+    mobile: String,
+
+    #[serde(rename = "Street Address")]
+    street: String,
+
+    #[serde(rename = "City")]
+    city: String,
+
+    #[serde(rename = "State")]
+    state: String,
+
+    #[serde(rename = "ZIP")]
+    zip: String,
+
+    #[serde(rename = "Country")]
+    country: String,
+
+    #[serde(rename = "Taxable")]
+    taxable: String,
+
+    #[serde(rename = "Resale No.")]
+    resale_number: String,
+
+    #[serde(rename = "Open Balance")]
+    open_balance: String,
+
+    #[serde(rename = "Notes")]
+    notes: String,
+
+    #[serde(rename = "Created")]
+    created: String,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let name = cloned
+            .name
+            .rsplit(HIERARCHY_SEPARATOR)
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+
+        // Sub-items are filed under their parent, as sub-categories are.
+        let mut tags = parse_tags(&cloned.category, HIERARCHY_SEPARATOR, lowercase_tags());
+
+        if let Some((parent, _)) = cloned.name.rsplit_once(HIERARCHY_SEPARATOR) {
+            merge_tags(
+                &mut tags,
+                parse_tags(parent, HIERARCHY_SEPARATOR, lowercase_tags()),
+                lowercase_tags(),
+            );
+        }
+
+        let inventory = cloned.item_type.eq_ignore_ascii_case("Inventory");
+        let service = cloned.item_type.eq_ignore_ascii_case("Service");
+        let price = parse_amount(&cloned.sales_price).unwrap_or(0.0);
+
+        // Only inventory items are counted.
+        let store = if inventory {
+            resolve_store(COMPANY, db)
+        } else {
+            None
+        };
+
+        let stock = match store {
+            Some(store) => vec![Stock {
+                store: Location {
+                    store_code: store.code.clone(),
+                    store_id: store.id.clone(),
+                    contact: store.contact.clone(),
+                },
+                quantity: Quantity {
+                    quantity_sellable: parse_amount(&cloned.quantity_on_hand).unwrap_or(0.0),
+                    quantity_unsellable: 0.0,
+                    quantity_on_order: 0.0,
+                    quantity_allocated: 0.0,
+                },
+            }],
+            None => vec![],
+        };
+
+        let tax_code = if !cloned.sales_tax_code.is_empty() {
+            cloned.sales_tax_code.clone()
+        } else if cloned.taxable.is_empty() {
+            String::new()
+        } else if parse_flag(&cloned.taxable) {
+            "Taxable".to_string()
+        } else {
+            "Non-taxable".to_string()
+        };
+
+        let mut specifications = vec![];
+
+        if !cloned.purchase_tax_code.is_empty() {
+            specifications.push((
+                "Purchase Tax Code".to_string(),
+                cloned.purchase_tax_code.clone(),
+            ));
+        }
+
+        Ok(Product {
+            name: name.clone(),
+            company: String::new(),
+            variant_groups: vec![],
+            variants: vec![VariantInformation {
+                name: name.clone(),
+                stock,
+                images: vec![],
+                retail_price: price,
+                marginal_price: parse_amount(&cloned.purchase_cost).unwrap_or(price),
+                loyalty_discount: DiscountValue::Absolute(0),
+                variant_code: if cloned.sku.is_empty() {
+                    vec![]
+                } else {
+                    vec![cloned.sku.clone()]
+                },
+                order_history: vec![],
+                stock_information: StockInformation {
+                    stock_group: cloned.inventory_asset_account.clone(),
+                    sales_group: cloned.income_account.clone(),
+                    value_stream: cloned.expense_account.clone(),
+                    brand: String::new(),
+                    tax_code,
+                    weight: "0".to_string(),
+                    volume: "0.00".to_string(),
+                    max_volume: "0.00".to_string(),
+                    back_order: false,
+                    discontinued: false,
+                    non_diminishing: service,
+                    shippable: !service,
+                    size_override_unit: String::new(),
+                    size_x_unit: String::new(),
+                    size_y_unit: String::new(),
+                    size_z_unit: String::new(),
+                    size_x: 0.0,
+                    size_y: 0.0,
+                    size_z: 0.0,
+                    min_stock_before_alert: parse_amount(&cloned.reorder_point).unwrap_or(0.0)
+                        as f64,
+                    min_stock_level: 0.0,
+                    colli: String::new(),
+                },
+                barcode: String::new(),
+                id: Uuid::new_v4().to_string(),
+                buy_max: -1.0,
+                buy_min: 1.0,
+                identification: identify(&cloned.sku, ""),
+                stock_tracking: inventory,
+            }],
+            sku: cloned.sku.clone(),
+            images: vec![],
+            tags,
+            description: cloned.sales_description.clone(),
+            specifications,
+            name_long: cloned.name.clone(),
+            identification: ProductIdentification {
+                sku: cloned.sku.clone(),
+                ..Default::default()
+            },
+            description_long: if cloned.purchase_description.is_empty() {
+                cloned.sales_description.clone()
+            } else {
+                format!(
+                    "{}\n\n{}",
+                    cloned.sales_description, cloned.purchase_description
+                )
+                .trim()
+                .to_string()
+            },
+            visible: ProductVisibility::ShowWhenInStock,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+impl Parsable<CustomerRecord> for Customer {
+    fn parse_individual(
+        reader: &[Result<CustomerRecord, csv::Error>],
+        line: &mut usize,
+        _db: &mut InlineDatabase,
+    ) -> Result<Customer, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let full_name = format!("{} {}", cloned.first_name, cloned.last_name)
+            .trim()
+            .to_string();
+
+        let name = if cloned.customer.is_empty() {
+            full_name.clone()
+        } else {
+            cloned.customer.clone()
+        };
+
+        let mut customer_notes = vec![];
+
+        if !cloned.company.is_empty() && cloned.company != name {
+            customer_notes.push(import_note(format!("Company: {}", cloned.company)));
+        }
+
+        if !cloned.resale_number.is_empty() {
+            customer_notes.push(import_note(format!(
+                "Resale number: {}",
+                cloned.resale_number
+            )));
+        }
+
+        // Balances are owed to the business, and are noted
+        // to be carried over by hand.
+        if parse_amount(&cloned.open_balance).unwrap_or(0.0) != 0.0 {
+            customer_notes.push(import_note(format!(
+                "Open balance: {}",
+                cloned.open_balance
+            )));
+        }
+
+        if !cloned.notes.is_empty() {
+            customer_notes.push(import_note(cloned.notes.clone()));
+        }
+
+        let city = match (cloned.city.is_empty(), cloned.state.is_empty()) {
+            (_, true) => cloned.city.clone(),
+            (true, false) => cloned.state.clone(),
+            (false, false) => format!("{}, {}", cloned.city, cloned.state),
+        };
+
+        Ok(Customer {
+            id: Uuid::new_v4().to_string(),
+            name: name.clone(),
+            contact: ContactInformation {
+                name: if full_name.is_empty() {
+                    name
+                } else {
+                    full_name
+                },
+                mobile: MobileNumber::from(if cloned.mobile.is_empty() {
+                    cloned.phone.clone()
+                } else {
+                    cloned.mobile.clone()
+                }),
+                email: Email::from(cloned.email.clone()),
+                landline: if cloned.mobile.is_empty() {
+                    String::new()
+                } else {
+                    cloned.phone.clone()
+                },
+                address: Address {
+                    street: cloned.street.clone(),
+                    street2: String::new(),
+                    city,
+                    country: cloned.country.clone(),
+                    po_code: cloned.zip.clone(),
+                    lat: 0.0,
+                    lon: 0.0,
+                },
+            },
+            customer_notes,
+            balance: 0,
+            special_pricing: SpecialPricing::tax_exempt(
+                !cloned.taxable.is_empty() && !parse_flag(&cloned.taxable),
+            )
+            .to_field(),
+            accepts_marketing: false,
+            created_at: parse_date(&cloned.created).unwrap_or(Utc::now()),
+            updated_at: Utc::now(),
+        })
+    }
+}
@@ -1,13 +1,23 @@
-use crate::{parser::ParseFailure, InlineDatabase};
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        apportion_payment, identify, lowercase_tags, merge_tags, metafield_target, parse_discount,
+        parse_flag, parse_tags, resolve_employee, resolve_kiosk, resolve_store, split_payment_methods,
+        weight_in_kilograms, Barcode, GiftCard, MetafieldTarget, PaymentKind, PaymentMethodTable,
+        ProductStatus, PromotionTerms, SpecialPricing,
+    },
+    InlineDatabase,
+};
 use chrono::prelude::*;
-use csv::Reader;
+use csv::{Reader, StringRecord};
 use open_stock::{
     Address, ContactInformation, Customer, DiscountValue, Email, FulfillmentStatus, Kiosk,
-    KioskPreferences, Location, MobileNumber, Note, Order, Payment, PaymentMethod,
-    PaymentProcessor, PickStatus, Price, Product, ProductInstance, ProductPurchase, Quantity,
-    Stock, StockInformation, Store, Transaction, TransactionCustomer, Variant, VariantCategory,
-    VariantInformation,
+    KioskPreferences, Location, MobileNumber, Note, Order, OrderType, Payment, PaymentMethod,
+    PaymentProcessor, PickStatus, Price, Product, ProductIdentification, ProductInstance,
+    ProductPurchase, Promotion, PromotionBuy, PromotionGet, Quantity, Stock, StockInformation,
+    Store, Transaction, TransactionCustomer, Variant, VariantCategory, VariantInformation,
 };
+use phf::{phf_map, Map};
 use serde::{Deserialize, Serialize};
 use std::{fs::File, ops::Deref, str::FromStr};
 use uuid::Uuid;
@@ -19,14 +29,40 @@ pub fn match_self(parse_type: ParseType) -> String {
         ParseType::Store => "Store",
         ParseType::Kiosk => "Kiosk",
         ParseType::Product => "Handle,Title,Body (HTML),Vendor,Product Category,Type,Tags,Published,Option1 Name,Option1 Value,Option2 Name,Option2 Value,Option3 Name,Option3 Value,Variant SKU,Variant Grams,Variant Inventory Tracker,Variant Inventory Qty,Variant Inventory Policy,Variant Fulfillment Service,Variant Price,Variant Compare At Price,Variant Requires Shipping,Variant Taxable,Variant Barcode,Image Src,Image Position,Image Alt Text,Gift Card,SEO Title,SEO Description,Google Shopping / Google Product Category,Google Shopping / Gender,Google Shopping / Age Group,Google Shopping / MPN,Google Shopping / AdWords Grouping,Google Shopping / AdWords Labels,Google Shopping / Condition,Google Shopping / Custom Product,Google Shopping / Custom Label 0,Google Shopping / Custom Label 1,Google Shopping / Custom Label 2,Google Shopping / Custom Label 3,Google Shopping / Custom Label 4,Variant Image,Variant Weight Unit,Variant Tax Code,Cost per item,Included / New Zealand,Included / International,Price / International,Compare At Price / International,Status",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "Name,Value,Value Type,Type,Applies To,Minimum Requirement,Minimum Requirement Value,Usage Limit Per Code,Times Used,Start,End,Status",
+        ParseType::GiftCard => "Id,Last Characters,Customer Name,Email,Order Name,Created At,Initial Value,Balance,Currency,Expires On,Note,Disabled At",
         ParseType::Customer => "First Name,Last Name,Email,Accepts Email Marketing,Company,Address1,Address2,City,Province,Province Code,Country,Country Code,Zip,Phone,Accepts SMS Marketing,Total Spent,Total Orders,Tags,Note,Tax Exempt",
+        ParseType::CustomerAddress => "Email,First Name,Last Name,Company,Address1,Address2,City,Province,Province Code,Country,Country Code,Zip,Phone,Default Address",
         ParseType::Transaction => "Name,Email,Financial Status,Paid at,Fulfillment Status,Fulfilled at,Accepts Marketing,Currency,Subtotal,Shipping,Taxes,Total,Discount Code,Discount Amount,Shipping Method,Created at,Lineitem quantity,Lineitem name,Lineitem price,Lineitem compare at price,Lineitem sku,Lineitem requires shipping,Lineitem taxable,Lineitem fulfillment status,Billing Name,Billing Street,Billing Address1,Billing Address2,Billing Company,Billing City,Billing Zip,Billing Province,Billing Country,Billing Phone,Shipping Name,Shipping Street,Shipping Address1,Shipping Address2,Shipping Company,Shipping City,Shipping Zip,Shipping Province,Shipping Country,Shipping Phone,Notes,Note Attributes,Cancelled at,Payment Method,Payment Reference,Refunded Amount,Vendor,Outstanding Balance,Employee,Location,Device ID,Id,Tags,Risk Level,Source,Lineitem discount,Tax 1 Name,Tax 1 Value,Tax 2 Name,Tax 2 Value,Tax 3 Name,Tax 3 Value,Tax 4 Name,Tax 4 Value,Tax 5 Name,Tax 5 Value,Phone,Receipt Number,Duties,Billing Province Name,Shipping Province Name,Payment ID,Payment Terms Name,Next Payment Due At,Payment References",
+        ParseType::TransactionItem => "ZZZZ",
         ParseType::Invalid => "",
     };
 
     String::from_str(matchable).unwrap()
 }
 
+/// The store online orders originate from, named as Shopify's sales channel.
+pub const ONLINE_STORE: &str = "Online Store";
+
+/// Payment gateway names as they appear in the `Payment Method` column.
+pub static PAYMENT_METHODS: PaymentMethodTable = phf_map! {
+    "shopify payments" => PaymentKind::Card,
+    "shop pay installments" => PaymentKind::Card,
+    "afterpay" => PaymentKind::Card,
+    "external credit" => PaymentKind::Card,
+    "external debit" => PaymentKind::Card,
+    "cash" => PaymentKind::Cash,
+    "cash on delivery (cod)" => PaymentKind::Cash,
+    "bank deposit" => PaymentKind::Transfer,
+    "money order" => PaymentKind::Transfer,
+    "paypal express checkout" => PaymentKind::Transfer,
+    "gift card" => PaymentKind::GiftCard,
+    "store credit" => PaymentKind::StoreCredit,
+    "shop_cash" => PaymentKind::StoreCredit,
+};
+
 fn search_for_matching_customer(customer: String, customers: Vec<Customer>) -> Vec<Customer> {
     customers
         .iter()
@@ -40,6 +76,56 @@ fn search_for_matching_customer(customer: String, customers: Vec<Customer>) -> V
         .collect::<Vec<Customer>>()
 }
 
+/// Reads the timestamps of Shopify exports, which are
+/// either RFC 3339 or `2023-05-01 10:00:00 +1200`.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .or_else(|_| DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S %z"))
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .ok()
+}
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "SHOPIFY-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Contacts have no company field, so the company is named alongside the person.
+fn contact_name(name: &str, company: &str) -> String {
+    match (name.is_empty(), company.is_empty()) {
+        (_, true) => name.to_string(),
+        (true, false) => company.to_string(),
+        (false, false) => format!("{} ({})", name, company),
+    }
+}
+
+/// Addresses have no province field, so it is kept alongside the city.
+fn city_with_province(city: &str, province: &str, province_code: &str) -> String {
+    let province = if province.is_empty() {
+        province_code
+    } else {
+        province
+    };
+
+    match (city.is_empty(), province.is_empty()) {
+        (_, true) => city.to_string(),
+        _ if city.eq_ignore_ascii_case(province) => city.to_string(),
+        (true, false) => province.to_string(),
+        (false, false) => format!("{}, {}", city, province),
+    }
+}
+
+fn country_or_code(country: &str, country_code: &str) -> String {
+    if country.is_empty() {
+        country_code.to_string()
+    } else {
+        country.to_string()
+    }
+}
+
 /// Note: This will only fill for non-disctinct instances.
 ///
 /// i.e. instances with decimal quantities or unit based quantities (e.g. 3m^2)
@@ -57,6 +143,100 @@ fn fill_instances(f_status: FulfillmentStatus, quantity: u32) -> Vec<ProductInst
     instances
 }
 
+/// Derives how an order reached the customer from its `Source`,
+/// `Shipping Method` and shipping address.
+fn match_order_type(record: &TransactionRecord) -> OrderType {
+    let method = record.shipping_method.to_lowercase();
+
+    if ["pickup", "pick up", "pick-up"]
+        .iter()
+        .any(|keyword| method.contains(keyword))
+    {
+        OrderType::Pickup
+    } else if shipping_contact(record).is_some() {
+        OrderType::Shipment
+    } else if record.source == "pos" || method.is_empty() {
+        // In-store sales, and online orders with nothing to
+        // ship (such as digital goods), are handed over directly.
+        OrderType::Direct
+    } else {
+        OrderType::Shipment
+    }
+}
+
+/// Reads the `Shipping *` columns into the contact an order is shipped to,
+/// if the order has a shipping address at all.
+fn shipping_contact(record: &TransactionRecord) -> Option<ContactInformation> {
+    if record.shipping_address.is_empty() && record.shipping_city.is_empty() {
+        return None;
+    }
+
+    Some(ContactInformation {
+        name: record.shipping_name.clone(),
+        mobile: MobileNumber::from(record.shipping_phone.clone()),
+        email: Email::from(record.email.clone()),
+        landline: record.shipping_phone.clone(),
+        address: Address {
+            street: record.shipping_address.clone(),
+            street2: record.shipping_address2.clone(),
+            city: record.shipping_city.clone(),
+            country: record.shipping_country.clone(),
+            po_code: record.shipping_zip.clone(),
+            lat: 0.0,
+            lon: 0.0,
+        },
+    })
+}
+
+/// Builds a payment for each tender of the order, splitting combined
+/// methods where their amounts can be determined.
+///
+/// Orders export no amount for each tender, so a combined method such as
+/// `Shopify Payments + Gift Card` is kept as a single payment of the total,
+/// under the method as exported.
+fn build_payments(record: &TransactionRecord) -> Vec<Payment> {
+    let total = record.total.parse::<f32>().unwrap_or(0.0);
+    let splits = split_payment_methods(&record.payment_method, &PAYMENT_METHODS);
+
+    let tenders = match apportion_payment(&splits, total) {
+        Some(tenders) if !tenders.is_empty() => tenders,
+        _ => vec![(PaymentMethod::Other(record.payment_method.clone()), total)],
+    };
+
+    tenders
+        .into_iter()
+        .map(|(payment_method, amount)| Payment {
+            id: Uuid::new_v4().to_string(),
+            payment_method,
+            fulfillment_date: DateTime::from_str(record.paid_at.as_str()).unwrap_or(Utc::now()),
+            amount: Price {
+                quantity: amount,
+                currency: record.currency.clone(),
+            },
+            // Fees are not exported with orders, only by the processor.
+            processing_fee: Price {
+                quantity: 0.0,
+                currency: record.currency.clone(),
+            },
+            status: open_stock::PaymentStatus::Complete(open_stock::Processable::Anonymous(
+                String::from("shopify"),
+            )),
+            processor: PaymentProcessor::anonymous(String::from("shopify")),
+            order_ids: vec![],
+            delay_action: open_stock::PaymentAction::Complete,
+            delay_duration: String::new(),
+        })
+        .collect()
+}
+
+/// An `Image Src` of a product, with where it sits in the gallery.
+#[derive(Debug, Clone)]
+struct GalleryImage {
+    position: u32,
+    src: String,
+    alt: String,
+}
+
 #[derive(Debug, Clone)]
 struct Options {
     option_1_name: String,
@@ -163,6 +343,9 @@ pub struct ProductRecord {
     #[serde(rename = "SEO Title")]
     seo_title: String,
 
+    #[serde(rename = "SEO Description")]
+    seo_description: String,
+
     #[serde(rename = "Variant Image")]
     variant_image: String,
 
@@ -181,6 +364,9 @@ pub struct ProductRecord {
     #[serde(rename = "Google Shopping / Google Product Category")]
     google_product_category: String,
 
+    #[serde(rename = "Google Shopping / Gender")]
+    google_gender: String,
+
     #[serde(rename = "Google Shopping / Age Group")]
     google_age_group: String,
 
@@ -198,6 +384,208 @@ pub struct ProductRecord {
 
     #[serde(rename = "Google Shopping / Custom Product")]
     google_custom_product: String,
+
+    #[serde(rename = "Google Shopping / Custom Label 0")]
+    google_custom_label_0: String,
+
+    #[serde(rename = "Google Shopping / Custom Label 1")]
+    google_custom_label_1: String,
+
+    #[serde(rename = "Google Shopping / Custom Label 2")]
+    google_custom_label_2: String,
+
+    #[serde(rename = "Google Shopping / Custom Label 3")]
+    google_custom_label_3: String,
+
+    #[serde(rename = "Google Shopping / Custom Label 4")]
+    google_custom_label_4: String,
+
+    /// Read from the raw row by `parse_products`, as the
+    /// metafield columns differ between every store.
+    #[serde(skip)]
+    metafields: Vec<Metafield>,
+}
+
+/// A `Name (product.metafields.namespace.key)` column of a product export.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Metafield {
+    name: String,
+    namespace: String,
+    key: String,
+    value: String,
+}
+
+/// Where the value of a metafield is placed on the product unless configured
+/// otherwise, keyed by `namespace.key`. Unlisted metafields become specifications.
+pub static DEFAULT_METAFIELD_TARGETS: Map<&'static str, MetafieldTarget> = phf_map! {
+    "shopify.color-pattern" => MetafieldTarget::Tag,
+    "shopify.target-gender" => MetafieldTarget::Tag,
+    "shopify.age-group" => MetafieldTarget::Tag,
+    "descriptors.subtitle" => MetafieldTarget::Description,
+    "descriptors.care_guide" => MetafieldTarget::Description,
+    "reviews.rating" => MetafieldTarget::Ignore,
+    "reviews.rating_count" => MetafieldTarget::Ignore,
+};
+
+/// Where the metafield `namespace.key` is placed, as set for the
+/// conversion, otherwise by default.
+fn metafield_placement(path: &str) -> MetafieldTarget {
+    metafield_target(path)
+        .or_else(|| DEFAULT_METAFIELD_TARGETS.get(path).copied())
+        .unwrap_or(MetafieldTarget::Specification)
+}
+
+/// Reads the metafield columns of a product export row.
+fn read_metafields(headers: &StringRecord, record: &StringRecord) -> Vec<Metafield> {
+    headers
+        .iter()
+        .zip(record.iter())
+        .filter(|(_, value)| !value.is_empty())
+        .filter_map(|(header, value)| {
+            let (name, path) = header.split_once(" (product.metafields.")?;
+            let (namespace, key) = path.strip_suffix(')')?.split_once('.')?;
+
+            Some(Metafield {
+                name: name.trim().to_string(),
+                namespace: namespace.to_string(),
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Places a product's Google Shopping, SEO and metafield columns,
+/// the latter by the target set for each with `ConversionOptions::set_metafield_target`.
+fn apply_catalog_attributes(product: &mut Product, record: &ProductRecord) {
+    let columns = [
+        ("SEO Title", &record.seo_title),
+        ("SEO Description", &record.seo_description),
+        ("Google Product Category", &record.google_product_category),
+        ("Gender", &record.google_gender),
+        ("Age Group", &record.google_age_group),
+        ("MPN", &record.google_mpn),
+        ("AdWords Grouping", &record.google_adwords),
+        ("AdWords Labels", &record.google_adword_labels),
+        ("Condition", &record.google_condition),
+        ("Custom Product", &record.google_custom_product),
+        ("Custom Label 0", &record.google_custom_label_0),
+        ("Custom Label 1", &record.google_custom_label_1),
+        ("Custom Label 2", &record.google_custom_label_2),
+        ("Custom Label 3", &record.google_custom_label_3),
+        ("Custom Label 4", &record.google_custom_label_4),
+    ];
+
+    for (name, value) in columns {
+        if !value.is_empty() {
+            product
+                .specifications
+                .push((name.to_string(), value.to_string()));
+        }
+    }
+
+    for metafield in &record.metafields {
+        let path = format!("{}.{}", metafield.namespace, metafield.key);
+
+        match metafield_placement(&path) {
+            MetafieldTarget::Specification => product
+                .specifications
+                .push((metafield.name.clone(), metafield.value.clone())),
+            // List metafields are exported separated by semicolons.
+            MetafieldTarget::Tag => merge_tags(
+                &mut product.tags,
+                parse_tags(&metafield.value, ';', lowercase_tags()),
+                lowercase_tags(),
+            ),
+            MetafieldTarget::Description => {
+                if !product.description_long.is_empty() {
+                    product.description_long.push_str("\n\n");
+                }
+
+                product.description_long.push_str(&metafield.value);
+            }
+            MetafieldTarget::Ignore => {}
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PromotionRecord {
+    #[serde(rename = "Name")]
+    code: String,
+
+    #[serde(rename = "Value")]
+    value: String,
+
+    #[serde(rename = "Value Type")]
+    value_type: String,
+
+    #[serde(rename = "Type")]
+    discount_type: String,
+
+    #[serde(rename = "Applies To")]
+    applies_to: String,
+
+    #[serde(rename = "Minimum Requirement")]
+    minimum_requirement: String,
+
+    #[serde(rename = "Minimum Requirement Value")]
+    minimum_requirement_value: String,
+
+    #[serde(rename = "Usage Limit Per Code")]
+    usage_limit: String,
+
+    #[serde(rename = "Times Used")]
+    times_used: String,
+
+    #[serde(rename = "Start")]
+    starts_at: String,
+
+    #[serde(rename = "End")]
+    ends_at: String,
+
+    #[serde(rename = "Status")]
+    status: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GiftCardRecord {
+    #[serde(rename = "Id")]
+    id: String,
+
+    /// Shopify only exports the final characters of the code.
+    #[serde(rename = "Last Characters")]
+    last_characters: String,
+
+    #[serde(rename = "Customer Name")]
+    customer_name: String,
+
+    #[serde(rename = "Email")]
+    email: String,
+
+    #[serde(rename = "Order Name")]
+    order_name: String,
+
+    #[serde(rename = "Created At")]
+    created_at: String,
+
+    #[serde(rename = "Initial Value")]
+    initial_value: String,
+
+    #[serde(rename = "Balance")]
+    balance: String,
+
+    #[serde(rename = "Currency")]
+    currency: String,
+
+    #[serde(rename = "Expires On")]
+    expires_on: String,
+
+    #[serde(rename = "Note")]
+    note: String,
+
+    #[serde(rename = "Disabled At")]
+    disabled_at: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -263,6 +651,51 @@ pub struct CustomerRecord {
     tax_exempt: String,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerAddressRecord {
+    #[serde(rename = "Email")]
+    email: String,
+
+    #[serde(rename = "First Name")]
+    first_name: String,
+
+    #[serde(rename = "Last Name")]
+    last_name: String,
+
+    #[serde(rename = "Company")]
+    company: String,
+
+    #[serde(rename = "Address1")]
+    address_street: String,
+
+    #[serde(rename = "Address2")]
+    address_suburb: String,
+
+    #[serde(rename = "City")]
+    address_city: String,
+
+    #[serde(rename = "Province")]
+    address_province: String,
+
+    #[serde(rename = "Province Code")]
+    address_province_code: String,
+
+    #[serde(rename = "Country")]
+    address_country: String,
+
+    #[serde(rename = "Country Code")]
+    address_country_code: String,
+
+    #[serde(rename = "Zip")]
+    address_zip: String,
+
+    #[serde(rename = "Phone")]
+    phone_number: String,
+
+    #[serde(rename = "Default Address")]
+    default_address: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransactionRecord {
     #[serde(rename = "Name")]
@@ -382,6 +815,9 @@ pub struct TransactionRecord {
     #[serde(rename = "Shipping Company")]
     shipping_company: String,
 
+    #[serde(rename = "Shipping City")]
+    shipping_city: String,
+
     #[serde(rename = "Shipping Zip")]
     shipping_zip: String,
 
@@ -493,6 +929,39 @@ pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
     db: &mut InlineDatabase,
 ) -> Result<Vec<T>, ParseFailure> {
     let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+/// Parses a product export, which carries a column for each metafield
+/// on top of those of `ProductRecord`.
+pub fn parse_products(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(error) => return Err(ParseFailure::ReadFailure(error.to_string())),
+    };
+
+    let collected: Vec<Result<ProductRecord, csv::Error>> = reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let mut product: ProductRecord = record.deserialize(Some(&headers))?;
+            product.metafields = read_metafields(&headers, &record);
+
+            Ok(product)
+        })
+        .collect();
+
+    parse_collected(collected, db)
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
     let mut iterator: usize = 0;
     let mut items: Vec<T> = vec![];
 
@@ -524,42 +993,81 @@ impl Parsable<CustomerRecord> for Customer {
             };
 
             let cloned = (*line_value).as_ref().unwrap();
-            let name = format!("{} {}", cloned.first_name, cloned.last_name);
+            let name = format!("{} {}", cloned.first_name, cloned.last_name)
+                .trim()
+                .to_string();
+
+            let accepts_email = parse_flag(&cloned.accepts_marketing);
+            let accepts_sms = parse_flag(&cloned.accepts_sms);
+
+            let mut customer_notes = vec![];
+
+            if !cloned.note.is_empty() {
+                customer_notes.push(import_note(cloned.note.clone()));
+            }
+
+            // Customers have no tags of their own, so
+            // they are kept as a note to not be lost.
+            let tags = parse_tags(&cloned.tags, ',', lowercase_tags());
+
+            if !tags.is_empty() {
+                customer_notes.push(import_note(format!("Tags: {}", tags.join(", "))));
+            }
+
+            // `accepts_marketing` holds the email consent, so SMS
+            // consent is only noted when it differs from it.
+            if accepts_sms != accepts_email {
+                customer_notes.push(import_note(format!(
+                    "Accepts SMS marketing: {}",
+                    if accepts_sms { "yes" } else { "no" },
+                )));
+            }
+
+            let order_count = cloned.order_total.parse::<u32>().unwrap_or(0);
+
+            if order_count > 0 {
+                customer_notes.push(import_note(format!(
+                    "Shopify history: {} orders, {} spent",
+                    order_count, cloned.accrued_billing
+                )));
+            }
 
             Customer {
                 id: Uuid::new_v4().to_string(),
-                name: name.clone(),
+                name: if name.is_empty() {
+                    cloned.company.clone()
+                } else {
+                    name.clone()
+                },
                 contact: ContactInformation {
-                    name,
+                    name: contact_name(&name, &cloned.company),
                     mobile: MobileNumber::from(cloned.phone_number.clone()),
                     email: Email::from(cloned.email.clone()),
-                    landline: cloned.phone_number.clone(),
+                    // Shopify keeps a single phone number,
+                    // which is most often a mobile.
+                    landline: String::new(),
                     address: Address {
                         street: cloned.address_street.clone(),
                         street2: cloned.address_suburb.clone(),
-                        city: cloned.address_city.clone(),
-                        country: cloned.address_country.clone(),
+                        city: city_with_province(
+                            &cloned.address_city,
+                            &cloned.address_province,
+                            &cloned.address_province_code,
+                        ),
+                        country: country_or_code(
+                            &cloned.address_country,
+                            &cloned.address_country_code,
+                        ),
                         po_code: cloned.address_zip.clone(),
                         lat: 0.0,
                         lon: 0.0,
                     },
                 },
-                customer_notes: if cloned.note.is_empty() {
-                    vec![]
-                } else {
-                    vec![Note {
-                        message: cloned.note.clone(),
-                        author: "SHOPIFY-IMPORT".to_string(),
-                        timestamp: Utc::now(),
-                    }]
-                },
+                customer_notes,
                 balance: 0,
-                special_pricing: if cloned.tax_exempt == "yes" {
-                    "TAX-EXEMPT".to_string()
-                } else {
-                    "".to_string()
-                },
-                accepts_marketing: cloned.accepts_marketing == "yes",
+                special_pricing: SpecialPricing::tax_exempt(parse_flag(&cloned.tax_exempt))
+                    .to_field(),
+                accepts_marketing: accepts_email,
                 created_at: Utc::now(),
                 updated_at: Utc::now(),
             }
@@ -571,6 +1079,87 @@ impl Parsable<CustomerRecord> for Customer {
     }
 }
 
+/// Parses a customer addresses export, attaching each address to the
+/// customer with the same email. A customer's default address becomes
+/// their contact address, and any others are kept as notes.
+///
+/// No customers are created, so the returned list is always empty.
+pub fn parse_customer_addresses(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Customer>, ParseFailure> {
+    for record in reader.deserialize::<CustomerAddressRecord>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        let customer = match db.1.iter_mut().find(|customer| {
+            customer
+                .contact
+                .email
+                .full
+                .eq_ignore_ascii_case(&record.email)
+        }) {
+            Some(customer) => customer,
+            None => {
+                println!(
+                    "[warn]: No customer with email {} for address {}.",
+                    record.email, record.address_street
+                );
+                continue;
+            }
+        };
+
+        let address = Address {
+            street: record.address_street.clone(),
+            street2: record.address_suburb.clone(),
+            city: city_with_province(
+                &record.address_city,
+                &record.address_province,
+                &record.address_province_code,
+            ),
+            country: country_or_code(&record.address_country, &record.address_country_code),
+            po_code: record.address_zip.clone(),
+            lat: 0.0,
+            lon: 0.0,
+        };
+
+        if customer.contact.address == address {
+            continue;
+        }
+
+        let additional = if parse_flag(&record.default_address) {
+            std::mem::replace(&mut customer.contact.address, address)
+        } else {
+            address
+        };
+
+        if !additional.street.is_empty() || !additional.city.is_empty() {
+            customer.customer_notes.push(import_note(format!(
+                "Address: {}",
+                [
+                    additional.street,
+                    additional.street2,
+                    additional.city,
+                    additional.country,
+                    additional.po_code,
+                ]
+                .iter()
+                .filter(|part| !part.is_empty())
+                .cloned()
+                .collect::<Vec<String>>()
+                .join(", ")
+            )));
+        }
+    }
+
+    Ok(vec![])
+}
+
 impl Parsable<TransactionRecord> for Transaction {
     fn parse_individual(
         reader: &[Result<TransactionRecord, csv::Error>],
@@ -622,27 +1211,82 @@ impl Parsable<TransactionRecord> for Transaction {
                 c
             };
 
+            let store = resolve_store(&cloned.location, db);
+            let kiosk = match (&store, cloned.device_id.trim()) {
+                (Some(store), device_id) if !device_id.is_empty() => {
+                    resolve_kiosk(&format!("Shopify POS {}", device_id), store, db)
+                }
+                _ => None,
+            };
+            let employee = resolve_employee(&cloned.employee, db);
+
+            let order_type = match_order_type(cloned);
+
+            let promotion =
+                db.6.iter()
+                    .find(|promotion| {
+                        !cloned.discount_code.is_empty()
+                            && promotion.name.eq_ignore_ascii_case(&cloned.discount_code)
+                    })
+                    .cloned();
+
+            // The order saved the amount it was discounted by, whatever the
+            // promotion would give, so its exact amount is kept in a note.
+            let (discount, saved) = parse_discount(&cloned.discount_amount, false)
+                .unwrap_or((DiscountValue::Absolute(0), 0.0));
+
+            // Orders have no field for the promotion they redeemed,
+            // so the code is linked to it through an order note.
+            let order_notes = match (&promotion, cloned.discount_code.is_empty()) {
+                (Some(promotion), _) => vec![import_note(format!(
+                    "Discount code {} redeemed for {:.2}, promotion {}",
+                    cloned.discount_code, saved, promotion.id
+                ))],
+                (None, false) => vec![import_note(format!(
+                    "Discount code {} redeemed for {:.2}",
+                    cloned.discount_code, saved
+                ))],
+                (None, true) if saved.fract() != 0.0 => {
+                    vec![import_note(format!("Discounted by {:.2}", saved))]
+                }
+                (None, true) => vec![],
+            };
+
+            // Online orders are sold through the Online Store sales channel,
+            // which stands as their origin however the export's rows are ordered.
+            let store = match store.or_else(|| resolve_store(ONLINE_STORE, db)) {
+                Some(store) => store,
+                None => {
+                    return Err(ParseFailure::FormatFailure(format!(
+                        "Order {} has no store to originate from.",
+                        cloned.order_name
+                    )))
+                }
+            };
+
+            let origin = Location {
+                contact: store.contact.clone(),
+                store_code: store.code.clone(),
+                store_id: store.id.clone(),
+            };
+
+            let destination = Location {
+                contact: match order_type {
+                    OrderType::Shipment => {
+                        shipping_contact(cloned).unwrap_or(customer.contact.clone())
+                    }
+                    OrderType::Pickup => origin.contact.clone(),
+                    _ => customer.contact.clone(),
+                },
+                store_code: origin.store_code.clone(),
+                store_id: origin.store_id.clone(),
+            };
+
             (
                 Order {
                     id: Uuid::new_v4().to_string(),
-                    destination: Location {
-                        contact: customer.contact.clone(),
-                        // Shopify won't permit exporting
-                        // stores, so we have to do it manually.
-                        store_code: "000".to_string(),
-                        store_id: "000".to_string(),
-                    },
-                    origin: Location {
-                        // As we do not know what store is actually being utilized,
-                        // we must default the contact information to the customer.
-                        // This is NOT recommended, nor endorsed but rather out of
-                        // necessity due to shopify's lack of transparency.
-                        contact: customer.contact,
-                        // Shopify won't permit exporting
-                        // stores, so we have to do it manually.
-                        store_code: "000".to_string(),
-                        store_id: "000".to_string(),
-                    },
+                    destination,
+                    origin,
                     products: vec![],
                     status: open_stock::OrderStatusAssignment {
                         status: open_stock::OrderStatus::Fulfilled(
@@ -655,12 +1299,12 @@ impl Parsable<TransactionRecord> for Transaction {
                     status_history: vec![],
                     order_history: vec![],
                     previous_failed_fulfillment_attempts: vec![],
-                    order_notes: vec![],
+                    order_notes,
                     reference: cloned.order_name.clone(),
                     creation_date: DateTime::from_str(cloned.created_at.as_str())
                         .unwrap_or(Utc::now()),
-                    discount: DiscountValue::Absolute(0),
-                    order_type: open_stock::OrderType::Shipment,
+                    discount,
+                    order_type,
                 },
                 Transaction {
                     id: cloned.id.clone(),
@@ -668,35 +1312,16 @@ impl Parsable<TransactionRecord> for Transaction {
                         customer_type: open_stock::CustomerType::Individual,
                         customer_id: customer.id,
                     },
-                    kiosk: db.4.get(0).map_or("".to_owned(), |kiosk| kiosk.id.clone()),
+                    // Online orders are rung up at no register.
+                    kiosk: kiosk.map_or(String::new(), |kiosk| kiosk.id),
                     transaction_type: open_stock::TransactionType::Out,
                     products: vec![],
                     order_total: cloned.total.clone().parse::<i64>().unwrap_or(0),
-                    payment: vec![Payment {
-                        id: uuid::Uuid::new_v4().to_string(),
-                        payment_method: PaymentMethod::Other(cloned.payment_method.clone()),
-                        fulfillment_date: DateTime::from_str(cloned.paid_at.as_str())
-                            .unwrap_or(Utc::now()),
-                        amount: Price {
-                            quantity: cloned.total.parse::<f32>().unwrap_or(0.0),
-                            currency: cloned.currency.clone(),
-                        },
-                        processing_fee: Price {
-                            quantity: cloned.total.parse::<f32>().unwrap_or(0.0),
-                            currency: cloned.currency.clone(),
-                        },
-                        status: open_stock::PaymentStatus::Complete(
-                            open_stock::Processable::Anonymous(String::from("shopify")),
-                        ),
-                        processor: PaymentProcessor::anonymous(String::from("shopify")),
-                        order_ids: vec![],
-                        delay_action: open_stock::PaymentAction::Complete,
-                        delay_duration: String::new(),
-                    }],
+                    payment: build_payments(cloned),
                     order_date: DateTime::from_str(cloned.created_at.as_str())
                         .unwrap_or(Utc::now()),
                     order_notes: vec![],
-                    salesperson: String::new(),
+                    salesperson: employee.map_or(String::new(), |employee| employee.id),
                     created_at: DateTime::from_str(cloned.created_at.as_str())
                         .unwrap_or(Utc::now()),
                     updated_at: Utc::now(),
@@ -720,12 +1345,11 @@ impl Parsable<TransactionRecord> for Transaction {
                 product_name: cloned.lineitem_name.clone(),
                 product_sku: cloned.lineitem_sku.clone(),
                 product_cost: cloned.lineitem_price.parse::<f32>().unwrap_or(0.0),
-                discount: DiscountValue::Absolute(
-                    cloned.discount_amount.parse::<u32>().unwrap_or(0),
-                ),
+                discount: parse_discount(&cloned.lineitem_discount, false)
+                    .map_or(DiscountValue::Absolute(0), |(discount, _)| discount),
                 product_variant_name: cloned.lineitem_name.clone(),
                 quantity,
-                tags: vec![cloned.tags.clone()],
+                tags: parse_tags(&cloned.tags, ',', lowercase_tags()),
                 transaction_type: open_stock::TransactionType::Out,
                 instances: fill_instances(
                     FulfillmentStatus {
@@ -755,18 +1379,7 @@ impl Parsable<ProductRecord> for Product {
     ) -> Result<Product, ParseFailure> {
         let init_line = *line;
 
-        // Shopify will not provide any information like this,
-        // so we must freshly generate it.
-        let generated_sku = uuid::Uuid::new_v4().to_string();
-        let pdt_ident = open_stock::ProductIdentification {
-            sku: generated_sku.clone(),
-            ean: String::new(),
-            hs_code: String::new(),
-            article_code: String::new(),
-            isbn: String::new(),
-        };
-
-        let (mut product, options): (Product, Option<Options>) = {
+        let (mut product, options, status): (Product, Option<Options>, ProductStatus) = {
             // Generate Variant Groups
             let mut vcs = vec![];
 
@@ -809,21 +1422,30 @@ impl Parsable<ProductRecord> for Product {
                 vcs.push(vc);
             }
 
+            let status = ProductStatus::from(cloned.status.as_str());
+
+            // Shopify only identifies variants, the handle
+            // is the only stable identifier of the product.
+            let identification = ProductIdentification {
+                sku: cloned.handle.clone(),
+                ..Default::default()
+            };
+
             (
                 Product {
                     name: (*cloned.title.clone()).to_string(),
                     company: (*cloned.vendor.clone()).to_string(),
                     variant_groups: vcs,
                     variants: vec![],
-                    sku: generated_sku,
-                    images: vec![(*cloned.image_url.clone()).to_string()],
-                    tags: vec![(*cloned.tags.clone()).to_string()],
+                    sku: cloned.handle.clone(),
+                    images: vec![],
+                    tags: parse_tags(&cloned.tags, ',', lowercase_tags()),
                     description: (*cloned.body.clone()).to_string(),
                     specifications: vec![],
                     name_long: (*cloned.title.clone()).to_string(),
-                    identification: pdt_ident.clone(),
+                    identification,
                     description_long: (*cloned.body.clone()).to_string(),
-                    visible: open_stock::ProductVisibility::ShowWhenInStock,
+                    visible: status.visibility(parse_flag(&cloned.published)),
                     created_at: Utc::now(),
                     updated_at: Utc::now(),
                 },
@@ -832,20 +1454,47 @@ impl Parsable<ProductRecord> for Product {
                     option_2_name: (*cloned.option_2_name.clone()).to_string(),
                     option_3_name: (*cloned.option_3_name.clone()).to_string(),
                 }),
+                status,
             )
         };
 
+        if let Some(Ok(record)) = reader.get(init_line) {
+            apply_catalog_attributes(&mut product, record);
+        }
+
+        let mut gallery: Vec<GalleryImage> = vec![];
+
         // Keep parsing till reached.
         while let Some(val) = reader.get(*line) {
             let cloned = (*val).as_ref().unwrap();
 
-            if (*cloned.title.clone()).to_string() != "" && *line.deref() != init_line
-                || (*cloned.title.clone()).to_string() == "" && cloned.price.is_empty()
+            if *line.deref() != init_line
+                && ((*cloned.title.clone()).to_string() != "" || cloned.handle != product.sku)
             {
                 // End of valid product range
                 break;
             }
 
+            if !cloned.image_url.is_empty() {
+                gallery.push(GalleryImage {
+                    position: cloned.image_pos.parse::<u32>().unwrap_or(u32::MAX),
+                    src: (*cloned.image_url.clone()).to_string(),
+                    alt: (*cloned.image_alt.clone()).to_string(),
+                });
+            }
+
+            if (*cloned.title.clone()).to_string() == "" && cloned.price.is_empty() {
+                // Image-only row, which only extends the gallery.
+                *line += 1;
+                continue;
+            }
+
+            let variant_images = if cloned.variant_image.is_empty() {
+                vec![]
+            } else {
+                vec![(*cloned.variant_image.clone()).to_string()]
+            };
+
             let mut actual_title = format!(
                 "{} {} {}",
                 &(*cloned.option_1_value.clone()),
@@ -863,7 +1512,12 @@ impl Parsable<ProductRecord> for Product {
                 Err(err) => return Err(ParseFailure::FormatFailure(err.to_string())),
             };
 
-            let stock = match _db.3.get(0) {
+            // Gift cards are a liability once sold rather than
+            // a stocked item, so hold no stock of their own.
+            let is_gift_card = parse_flag(&cloned.is_gift_card);
+
+            let stock = match _db.3.first() {
+                _ if is_gift_card => vec![],
                 Some(store) => {
                     vec![Stock {
                         store: Location {
@@ -885,10 +1539,17 @@ impl Parsable<ProductRecord> for Product {
                 }
             };
 
+            if !cloned.barcode.is_empty() && Barcode::parse(&cloned.barcode).is_none() {
+                println!(
+                    "[warn]: Barcode {} of {} is not a valid EAN, UPC or ISBN.",
+                    cloned.barcode, cloned.sku
+                );
+            }
+
             let variant = VariantInformation {
                 name: actual_title,
                 stock, // Stock must be loaded from a stock CSV in shopify
-                images: vec![(*cloned.variant_image.clone()).to_string()],
+                images: variant_images.clone(),
                 retail_price: price,
                 marginal_price: cloned.marginal_cost.parse::<f32>().unwrap_or(price),
                 loyalty_discount: DiscountValue::Absolute(0),
@@ -900,17 +1561,22 @@ impl Parsable<ProductRecord> for Product {
                     value_stream: String::new(),
                     brand: (*cloned.vendor.clone()).to_string(),
                     tax_code: (*cloned.tax_code.clone()).to_string(),
-                    weight: (*cloned.weight_grams.clone()).to_string(),
+                    // `Variant Grams` is always in grams, `Variant Weight Unit`
+                    // is only the unit the merchant chose to display it in.
+                    weight: weight_in_kilograms(&cloned.weight_grams, "g")
+                        .unwrap_or(0.0)
+                        .to_string(),
                     volume: "0.00".to_string(),
                     max_volume: "0.00".to_string(),
-                    back_order: false,
-                    discontinued: (cloned.status.clone()) == "active",
-                    non_diminishing: false,
-                    shippable: (cloned.requires_shipping.clone()) == "TRUE",
-                    size_override_unit: (*cloned.weight_unit.clone()).to_string(),
-                    size_x_unit: (*cloned.weight_unit.clone()).to_string(),
-                    size_y_unit: (*cloned.weight_unit.clone()).to_string(),
-                    size_z_unit: (*cloned.weight_unit.clone()).to_string(),
+                    back_order: cloned.vip == "continue",
+                    discontinued: status.discontinued(),
+                    non_diminishing: is_gift_card,
+                    shippable: parse_flag(&cloned.requires_shipping),
+                    // Shopify does not export product dimensions.
+                    size_override_unit: String::new(),
+                    size_x_unit: String::new(),
+                    size_y_unit: String::new(),
+                    size_z_unit: String::new(),
                     size_x: 0.0,
                     size_y: 0.0,
                     size_z: 0.0,
@@ -924,8 +1590,8 @@ impl Parsable<ProductRecord> for Product {
                 // Considers if the quantity is a decimal,
                 // otherwise would take value `1.0`.
                 buy_min: 0.0,
-                identification: pdt_ident.clone(),
-                stock_tracking: true,
+                identification: identify(&cloned.sku, &cloned.barcode),
+                stock_tracking: !is_gift_card,
             };
 
             let options = options.clone();
@@ -933,7 +1599,7 @@ impl Parsable<ProductRecord> for Product {
             if !cloned.option_1_value.is_empty() {
                 let vc: Variant = Variant {
                     name: (*cloned.option_1_value.clone()).to_string(),
-                    images: vec![(*cloned.variant_image.clone()).to_string()],
+                    images: variant_images.clone(),
                     marginal_price: 0.00,
                     variant_code: format!(
                         "{}-{}",
@@ -981,7 +1647,7 @@ impl Parsable<ProductRecord> for Product {
             if !cloned.option_2_value.is_empty() {
                 let vc: Variant = Variant {
                     name: (*cloned.option_2_value.clone()).to_string(),
-                    images: vec![(*cloned.variant_image.clone()).to_string()],
+                    images: variant_images.clone(),
                     marginal_price: 0.00,
                     variant_code: format!(
                         "{}-{}",
@@ -1029,7 +1695,7 @@ impl Parsable<ProductRecord> for Product {
             if !cloned.option_3_value.is_empty() {
                 let vc: Variant = Variant {
                     name: (*cloned.option_3_value.clone()).to_string(),
-                    images: vec![(*cloned.variant_image.clone()).to_string()],
+                    images: variant_images.clone(),
                     marginal_price: 0.00,
                     variant_code: format!(
                         "{}-{}",
@@ -1078,10 +1744,191 @@ impl Parsable<ProductRecord> for Product {
             *line += 1;
         }
 
+        // `sort_by_key` is stable, so images without a
+        // position keep the order they were listed in.
+        gallery.sort_by_key(|image| image.position);
+
+        for image in gallery {
+            if product.images.contains(&image.src) {
+                continue;
+            }
+
+            if !image.alt.is_empty() {
+                product.specifications.push((
+                    format!("Image {} Alt Text", product.images.len() + 1),
+                    image.alt,
+                ));
+            }
+
+            product.images.push(image.src);
+        }
+
+        // Variant images are usually also listed in the gallery,
+        // any which are not are appended so none are lost.
+        for variant in &product.variants {
+            for image in &variant.images {
+                if !product.images.contains(image) {
+                    product.images.push(image.clone());
+                }
+            }
+        }
+
         Ok(product)
     }
 }
 
+impl Parsable<PromotionRecord> for Promotion {
+    fn parse_individual(
+        reader: &[Result<PromotionRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Self, ParseFailure>
+    where
+        Self: Sized,
+    {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let free_shipping = cloned.discount_type == "Free shipping";
+        let percentage = cloned.value_type == "percentage";
+
+        // Shopify exports the value as the (negative) change to the price,
+        // and leaves it empty for free shipping.
+        let (discount, value) = match parse_discount(&cloned.value, percentage) {
+            Some(discount) => discount,
+            None if free_shipping && cloned.value.trim().is_empty() => {
+                (DiscountValue::Absolute(0), 0.0)
+            }
+            None => {
+                return Err(ParseFailure::FormatFailure(format!(
+                    "Discount {} has a value of {} which is not numeric.",
+                    cloned.code, cloned.value
+                )))
+            }
+        };
+
+        let quantity = match cloned.minimum_requirement.as_str() {
+            "Minimum quantity of items" => cloned
+                .minimum_requirement_value
+                .parse::<f32>()
+                .unwrap_or(1.0),
+            _ => 1.0,
+        };
+
+        let get = match cloned.discount_type.as_str() {
+            "Amount off products" => PromotionGet::SoloThis(discount),
+            "Amount off order" => PromotionGet::Any((quantity, discount)),
+            "Buy X get Y" => PromotionGet::This((quantity, discount)),
+            // Promotions only discount products, so the
+            // waived shipping is kept in the promotion's terms.
+            "Free shipping" => PromotionGet::Any((quantity, discount)),
+            other => {
+                return Err(ParseFailure::FormatFailure(format!(
+                    "Discount {} is of unsupported type {}.",
+                    cloned.code, other
+                )))
+            }
+        };
+
+        let id = Uuid::new_v4().to_string();
+
+        db.8.push(PromotionTerms {
+            promotion_id: id.clone(),
+            code: cloned.code.clone(),
+            value,
+            percentage,
+            free_shipping,
+            usage_limit: cloned.usage_limit.trim().parse::<u32>().ok(),
+            times_used: cloned.times_used.trim().parse::<u32>().unwrap_or(0),
+        });
+
+        Ok(Promotion {
+            id,
+            name: cloned.code.clone(),
+            buy: PromotionBuy::Any(quantity),
+            get,
+            valid_till: if cloned.status == "Expired" {
+                parse_timestamp(&cloned.ends_at).unwrap_or(Utc::now())
+            } else {
+                parse_timestamp(&cloned.ends_at).unwrap_or(DateTime::<Utc>::MAX_UTC)
+            },
+            timestamp: parse_timestamp(&cloned.starts_at).unwrap_or(Utc::now()),
+        })
+    }
+}
+
+impl Parsable<GiftCardRecord> for GiftCard {
+    fn parse_individual(
+        reader: &[Result<GiftCardRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Self, ParseFailure>
+    where
+        Self: Sized,
+    {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let balance = match cloned.balance.parse::<f32>() {
+            Ok(balance) => balance,
+            Err(error) => return Err(ParseFailure::FormatFailure(error.to_string())),
+        };
+
+        let customer_id =
+            db.1.iter()
+                .find(|customer| {
+                    !cloned.email.is_empty()
+                        && customer
+                            .contact
+                            .email
+                            .full
+                            .eq_ignore_ascii_case(&cloned.email)
+                })
+                .map(|customer| customer.id.clone());
+
+        if customer_id.is_none() && !cloned.email.is_empty() {
+            println!(
+                "[warn]: No customer with email {} for gift card ending {}.",
+                cloned.email, cloned.last_characters
+            );
+        }
+
+        Ok(GiftCard {
+            id: Uuid::new_v4().to_string(),
+            code: cloned.last_characters.clone(),
+            initial_value: cloned.initial_value.parse::<f32>().unwrap_or(balance),
+            balance,
+            currency: cloned.currency.clone(),
+            customer_id,
+            order_reference: cloned.order_name.clone(),
+            note: cloned.note.clone(),
+            disabled: !cloned.disabled_at.is_empty(),
+            // Cards expire at the end of their expiry date.
+            expires_at: NaiveDate::parse_from_str(&cloned.expires_on, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(23, 59, 59))
+                .map(|expiry| expiry.and_utc()),
+            created_at: parse_timestamp(&cloned.created_at).unwrap_or(Utc::now()),
+        })
+    }
+}
+
 impl Parsable<KioskRecord> for Kiosk {
     fn parse_individual(
         _reader: &[Result<KioskRecord, csv::Error>],
@@ -1097,9 +1944,9 @@ impl Parsable<KioskRecord> for Kiosk {
             return Ok(Kiosk {
                 id: uuid::Uuid::new_v4().to_string(),
                 name: "Default Kiosk".to_string(),
-                store_id: (&_db)
+                store_id: _db
                     .3
-                    .get(0)
+                    .first()
                     .map_or("".to_owned(), |store| store.id.clone()),
                 preferences: KioskPreferences {
                     printer_id: "".to_string(),
@@ -0,0 +1,278 @@
+//! Stripe payments exports.
+//!
+//! The charges of an online store, which settle the payments of the orders
+//! already imported. A charge is matched to its order by the `order_id`
+//! metadata the store attaches to it, or else by the order number which ends
+//! its description (such as `Order #1001`). Each matched payment is given the
+//! charge's fee, status and card, with the charge id as its processor token.
+//! Failed and refunded charges are kept alongside the payments they would
+//! have settled, which are left for the charges which did.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_payment, find_transaction, match_payment_method, resolve_payment, PaymentKind,
+        PaymentMethodTable,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::Reader;
+use open_stock::{
+    CardDetails, Note, PaymentAction, PaymentProcessor, PaymentStatus, PaymentTimeline,
+    Processable, Transaction,
+};
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+
+use super::ParseType;
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "ZZZZ",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "ZZZZ",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "ZZZZ",
+        ParseType::TransactionItem => "id,Created (UTC),Amount,Amount Refunded,Currency,Captured,Converted Amount,Converted Amount Refunded,Converted Currency,Decline Reason,Description,Fee,Refunded date (UTC),Statement Descriptor,Status,Seller Message,Taxes On Fee,Card ID,Customer ID,Customer Description,Customer Email,Card Brand,Card Funding,Card Last4,Card Exp Month,Card Exp Year,Card Fingerprint,Card Country,Card Address Line1 Check,Card CVC Check,Card Address Zip Check,Invoice ID,Transfer,PaymentIntent ID,Payment Source Type,Destination,order_id (metadata)",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+static PAYMENT_METHODS: PaymentMethodTable = phf_map! {
+    "card" => PaymentKind::Card,
+    "card_present" => PaymentKind::Card,
+    "ach_debit" => PaymentKind::Transfer,
+    "ach_credit_transfer" => PaymentKind::Transfer,
+    "bacs_debit" => PaymentKind::Transfer,
+    "sepa_debit" => PaymentKind::Transfer,
+    "au_becs_debit" => PaymentKind::Transfer,
+};
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "STRIPE-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Reads the times of exports, given in UTC as `2024-01-15 10:32` (or
+/// with seconds, depending on the dashboard the export is taken from).
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value.trim(), format).ok())
+        .map(|timestamp| timestamp.and_utc())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PaymentRecord {
+    /// The charge id, such as `ch_3OZ...`.
+    #[serde(rename = "id")]
+    id: String,
+
+    #[serde(rename = "Created (UTC)")]
+    created: String,
+
+    #[serde(rename = "Amount")]
+    amount: String,
+
+    #[serde(rename = "Amount Refunded")]
+    amount_refunded: String,
+
+    #[serde(rename = "Currency")]
+    currency: String,
+
+    #[serde(rename = "Captured")]
+    captured: String,
+
+    #[serde(rename = "Decline Reason")]
+    decline_reason: String,
+
+    #[serde(rename = "Description")]
+    description: String,
+
+    #[serde(rename = "Fee")]
+    fee: String,
+
+    #[serde(rename = "Statement Descriptor")]
+    statement_descriptor: String,
+
+    /// `Paid`, `Refunded`, `Partially Refunded`, `Uncaptured` or `Failed`.
+    #[serde(rename = "Status")]
+    status: String,
+
+    #[serde(rename = "Card Brand")]
+    card_brand: String,
+
+    /// `credit`, `debit` or `prepaid`.
+    #[serde(rename = "Card Funding")]
+    card_funding: String,
+
+    #[serde(rename = "Card Last4")]
+    card_last4: String,
+
+    #[serde(rename = "Card Exp Month")]
+    card_exp_month: String,
+
+    #[serde(rename = "Card Exp Year")]
+    card_exp_year: String,
+
+    #[serde(rename = "Card Fingerprint")]
+    card_fingerprint: String,
+
+    #[serde(rename = "Card Address Zip Check")]
+    card_address_zip_check: String,
+
+    #[serde(rename = "Card CVC Check")]
+    card_cvc_check: String,
+
+    #[serde(rename = "Payment Source Type")]
+    payment_source_type: String,
+
+    /// Only exported when charges carry the metadata.
+    #[serde(rename = "order_id (metadata)", default)]
+    order_id: String,
+}
+
+impl PaymentRecord {
+    /// The reference the description ends with is tried first, as stores
+    /// may put the order's id rather than its reference in the metadata,
+    /// which could match the reference of another order.
+    fn order_references(&self) -> [&str; 2] {
+        [
+            self.description.rsplit(' ').next().unwrap_or_default(),
+            &self.order_id,
+        ]
+    }
+
+    fn card(&self) -> Processable {
+        Processable::CardDetails(Box::new(CardDetails {
+            card_brand: self.card_brand.clone(),
+            last_4: self.card_last4.clone(),
+            exp_month: self.card_exp_month.clone(),
+            exp_year: self.card_exp_year.clone(),
+            fingerprint: self.card_fingerprint.clone(),
+            card_type: self.card_funding.clone(),
+            prepaid_type: String::new(),
+            bin: String::new(),
+            entry_method: self.payment_source_type.clone(),
+            cvv_accepted: self.card_cvc_check.clone(),
+            avs_accepted: self.card_address_zip_check.clone(),
+            auth_result_code: String::new(),
+            statement_description: self.statement_descriptor.clone(),
+            card_payment_timeline: PaymentTimeline {
+                authorized_at: self.created.clone(),
+                captured_at: if self.captured.eq_ignore_ascii_case("true") {
+                    self.created.clone()
+                } else {
+                    String::new()
+                },
+            },
+        }))
+    }
+}
+
+/// Settles the payments of the transactions already imported with the
+/// charges which paid them. Failed and refunded charges are noted on their
+/// transaction, and added to it without settling its payments.
+pub fn parse_payments(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Transaction>, ParseFailure> {
+    for record in reader.deserialize::<PaymentRecord>() {
+        let record = match record {
+            Ok(record) => record,
+            Err(error) => {
+                println!("[warn]: Parser Warning: {:?}", error);
+                continue;
+            }
+        };
+
+        let found = record
+            .order_references()
+            .into_iter()
+            .find(|reference| find_transaction(reference, db).is_some());
+
+        let transaction = match found.and_then(|reference| find_transaction(reference, db)) {
+            Some(transaction) => transaction,
+            None => {
+                println!(
+                    "[warn]: The order of charge {} ({}) is not in the export.",
+                    record.id, record.description
+                );
+                continue;
+            }
+        };
+
+        let status = record.status.to_lowercase();
+        let currency = record.currency.to_uppercase();
+        let amount = record.amount.parse::<f32>().unwrap_or(0.0);
+        let refunded = record.amount_refunded.parse::<f32>().unwrap_or(0.0);
+
+        if status == "failed" {
+            transaction.order_notes.push(import_note(format!(
+                "Charge {} of {:.2} {} failed: {}",
+                record.id, amount, currency, record.decline_reason
+            )));
+        }
+
+        if refunded > 0.0 {
+            transaction.order_notes.push(import_note(format!(
+                "Refunded {:.2} {} of charge {}",
+                refunded, currency, record.id
+            )));
+        }
+
+        let method = match_payment_method(&record.payment_source_type, &PAYMENT_METHODS);
+
+        // Charges which never paid for the order settle none of its payments.
+        let payment = if status == "failed" || status == "refunded" {
+            add_payment(transaction, method, amount, &currency, "stripe")
+        } else {
+            resolve_payment(transaction, method, amount, &currency, "stripe")
+        };
+
+        if let Some(created) = parse_timestamp(&record.created) {
+            payment.fulfillment_date = created;
+        }
+
+        if payment.amount.currency.is_empty() {
+            payment.amount.currency = currency.clone();
+        }
+
+        payment.processing_fee.quantity = record.fee.parse::<f32>().unwrap_or(0.0);
+        payment.processing_fee.currency = currency.clone();
+        payment.processor = PaymentProcessor {
+            token: record.id.clone(),
+            ..PaymentProcessor::anonymous(String::from("stripe"))
+        };
+
+        (payment.status, payment.delay_action) = match status.as_str() {
+            "uncaptured" => (
+                PaymentStatus::Pending(String::from("stripe")),
+                PaymentAction::RequireFurtherAction,
+            ),
+            "failed" => (PaymentStatus::Failed(record.card()), PaymentAction::Cancel),
+            "refunded" => (
+                PaymentStatus::Unfulfilled(String::from("stripe")),
+                PaymentAction::Cancel,
+            ),
+            _ => (
+                PaymentStatus::Complete(record.card()),
+                PaymentAction::Complete,
+            ),
+        };
+    }
+
+    Ok(vec![])
+}
@@ -0,0 +1,560 @@
+//! SumUp exports.
+//!
+//! The item library lists a row for each variation, the rows of an item
+//! following one another. The transactions report describes the items of
+//! each sale in a single column, such as `2 x Latte, 1 x Croissant`.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_variant_option, identify, lowercase_tags, merge_tags, named_contact, parse_flag,
+        resolve_employee, resolve_store, split_payment_methods, vat_tax_code, PaymentKind,
+        PaymentMethodTable,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::Reader;
+use open_stock::{
+    CustomerType, DiscountValue, FulfillmentStatus, Location, Note, Order, OrderStatus,
+    OrderStatusAssignment, OrderType, Payment, PaymentAction, PaymentMethod, PaymentProcessor,
+    PaymentStatus, PickStatus, Price, Processable, Product, ProductIdentification, ProductInstance,
+    ProductPurchase, ProductVisibility, Quantity, Stock, StockInformation, Store, Transaction,
+    TransactionCustomer, TransactionType, VariantInformation,
+};
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "Item Name,Variation,Category,Description,Price,Cost Price,Tax Rate,SKU,Barcode,Track Stock,Quantity,Low Stock Alert",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "ZZZZ",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "Transaction ID,Date,Time,Type,Status,Payment Method,Card Type,Description,Total Amount,Tip Amount,Fee,Payout,Currency,Employee",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The variant group the variations of an item are placed in,
+/// as the library does not name the option they vary by.
+const VARIATION: &str = "Variation";
+
+/// The store standing for the merchant, which holds the item library's stock.
+const MERCHANT: &str = "SumUp";
+
+pub static PAYMENT_METHODS: PaymentMethodTable = phf_map! {
+    "card" => PaymentKind::Card,
+    "pos" => PaymentKind::Card,
+    "ecom" => PaymentKind::Card,
+    "cash" => PaymentKind::Cash,
+    "other" => PaymentKind::Transfer,
+    "gift card" => PaymentKind::GiftCard,
+};
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "SUMUP-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Reads the separate date and time columns of a
+/// transaction, such as `01.05.2023` and `10:02`.
+fn parse_timestamp(date: &str, time: &str) -> Option<DateTime<Utc>> {
+    let value = format!("{} {}", date.trim(), time.trim());
+
+    ["%d.%m.%Y %H:%M", "%d.%m.%Y %H:%M:%S", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&value, format).ok())
+        .map(|timestamp| timestamp.and_utc())
+}
+
+/// Reads an amount, which is exported with a decimal comma in most locales.
+fn parse_amount(value: &str) -> f32 {
+    value.trim().replace(',', ".").parse::<f32>().unwrap_or(0.0)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "Item Name")]
+    name: String,
+
+    #[serde(rename = "Variation")]
+    variation: String,
+
+    #[serde(rename = "Category")]
+    category: String,
+
+    #[serde(rename = "Description")]
+    description: String,
+
+    #[serde(rename = "Price")]
+    price: String,
+
+    #[serde(rename = "Cost Price")]
+    cost_price: String,
+
+    #[serde(rename = "Tax Rate")]
+    tax_rate: String,
+
+    #[serde(rename = "SKU")]
+    sku: String,
+
+    #[serde(rename = "Barcode")]
+    barcode: String,
+
+    #[serde(rename = "Track Stock")]
+    track_stock: String,
+
+    #[serde(rename = "Quantity")]
+    quantity: String,
+
+    #[serde(rename = "Low Stock Alert")]
+    low_stock_alert: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionRecord {
+    #[serde(rename = "Transaction ID")]
+    transaction_id: String,
+
+    #[serde(rename = "Date")]
+    date: String,
+
+    #[serde(rename = "Time")]
+    time: String,
+
+    /// `Sales` or `Refund`.
+    #[serde(rename = "Type")]
+    transaction_type: String,
+
+    /// `Successful`, `Refunded`, `Failed` or `Cancelled`.
+    #[serde(rename = "Status")]
+    status: String,
+
+    #[serde(rename = "Payment Method")]
+    payment_method: String,
+
+    #[serde(rename = "Description")]
+    description: String,
+
+    #[serde(rename = "Total Amount")]
+    total: String,
+
+    #[serde(rename = "Tip Amount")]
+    tip: String,
+
+    #[serde(rename = "Fee")]
+    fee: String,
+
+    #[serde(rename = "Currency")]
+    currency: String,
+
+    #[serde(rename = "Employee")]
+    employee: String,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+fn location(store: &Store) -> Location {
+    Location {
+        store_code: store.code.clone(),
+        store_id: store.id.clone(),
+        contact: store.contact.clone(),
+    }
+}
+
+/// SumUp keeps a single item library for each merchant.
+fn merchant_store(db: &mut InlineDatabase) -> Option<Store> {
+    resolve_store(MERCHANT, db)
+}
+
+fn build_variant(record: &ProductRecord, store: &Option<Store>) -> VariantInformation {
+    let price = parse_amount(&record.price);
+    let stock_tracking = parse_flag(&record.track_stock);
+
+    let stock = match store {
+        Some(store) if stock_tracking => vec![Stock {
+            store: location(store),
+            quantity: Quantity {
+                quantity_sellable: parse_amount(&record.quantity),
+                quantity_unsellable: 0.0,
+                quantity_on_order: 0.0,
+                quantity_allocated: 0.0,
+            },
+        }],
+        _ => vec![],
+    };
+
+    VariantInformation {
+        name: if record.variation.is_empty() {
+            record.name.clone()
+        } else {
+            record.variation.clone()
+        },
+        stock,
+        images: vec![],
+        retail_price: price,
+        marginal_price: if record.cost_price.is_empty() {
+            price
+        } else {
+            parse_amount(&record.cost_price)
+        },
+        loyalty_discount: DiscountValue::Absolute(0),
+        variant_code: vec![record.sku.clone()],
+        order_history: vec![],
+        stock_information: StockInformation {
+            stock_group: String::new(),
+            sales_group: record.category.clone(),
+            value_stream: String::new(),
+            brand: String::new(),
+            tax_code: vat_tax_code(&record.tax_rate),
+            weight: "0".to_string(),
+            volume: "0.00".to_string(),
+            max_volume: "0.00".to_string(),
+            back_order: false,
+            discontinued: false,
+            non_diminishing: false,
+            shippable: true,
+            size_override_unit: String::new(),
+            size_x_unit: String::new(),
+            size_y_unit: String::new(),
+            size_z_unit: String::new(),
+            size_x: 0.0,
+            size_y: 0.0,
+            size_z: 0.0,
+            min_stock_before_alert: parse_amount(&record.low_stock_alert) as f64,
+            min_stock_level: 0.0,
+            colli: String::new(),
+        },
+        barcode: record.barcode.clone(),
+        id: Uuid::new_v4().to_string(),
+        buy_max: -1.0,
+        buy_min: 1.0,
+        identification: identify(&record.sku, &record.barcode),
+        stock_tracking,
+    }
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let first = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        let rows: Vec<&ProductRecord> = reader[*line..]
+            .iter()
+            .map_while(|row| row.as_ref().ok())
+            .take_while(|row| row.name == first.name)
+            .collect();
+
+        *line += rows.len();
+
+        // The item's category is kept as its only tag.
+        let mut tags = vec![];
+        merge_tags(
+            &mut tags,
+            [first.category.trim().to_string()],
+            lowercase_tags(),
+        );
+
+        let store = merchant_store(db);
+
+        let mut product = Product {
+            name: first.name.clone(),
+            company: String::new(),
+            variant_groups: vec![],
+            variants: vec![],
+            sku: first.sku.clone(),
+            images: vec![],
+            tags,
+            description: first.description.clone(),
+            specifications: vec![],
+            name_long: first.name.clone(),
+            identification: ProductIdentification {
+                sku: first.sku.clone(),
+                ..Default::default()
+            },
+            description_long: first.description.clone(),
+            visible: ProductVisibility::ShowWhenInStock,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        for row in &rows {
+            add_variant_option(&mut product, VARIATION, &row.variation);
+
+            product.variants.push(build_variant(row, &store));
+        }
+
+        Ok(product)
+    }
+}
+
+/// The SKU, variant code and price of an item in the library.
+type LibraryItem = (String, String, f32);
+
+/// Reads the items of a transaction's description, such
+/// as `2 x Latte, 1 x Croissant`, as quantities and names.
+fn parse_description(description: &str) -> Vec<(f32, String)> {
+    description
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| match item.split_once(" x ") {
+            Some((quantity, name)) => match quantity.trim().parse::<f32>() {
+                Ok(quantity) => (quantity, name.trim().to_string()),
+                Err(_) => (1.0, item.to_string()),
+            },
+            None => (1.0, item.to_string()),
+        })
+        .collect()
+}
+
+impl Parsable<TransactionRecord> for Transaction {
+    fn parse_individual(
+        reader: &[Result<TransactionRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Transaction, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        if !matches!(
+            cloned.status.trim().to_lowercase().as_str(),
+            "successful" | "refunded"
+        ) {
+            return Err(ParseFailure::FormatFailure(format!(
+                "Transaction {} was not completed, its status is {}.",
+                cloned.transaction_id, cloned.status
+            )));
+        }
+
+        let date = parse_timestamp(&cloned.date, &cloned.time).unwrap_or(Utc::now());
+        let transaction_type = if cloned.transaction_type.eq_ignore_ascii_case("refund") {
+            TransactionType::In
+        } else {
+            TransactionType::Out
+        };
+
+        let total = parse_amount(&cloned.total);
+        let tip = parse_amount(&cloned.tip);
+
+        let store = merchant_store(db);
+        let employee = resolve_employee(&cloned.employee, db);
+
+        let origin = match &store {
+            Some(store) => location(store),
+            None => Location {
+                contact: named_contact("SumUp"),
+                store_code: "000".to_string(),
+                store_id: "000".to_string(),
+            },
+        };
+
+        // Items are matched to the library for their price and code. The
+        // amount not accounted for by matched items is divided between the rest.
+        let items: Vec<(f32, String, Option<LibraryItem>)> = parse_description(&cloned.description)
+            .into_iter()
+            .map(|(quantity, name)| {
+                let matched =
+                    db.0.iter()
+                        .find(|product| product.name == name)
+                        .and_then(|product| {
+                            product.variants.first().map(|variant| {
+                                (
+                                    product.sku.clone(),
+                                    variant.variant_code.first().cloned().unwrap_or_default(),
+                                    variant.retail_price,
+                                )
+                            })
+                        });
+
+                (quantity, name, matched)
+            })
+            .collect();
+
+        let matched_amount: f32 = items
+            .iter()
+            .filter_map(|(quantity, _, matched)| {
+                matched.as_ref().map(|(_, _, price)| quantity * price)
+            })
+            .sum();
+        let unmatched_quantity: f32 = items
+            .iter()
+            .filter(|(_, _, matched)| matched.is_none())
+            .map(|(quantity, _, _)| quantity)
+            .sum();
+        let unmatched_price = if unmatched_quantity > 0.0 {
+            ((total.abs() - tip - matched_amount) / unmatched_quantity).max(0.0)
+        } else {
+            0.0
+        };
+
+        let products = items
+            .into_iter()
+            .map(|(quantity, name, matched)| {
+                let (sku, code, price) =
+                    matched.unwrap_or((String::new(), String::new(), unmatched_price));
+
+                ProductPurchase {
+                    id: Uuid::new_v4().to_string(),
+                    product_code: code,
+                    product_name: name.clone(),
+                    product_sku: sku,
+                    product_cost: price,
+                    discount: DiscountValue::Absolute(0),
+                    product_variant_name: name,
+                    quantity,
+                    tags: vec![],
+                    transaction_type: transaction_type.clone(),
+                    instances: (0..quantity.ceil() as u32)
+                        .map(|_| ProductInstance {
+                            id: Uuid::new_v4().to_string(),
+                            fulfillment_status: FulfillmentStatus {
+                                pick_status: PickStatus::Picked,
+                                pick_history: vec![],
+                                last_updated: date,
+                                notes: vec![],
+                            },
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let payment = Payment {
+            id: Uuid::new_v4().to_string(),
+            payment_method: split_payment_methods(&cloned.payment_method, &PAYMENT_METHODS)
+                .into_iter()
+                .next()
+                .map_or(
+                    PaymentMethod::Other(cloned.payment_method.clone()),
+                    |split| split.method,
+                ),
+            fulfillment_date: date,
+            amount: Price {
+                quantity: total,
+                currency: cloned.currency.clone(),
+            },
+            processing_fee: Price {
+                quantity: parse_amount(&cloned.fee),
+                currency: cloned.currency.clone(),
+            },
+            status: PaymentStatus::Complete(Processable::Anonymous(String::from("sumup"))),
+            processor: PaymentProcessor::anonymous(String::from("sumup")),
+            order_ids: vec![],
+            delay_action: PaymentAction::Complete,
+            delay_duration: String::new(),
+        };
+
+        let mut order_notes = vec![];
+
+        if tip > 0.0 {
+            order_notes.push(import_note(format!(
+                "Tip of {:.2} {} included in the total",
+                tip, cloned.currency
+            )));
+        }
+
+        if cloned.status.eq_ignore_ascii_case("refunded") {
+            order_notes.push(import_note("Refunded".to_string()));
+        }
+
+        Ok(Transaction {
+            id: Uuid::new_v4().to_string(),
+            customer: TransactionCustomer {
+                customer_type: CustomerType::Individual,
+                customer_id: String::new(),
+            },
+            kiosk: String::new(),
+            transaction_type,
+            products: vec![Order {
+                id: Uuid::new_v4().to_string(),
+                destination: origin.clone(),
+                origin,
+                products,
+                status: OrderStatusAssignment {
+                    status: OrderStatus::Fulfilled(date),
+                    assigned_products: vec![],
+                    timestamp: date,
+                },
+                status_history: vec![],
+                order_history: vec![],
+                previous_failed_fulfillment_attempts: vec![],
+                order_notes: order_notes.clone(),
+                reference: cloned.transaction_id.clone(),
+                creation_date: date,
+                discount: DiscountValue::Absolute(0),
+                order_type: OrderType::Direct,
+            }],
+            order_total: total.round() as i64,
+            payment: vec![payment],
+            order_date: date,
+            order_notes,
+            salesperson: employee.map_or(String::new(), |employee| employee.id),
+            created_at: date,
+            updated_at: Utc::now(),
+        })
+    }
+}
@@ -0,0 +1,860 @@
+//! Lightspeed X-Series (formerly Vend) exports.
+//!
+//! Distinct from Lightspeed R-Series, which `lightrail` targets. Stores are
+//! created from the outlets named in product inventory columns and sales.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_variant_option, identify, lowercase_tags, merge_tags, named_contact, parse_flag,
+        parse_tags, resolve_employee, resolve_kiosk, resolve_store, split_payment_methods,
+        PaymentKind, PaymentMethodTable, ProductStatus,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::{Reader, StringRecord};
+use open_stock::{
+    Address, ContactInformation, Customer, CustomerType, DiscountValue, Email, FulfillmentStatus,
+    Location, MobileNumber, Note, Order, OrderStatus, OrderStatusAssignment, OrderType, Payment,
+    PaymentAction, PaymentProcessor, PaymentStatus, PickStatus, Price, Processable, Product,
+    ProductIdentification, ProductInstance, ProductPurchase, Quantity, Stock, StockInformation,
+    Transaction, TransactionCustomer, TransactionType, VariantInformation,
+};
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "id,handle,sku,composite_handle,composite_sku,composite_quantity,name,description,type,variant_option_one_name,variant_option_one_value,variant_option_two_name,variant_option_two_value,variant_option_three_name,variant_option_three_value,tags,supply_price,retail_price,tax_name,tax_value,account_code,account_code_purchase,brand_name,supplier_name,supplier_code,active,track_inventory,inventory_Main_Outlet,reorder_point_Main_Outlet,restock_level_Main_Outlet",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "id,customer_code,first_name,last_name,email,year_to_date,balance,loyalty_balance,note,gender,date_of_birth,company_name,do_not_email,phone,mobile,fax,twitter,website,physical_address1,physical_address2,physical_suburb,physical_city,physical_postcode,physical_state,physical_country_id,postal_address1,postal_address2,postal_suburb,postal_city,postal_postcode,postal_state,postal_country_id,customer_group_name,enable_loyalty,created_at",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "Date,Receipt Number,Line Type,Customer Code,Customer Name,Note,Quantity,Subtotal,Sales Tax,Discount,Loyalty,Total,Paid,Details,Register,User,Status,Sku,AccountCodeSale,AccountCodePurchase",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The outlet every store starts with, which ledgers of a single outlet
+/// (that do not name it) were sold from.
+const MAIN_OUTLET: &str = "Main Outlet";
+
+/// The prefix of the per-outlet stock level columns of a product export.
+const INVENTORY_PREFIX: &str = "inventory_";
+
+/// Payment types are named by the merchant, these are the defaults.
+pub static PAYMENT_METHODS: PaymentMethodTable = phf_map! {
+    "cash" => PaymentKind::Cash,
+    "credit card" => PaymentKind::Card,
+    "eftpos" => PaymentKind::Card,
+    "card" => PaymentKind::Card,
+    "lightspeed payments" => PaymentKind::Card,
+    "vend payments" => PaymentKind::Card,
+    "bank transfer" => PaymentKind::Transfer,
+    "gift card" => PaymentKind::GiftCard,
+    "store credit" => PaymentKind::StoreCredit,
+    "on account" => PaymentKind::StoreCredit,
+    "loyalty" => PaymentKind::StoreCredit,
+};
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "VEND-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+/// Reads the timestamps of exports, which are in the
+/// retailer's local time as `2023-05-01 10:00:00`.
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value.trim(), "%Y-%m-%d %H:%M:%S")
+        .map(|timestamp| timestamp.and_utc())
+        .ok()
+}
+
+/// Outlet columns replace the spaces of the outlet's name with underscores.
+fn outlet_name(column: &str) -> Option<String> {
+    column
+        .strip_prefix(INVENTORY_PREFIX)
+        .filter(|outlet| !outlet.is_empty())
+        .map(|outlet| outlet.replace('_', " "))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "handle")]
+    handle: String,
+
+    #[serde(rename = "sku")]
+    sku: String,
+
+    #[serde(rename = "name")]
+    name: String,
+
+    #[serde(rename = "description")]
+    description: String,
+
+    #[serde(rename = "type")]
+    product_type: String,
+
+    #[serde(rename = "variant_option_one_name")]
+    option_1_name: String,
+
+    #[serde(rename = "variant_option_one_value")]
+    option_1_value: String,
+
+    #[serde(rename = "variant_option_two_name")]
+    option_2_name: String,
+
+    #[serde(rename = "variant_option_two_value")]
+    option_2_value: String,
+
+    #[serde(rename = "variant_option_three_name")]
+    option_3_name: String,
+
+    #[serde(rename = "variant_option_three_value")]
+    option_3_value: String,
+
+    #[serde(rename = "tags")]
+    tags: String,
+
+    #[serde(rename = "supply_price")]
+    supply_price: String,
+
+    #[serde(rename = "retail_price")]
+    retail_price: String,
+
+    #[serde(rename = "tax_name")]
+    tax_name: String,
+
+    #[serde(rename = "brand_name")]
+    brand_name: String,
+
+    #[serde(rename = "supplier_name")]
+    supplier_name: String,
+
+    #[serde(rename = "active")]
+    active: String,
+
+    #[serde(rename = "track_inventory")]
+    track_inventory: String,
+
+    /// The stock level at each outlet, read from
+    /// the `inventory_<Outlet>` columns.
+    #[serde(skip)]
+    inventory: Vec<(String, f32)>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerRecord {
+    #[serde(rename = "customer_code")]
+    customer_code: String,
+
+    #[serde(rename = "first_name")]
+    first_name: String,
+
+    #[serde(rename = "last_name")]
+    last_name: String,
+
+    #[serde(rename = "email")]
+    email: String,
+
+    #[serde(rename = "balance")]
+    balance: String,
+
+    #[serde(rename = "loyalty_balance")]
+    loyalty_balance: String,
+
+    #[serde(rename = "note")]
+    note: String,
+
+    #[serde(rename = "company_name")]
+    company: String,
+
+    #[serde(rename = "do_not_email")]
+    do_not_email: String,
+
+    #[serde(rename = "phone")]
+    phone: String,
+
+    #[serde(rename = "mobile")]
+    mobile: String,
+
+    #[serde(rename = "physical_address1")]
+    address_street: String,
+
+    #[serde(rename = "physical_address2")]
+    address_street2: String,
+
+    #[serde(rename = "physical_suburb")]
+    address_suburb: String,
+
+    #[serde(rename = "physical_city")]
+    address_city: String,
+
+    #[serde(rename = "physical_postcode")]
+    address_postcode: String,
+
+    #[serde(rename = "physical_state")]
+    address_state: String,
+
+    #[serde(rename = "physical_country_id")]
+    address_country: String,
+
+    #[serde(rename = "customer_group_name")]
+    customer_group: String,
+
+    #[serde(rename = "created_at")]
+    created_at: String,
+}
+
+/// A row of the sales ledger. Each sale is a `Sale` row,
+/// followed by a `Sale Line` per item and a `Payment` per tender.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionRecord {
+    #[serde(rename = "Date")]
+    date: String,
+
+    #[serde(rename = "Receipt Number")]
+    receipt_number: String,
+
+    #[serde(rename = "Line Type")]
+    line_type: String,
+
+    #[serde(rename = "Customer Code")]
+    customer_code: String,
+
+    #[serde(rename = "Customer Name")]
+    customer_name: String,
+
+    #[serde(rename = "Note")]
+    note: String,
+
+    #[serde(rename = "Quantity")]
+    quantity: String,
+
+    #[serde(rename = "Subtotal")]
+    subtotal: String,
+
+    #[serde(rename = "Discount")]
+    discount: String,
+
+    #[serde(rename = "Total")]
+    total: String,
+
+    #[serde(rename = "Paid")]
+    paid: String,
+
+    /// The product name of a sale line, or payment type of a payment.
+    #[serde(rename = "Details")]
+    details: String,
+
+    #[serde(rename = "Register")]
+    register: String,
+
+    #[serde(rename = "User")]
+    user: String,
+
+    #[serde(rename = "Status")]
+    status: String,
+
+    #[serde(rename = "Sku")]
+    sku: String,
+
+    /// Only present in ledgers exported across several outlets.
+    #[serde(rename = "Outlet", default)]
+    outlet: String,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+/// Parses a product export, which carries an `inventory_<Outlet>` column
+/// for each outlet. A store is created for every outlet.
+pub fn parse_products(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<Product>, ParseFailure> {
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(error) => return Err(ParseFailure::ReadFailure(error.to_string())),
+    };
+
+    for outlet in headers.iter().filter_map(outlet_name) {
+        resolve_store(&outlet, db);
+    }
+
+    let collected: Vec<Result<ProductRecord, csv::Error>> = reader
+        .records()
+        .map(|record| {
+            let record = record?;
+            let mut product: ProductRecord = record.deserialize(Some(&headers))?;
+            product.inventory = read_inventory(&headers, &record);
+            Ok(product)
+        })
+        .collect();
+
+    parse_collected(collected, db)
+}
+
+fn read_inventory(headers: &StringRecord, record: &StringRecord) -> Vec<(String, f32)> {
+    headers
+        .iter()
+        .zip(record.iter())
+        .filter_map(|(header, value)| {
+            outlet_name(header).map(|outlet| (outlet, value.trim().parse::<f32>().unwrap_or(0.0)))
+        })
+        .collect()
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let first = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        // Inactive products can no longer be sold.
+        let status = if parse_flag(&first.active) {
+            ProductStatus::Active
+        } else {
+            ProductStatus::Archived
+        };
+
+        let mut product = Product {
+            name: first.name.clone(),
+            company: if first.brand_name.is_empty() {
+                first.supplier_name.clone()
+            } else {
+                first.brand_name.clone()
+            },
+            variant_groups: vec![],
+            variants: vec![],
+            sku: first.handle.clone(),
+            images: vec![],
+            tags: parse_tags(&first.tags, ',', lowercase_tags()),
+            description: first.description.clone(),
+            specifications: vec![],
+            name_long: first.name.clone(),
+            // Variants are identified by their SKU, the
+            // handle is shared by every variant of the product.
+            identification: ProductIdentification {
+                sku: first.handle.clone(),
+                ..Default::default()
+            },
+            description_long: first.description.clone(),
+            visible: status.visibility(true),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        if !first.supplier_name.is_empty() {
+            product
+                .specifications
+                .push(("Supplier".to_string(), first.supplier_name.clone()));
+        }
+
+        // Each variant is a row of its own, sharing the product's handle.
+        while let Some(Ok(cloned)) = reader.get(*line) {
+            if cloned.handle != product.sku {
+                break;
+            }
+
+            *line += 1;
+
+            let price = match cloned.retail_price.parse::<f32>() {
+                Ok(p) => p,
+                Err(err) => {
+                    println!(
+                        "[warn]: Skipping variant {} of {}, {}.",
+                        cloned.sku, product.name, err
+                    );
+                    continue;
+                }
+            };
+
+            let stock_tracking = parse_flag(&cloned.track_inventory);
+
+            // Untracked products, such as services, hold no stock.
+            let stock = cloned
+                .inventory
+                .iter()
+                .filter(|_| stock_tracking)
+                .filter_map(|(outlet, quantity)| {
+                    let store = resolve_store(outlet, db)?;
+
+                    Some(Stock {
+                        store: Location {
+                            store_code: store.code.clone(),
+                            store_id: store.id.clone(),
+                            contact: store.contact.clone(),
+                        },
+                        quantity: Quantity {
+                            quantity_sellable: *quantity,
+                            quantity_unsellable: 0.0,
+                            quantity_on_order: 0.0,
+                            quantity_allocated: 0.0,
+                        },
+                    })
+                })
+                .collect();
+
+            let options = [
+                (&cloned.option_1_name, &cloned.option_1_value),
+                (&cloned.option_2_name, &cloned.option_2_value),
+                (&cloned.option_3_name, &cloned.option_3_value),
+            ];
+
+            for (category, value) in options {
+                add_variant_option(&mut product, category, value);
+            }
+
+            let name = options
+                .iter()
+                .map(|(_, value)| value.as_str())
+                .filter(|value| !value.is_empty())
+                .collect::<Vec<&str>>()
+                .join(" ");
+
+            merge_tags(
+                &mut product.tags,
+                parse_tags(&cloned.tags, ',', lowercase_tags()),
+                lowercase_tags(),
+            );
+
+            product.variants.push(VariantInformation {
+                name: if name.is_empty() {
+                    product.name.clone()
+                } else {
+                    name
+                },
+                stock,
+                images: vec![],
+                retail_price: price,
+                marginal_price: cloned.supply_price.parse::<f32>().unwrap_or(price),
+                loyalty_discount: DiscountValue::Absolute(0),
+                variant_code: vec![cloned.sku.clone()],
+                order_history: vec![],
+                stock_information: StockInformation {
+                    stock_group: cloned.product_type.clone(),
+                    sales_group: String::new(),
+                    value_stream: String::new(),
+                    brand: cloned.brand_name.clone(),
+                    tax_code: cloned.tax_name.clone(),
+                    // Vend does not export product weights or dimensions.
+                    weight: "0".to_string(),
+                    volume: "0.00".to_string(),
+                    max_volume: "0.00".to_string(),
+                    back_order: false,
+                    discontinued: status.discontinued(),
+                    non_diminishing: false,
+                    shippable: true,
+                    size_override_unit: String::new(),
+                    size_x_unit: String::new(),
+                    size_y_unit: String::new(),
+                    size_z_unit: String::new(),
+                    size_x: 0.0,
+                    size_y: 0.0,
+                    size_z: 0.0,
+                    min_stock_before_alert: 0.0,
+                    min_stock_level: 0.0,
+                    colli: String::new(),
+                },
+                // The export has no barcode column.
+                barcode: String::new(),
+                id: Uuid::new_v4().to_string(),
+                buy_max: -1.0,
+                buy_min: 0.0,
+                identification: identify(&cloned.sku, ""),
+                stock_tracking,
+            });
+        }
+
+        if let Some(Err(error)) = reader.get(*line) {
+            println!("[warn]: Parser Warning: {:?}", error);
+            *line += 1;
+        }
+
+        if product.variants.is_empty() {
+            return Err(ParseFailure::FormatFailure(format!(
+                "Product {} has no valid variants.",
+                product.name
+            )));
+        }
+
+        Ok(product)
+    }
+}
+
+impl Parsable<CustomerRecord> for Customer {
+    fn parse_individual(
+        reader: &[Result<CustomerRecord, csv::Error>],
+        line: &mut usize,
+        _db: &mut InlineDatabase,
+    ) -> Result<Customer, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let name = format!("{} {}", cloned.first_name, cloned.last_name)
+            .trim()
+            .to_string();
+
+        let mut customer_notes = vec![import_note(customer_code_note(&cloned.customer_code))];
+
+        if !cloned.note.is_empty() {
+            customer_notes.push(import_note(cloned.note.clone()));
+        }
+
+        if !cloned.customer_group.is_empty() {
+            customer_notes.push(import_note(format!(
+                "Customer group: {}",
+                cloned.customer_group
+            )));
+        }
+
+        // The units of `Customer.balance` are not those of Vend's
+        // balances, so they are noted to be carried over by hand.
+        for (kind, balance) in [
+            ("Account", &cloned.balance),
+            ("Loyalty", &cloned.loyalty_balance),
+        ] {
+            if balance.parse::<f32>().unwrap_or(0.0) != 0.0 {
+                customer_notes.push(import_note(format!("{} balance: {}", kind, balance)));
+            }
+        }
+
+        Ok(Customer {
+            id: Uuid::new_v4().to_string(),
+            name: if name.is_empty() {
+                cloned.company.clone()
+            } else {
+                name.clone()
+            },
+            contact: ContactInformation {
+                name: match (name.is_empty(), cloned.company.is_empty()) {
+                    (_, true) => name.clone(),
+                    (true, false) => cloned.company.clone(),
+                    (false, false) => format!("{} ({})", name, cloned.company),
+                },
+                mobile: MobileNumber::from(if cloned.mobile.is_empty() {
+                    cloned.phone.clone()
+                } else {
+                    cloned.mobile.clone()
+                }),
+                email: Email::from(cloned.email.clone()),
+                landline: cloned.phone.clone(),
+                address: Address {
+                    street: cloned.address_street.clone(),
+                    street2: [&cloned.address_street2, &cloned.address_suburb]
+                        .iter()
+                        .filter(|part| !part.is_empty())
+                        .map(|part| part.as_str())
+                        .collect::<Vec<&str>>()
+                        .join(", "),
+                    city: match (
+                        cloned.address_city.is_empty(),
+                        cloned.address_state.is_empty(),
+                    ) {
+                        (_, true) => cloned.address_city.clone(),
+                        (true, false) => cloned.address_state.clone(),
+                        (false, false) => {
+                            format!("{}, {}", cloned.address_city, cloned.address_state)
+                        }
+                    },
+                    country: cloned.address_country.clone(),
+                    po_code: cloned.address_postcode.clone(),
+                    lat: 0.0,
+                    lon: 0.0,
+                },
+            },
+            customer_notes,
+            balance: 0,
+            special_pricing: String::new(),
+            accepts_marketing: !cloned.email.is_empty() && !parse_flag(&cloned.do_not_email),
+            created_at: parse_timestamp(&cloned.created_at).unwrap_or(Utc::now()),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+/// The note each customer is imported with, recording the customer code
+/// by which the sales ledger refers to them.
+fn customer_code_note(code: &str) -> String {
+    format!("Vend customer code: {}", code)
+}
+
+/// Finds the customer imported with the given code, falling back to their name.
+fn find_customer(record: &TransactionRecord, db: &InlineDatabase) -> Option<Customer> {
+    let code_note = customer_code_note(&record.customer_code);
+
+    db.1.iter()
+        .find(|customer| {
+            !record.customer_code.is_empty()
+                && customer
+                    .customer_notes
+                    .iter()
+                    .any(|note| note.message == code_note)
+        })
+        .or_else(|| {
+            db.1.iter().find(|customer| {
+                !record.customer_name.is_empty() && customer.name == record.customer_name
+            })
+        })
+        .cloned()
+}
+
+impl Parsable<TransactionRecord> for Transaction {
+    fn parse_individual(
+        reader: &[Result<TransactionRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Transaction, ParseFailure> {
+        let sale = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        if sale.line_type != "Sale" {
+            return Err(ParseFailure::FormatFailure(format!(
+                "Expected a sale for receipt {}, found {}.",
+                sale.receipt_number, sale.line_type
+            )));
+        }
+
+        let rows: Vec<&TransactionRecord> = reader[*line..]
+            .iter()
+            .map_while(|row| row.as_ref().ok())
+            .take_while(|row| row.receipt_number == sale.receipt_number && row.line_type != "Sale")
+            .collect();
+
+        *line += rows.len();
+
+        if sale.status.eq_ignore_ascii_case("voided") {
+            return Err(ParseFailure::FormatFailure(format!(
+                "Receipt {} was voided.",
+                sale.receipt_number
+            )));
+        }
+
+        let date = parse_timestamp(&sale.date).unwrap_or(Utc::now());
+        let total = sale.total.parse::<f32>().unwrap_or(0.0);
+
+        // Returns are recorded as sales with a negative total.
+        let transaction_type = if total < 0.0 {
+            TransactionType::In
+        } else {
+            TransactionType::Out
+        };
+
+        // Single outlet ledgers do not name the outlet.
+        let store = resolve_store(&sale.outlet, db).or_else(|| resolve_store(MAIN_OUTLET, db));
+        let kiosk = store
+            .as_ref()
+            .and_then(|store| resolve_kiosk(&sale.register, store, db));
+        let employee = resolve_employee(&sale.user, db);
+        let customer = find_customer(sale, db);
+
+        let origin = match &store {
+            Some(store) => Location {
+                contact: store.contact.clone(),
+                store_code: store.code.clone(),
+                store_id: store.id.clone(),
+            },
+            None => Location {
+                contact: named_contact(&sale.register),
+                store_code: "000".to_string(),
+                store_id: "000".to_string(),
+            },
+        };
+
+        let products = rows
+            .iter()
+            .filter(|row| row.line_type == "Sale Line")
+            .map(|row| {
+                let quantity = row.quantity.parse::<f32>().unwrap_or(0.0).abs();
+                let subtotal = row.subtotal.parse::<f32>().unwrap_or(0.0).abs();
+
+                ProductPurchase {
+                    id: Uuid::new_v4().to_string(),
+                    product_code: row.sku.clone(),
+                    product_name: row.details.clone(),
+                    product_sku: row.sku.clone(),
+                    product_cost: if quantity > 0.0 {
+                        subtotal / quantity
+                    } else {
+                        subtotal
+                    },
+                    discount: DiscountValue::Absolute(
+                        row.discount.parse::<f32>().unwrap_or(0.0).abs().round() as u32,
+                    ),
+                    product_variant_name: row.details.clone(),
+                    quantity,
+                    tags: vec![],
+                    transaction_type: transaction_type.clone(),
+                    instances: (0..quantity as u32)
+                        .map(|_| ProductInstance {
+                            id: Uuid::new_v4().to_string(),
+                            fulfillment_status: FulfillmentStatus {
+                                pick_status: PickStatus::Picked,
+                                pick_history: vec![],
+                                last_updated: date,
+                                notes: vec![],
+                            },
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let payment = rows
+            .iter()
+            .filter(|row| row.line_type == "Payment")
+            .map(|row| {
+                let payment_method = split_payment_methods(&row.details, &PAYMENT_METHODS)
+                    .into_iter()
+                    .next()
+                    .map_or(
+                        open_stock::PaymentMethod::Other(row.details.clone()),
+                        |split| split.method,
+                    );
+                let amount = row.paid.parse::<f32>().unwrap_or(0.0);
+
+                Payment {
+                    id: Uuid::new_v4().to_string(),
+                    payment_method,
+                    fulfillment_date: parse_timestamp(&row.date).unwrap_or(date),
+                    amount: Price {
+                        quantity: amount,
+                        currency: String::new(),
+                    },
+                    processing_fee: Price {
+                        quantity: 0.0,
+                        currency: String::new(),
+                    },
+                    status: PaymentStatus::Complete(Processable::Anonymous(String::from("vend"))),
+                    processor: PaymentProcessor::anonymous(String::from("vend")),
+                    order_ids: vec![],
+                    delay_action: PaymentAction::Complete,
+                    delay_duration: String::new(),
+                }
+            })
+            .collect();
+
+        let order_notes = rows
+            .iter()
+            .copied()
+            .chain([sale])
+            .filter(|row| !row.note.is_empty())
+            .map(|row| import_note(row.note.clone()))
+            .collect::<Vec<Note>>();
+
+        let order = Order {
+            id: Uuid::new_v4().to_string(),
+            destination: Location {
+                contact: customer
+                    .as_ref()
+                    .map_or(origin.contact.clone(), |customer| customer.contact.clone()),
+                store_code: origin.store_code.clone(),
+                store_id: origin.store_id.clone(),
+            },
+            origin,
+            products,
+            status: OrderStatusAssignment {
+                status: OrderStatus::Fulfilled(date),
+                assigned_products: vec![],
+                timestamp: date,
+            },
+            status_history: vec![],
+            order_history: vec![],
+            previous_failed_fulfillment_attempts: vec![],
+            order_notes: order_notes.clone(),
+            reference: sale.receipt_number.clone(),
+            creation_date: date,
+            discount: DiscountValue::Absolute(
+                sale.discount.parse::<f32>().unwrap_or(0.0).abs().round() as u32,
+            ),
+            order_type: OrderType::Direct,
+        };
+
+        Ok(Transaction {
+            id: Uuid::new_v4().to_string(),
+            customer: TransactionCustomer {
+                customer_type: CustomerType::Individual,
+                customer_id: customer.map_or(String::new(), |customer| customer.id),
+            },
+            kiosk: kiosk.map_or(String::new(), |kiosk| kiosk.id),
+            transaction_type,
+            products: vec![order],
+            order_total: total.round() as i64,
+            payment,
+            order_date: date,
+            order_notes,
+            salesperson: employee.map_or(String::new(), |employee| employee.id),
+            created_at: date,
+            updated_at: Utc::now(),
+        })
+    }
+}
@@ -0,0 +1,456 @@
+//! Xero contacts and inventory items exports.
+//!
+//! An item's sales account, inventory asset account and cost of goods sold
+//! (or purchases) account are kept as its sales group, stock group and value
+//! stream. Its sales tax rate is kept as its tax code, as Xero names it,
+//! such as `20% (VAT on Income)`. Only tracked items carry a quantity.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{identify, resolve_store, SpecialPricing},
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::Reader;
+use open_stock::{
+    Address, ContactInformation, Customer, DiscountValue, Email, Location, MobileNumber, Note,
+    Product, ProductIdentification, ProductVisibility, Quantity, Stock, StockInformation,
+    VariantInformation,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "ItemCode,ItemName,Quantity,PurchasesDescription,PurchasesUnitPrice,PurchasesAccount,PurchasesTaxRate,SalesDescription,SalesUnitPrice,SalesAccount,SalesTaxRate,InventoryAssetAccount,CostOfGoodsSoldAccount",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "ContactName,AccountNumber,EmailAddress,FirstName,LastName,POAttentionTo,POAddressLine1,POAddressLine2,POAddressLine3,POAddressLine4,POCity,PORegion,POPostalCode,POCountry,SAAttentionTo,SAAddressLine1,SAAddressLine2,SAAddressLine3,SAAddressLine4,SACity,SARegion,SAPostalCode,SACountry,PhoneNumber,FaxNumber,MobileNumber,DDINumber,SkypeName,TaxNumber,AccountsReceivableTaxCodeName,AccountsPayableTaxCodeName,Website,Discount",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "ZZZZ",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The store standing for the organisation, which tracks all of its inventory.
+const ORGANISATION: &str = "Xero";
+
+fn import_note(message: String) -> Note {
+    Note {
+        message,
+        author: "XERO-IMPORT".to_string(),
+        timestamp: Utc::now(),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "ItemCode")]
+    item_code: String,
+
+    #[serde(rename = "ItemName")]
+    item_name: String,
+
+    /// Only exported for tracked items.
+    #[serde(rename = "Quantity")]
+    quantity: String,
+
+    #[serde(rename = "PurchasesDescription")]
+    purchases_description: String,
+
+    #[serde(rename = "PurchasesUnitPrice")]
+    purchases_unit_price: String,
+
+    #[serde(rename = "PurchasesAccount")]
+    purchases_account: String,
+
+    #[serde(rename = "PurchasesTaxRate")]
+    purchases_tax_rate: String,
+
+    #[serde(rename = "SalesDescription")]
+    sales_description: String,
+
+    #[serde(rename = "SalesUnitPrice")]
+    sales_unit_price: String,
+
+    #[serde(rename = "SalesAccount")]
+    sales_account: String,
+
+    #[serde(rename = "SalesTaxRate")]
+    sales_tax_rate: String,
+
+    #[serde(rename = "InventoryAssetAccount")]
+    inventory_asset_account: String,
+
+    #[serde(rename = "CostOfGoodsSoldAccount")]
+    cost_of_goods_sold_account: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CustomerRecord {
+    #[serde(rename = "ContactName")]
+    contact_name: String,
+
+    #[serde(rename = "AccountNumber")]
+    account_number: String,
+
+    #[serde(rename = "EmailAddress")]
+    email: String,
+
+    #[serde(rename = "FirstName")]
+    first_name: String,
+
+    #[serde(rename = "LastName")]
+    last_name: String,
+
+    #[serde(rename = "POAddressLine1")]
+    po_address_line1: String,
+
+    #[serde(rename = "POAddressLine2")]
+    po_address_line2: String,
+
+    #[serde(rename = "POAddressLine3")]
+    po_address_line3: String,
+
+    #[serde(rename = "POAddressLine4")]
+    po_address_line4: String,
+
+    #[serde(rename = "POCity")]
+    po_city: String,
+
+    #[serde(rename = "PORegion")]
+    po_region: String,
+
+    #[serde(rename = "POPostalCode")]
+    po_postal_code: String,
+
+    #[serde(rename = "POCountry")]
+    po_country: String,
+
+    #[serde(rename = "SAAddressLine1")]
+    sa_address_line1: String,
+
+    #[serde(rename = "SAAddressLine2")]
+    sa_address_line2: String,
+
+    #[serde(rename = "SAAddressLine3")]
+    sa_address_line3: String,
+
+    #[serde(rename = "SAAddressLine4")]
+    sa_address_line4: String,
+
+    #[serde(rename = "SACity")]
+    sa_city: String,
+
+    #[serde(rename = "SARegion")]
+    sa_region: String,
+
+    #[serde(rename = "SAPostalCode")]
+    sa_postal_code: String,
+
+    #[serde(rename = "SACountry")]
+    sa_country: String,
+
+    #[serde(rename = "PhoneNumber")]
+    phone: String,
+
+    #[serde(rename = "MobileNumber")]
+    mobile: String,
+
+    #[serde(rename = "TaxNumber")]
+    tax_number: String,
+
+    /// The tax rate applied to the contact's sales, such as `Tax Exempt`.
+    #[serde(rename = "AccountsReceivableTaxCodeName")]
+    sales_tax_code: String,
+
+    /// A percentage taken off the contact's sales.
+    #[serde(rename = "Discount")]
+    discount: String,
+}
+
+impl CustomerRecord {
+    /// Contacts are given their postal address, or their street
+    /// address if they have no postal address.
+    fn address(&self) -> Address {
+        let postal = !self.po_address_line1.is_empty();
+
+        let (lines, city, region, po_code, country) = if postal {
+            (
+                [
+                    &self.po_address_line1,
+                    &self.po_address_line2,
+                    &self.po_address_line3,
+                    &self.po_address_line4,
+                ],
+                &self.po_city,
+                &self.po_region,
+                &self.po_postal_code,
+                &self.po_country,
+            )
+        } else {
+            (
+                [
+                    &self.sa_address_line1,
+                    &self.sa_address_line2,
+                    &self.sa_address_line3,
+                    &self.sa_address_line4,
+                ],
+                &self.sa_city,
+                &self.sa_region,
+                &self.sa_postal_code,
+                &self.sa_country,
+            )
+        };
+
+        Address {
+            street: lines[0].clone(),
+            street2: lines[1..]
+                .iter()
+                .filter(|line| !line.is_empty())
+                .map(|line| line.as_str())
+                .collect::<Vec<&str>>()
+                .join(", "),
+            city: match (city.is_empty(), region.is_empty()) {
+                (_, true) => city.clone(),
+                (true, false) => region.clone(),
+                (false, false) => format!("{}, {}", city, region),
+            },
+            country: country.clone(),
+            po_code: po_code.clone(),
+            lat: 0.0,
+            lon: 0.0,
+        }
+    }
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let tracked = !cloned.inventory_asset_account.is_empty();
+        let price = cloned.sales_unit_price.parse::<f32>().unwrap_or(0.0);
+        let name = if cloned.item_name.is_empty() {
+            cloned.item_code.clone()
+        } else {
+            cloned.item_name.clone()
+        };
+
+        let store = if tracked {
+            resolve_store(ORGANISATION, db)
+        } else {
+            None
+        };
+
+        let mut specifications = vec![];
+
+        if !cloned.purchases_tax_rate.is_empty() {
+            specifications.push((
+                "Purchases Tax Rate".to_string(),
+                cloned.purchases_tax_rate.clone(),
+            ));
+        }
+
+        Ok(Product {
+            name: name.clone(),
+            company: String::new(),
+            variant_groups: vec![],
+            variants: vec![VariantInformation {
+                name: name.clone(),
+                stock: match store {
+                    Some(store) => vec![Stock {
+                        store: Location {
+                            store_code: store.code.clone(),
+                            store_id: store.id.clone(),
+                            contact: store.contact.clone(),
+                        },
+                        quantity: Quantity {
+                            quantity_sellable: cloned.quantity.parse::<f32>().unwrap_or(0.0),
+                            quantity_unsellable: 0.0,
+                            quantity_on_order: 0.0,
+                            quantity_allocated: 0.0,
+                        },
+                    }],
+                    None => vec![],
+                },
+                images: vec![],
+                retail_price: price,
+                marginal_price: cloned.purchases_unit_price.parse::<f32>().unwrap_or(price),
+                loyalty_discount: DiscountValue::Absolute(0),
+                variant_code: vec![cloned.item_code.clone()],
+                order_history: vec![],
+                stock_information: StockInformation {
+                    stock_group: cloned.inventory_asset_account.clone(),
+                    sales_group: cloned.sales_account.clone(),
+                    value_stream: if tracked {
+                        cloned.cost_of_goods_sold_account.clone()
+                    } else {
+                        cloned.purchases_account.clone()
+                    },
+                    brand: String::new(),
+                    tax_code: cloned.sales_tax_rate.clone(),
+                    weight: "0".to_string(),
+                    volume: "0.00".to_string(),
+                    max_volume: "0.00".to_string(),
+                    back_order: false,
+                    discontinued: false,
+                    non_diminishing: false,
+                    shippable: true,
+                    size_override_unit: String::new(),
+                    size_x_unit: String::new(),
+                    size_y_unit: String::new(),
+                    size_z_unit: String::new(),
+                    size_x: 0.0,
+                    size_y: 0.0,
+                    size_z: 0.0,
+                    min_stock_before_alert: 0.0,
+                    min_stock_level: 0.0,
+                    colli: String::new(),
+                },
+                barcode: String::new(),
+                id: Uuid::new_v4().to_string(),
+                buy_max: -1.0,
+                buy_min: 1.0,
+                identification: identify(&cloned.item_code, ""),
+                stock_tracking: tracked,
+            }],
+            sku: cloned.item_code.clone(),
+            images: vec![],
+            tags: vec![],
+            description: cloned.sales_description.clone(),
+            specifications,
+            name_long: name,
+            identification: ProductIdentification {
+                sku: cloned.item_code.clone(),
+                ..Default::default()
+            },
+            description_long: cloned.sales_description.clone(),
+            visible: ProductVisibility::ShowWhenInStock,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+    }
+}
+
+impl Parsable<CustomerRecord> for Customer {
+    fn parse_individual(
+        reader: &[Result<CustomerRecord, csv::Error>],
+        line: &mut usize,
+        _db: &mut InlineDatabase,
+    ) -> Result<Customer, ParseFailure> {
+        let cloned = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        *line += 1;
+
+        let person = format!("{} {}", cloned.first_name, cloned.last_name)
+            .trim()
+            .to_string();
+
+        let mut customer_notes = vec![];
+
+        for (name, value) in [
+            ("Account number", &cloned.account_number),
+            ("Tax number", &cloned.tax_number),
+            ("Sales tax", &cloned.sales_tax_code),
+            ("Discount", &cloned.discount),
+        ] {
+            if !value.is_empty() {
+                customer_notes.push(import_note(format!("{}: {}", name, value)));
+            }
+        }
+
+        Ok(Customer {
+            id: Uuid::new_v4().to_string(),
+            name: cloned.contact_name.clone(),
+            contact: ContactInformation {
+                name: if person.is_empty() {
+                    cloned.contact_name.clone()
+                } else {
+                    person
+                },
+                mobile: MobileNumber::from(if cloned.mobile.is_empty() {
+                    cloned.phone.clone()
+                } else {
+                    cloned.mobile.clone()
+                }),
+                email: Email::from(cloned.email.clone()),
+                landline: if cloned.mobile.is_empty() {
+                    String::new()
+                } else {
+                    cloned.phone.clone()
+                },
+                address: cloned.address(),
+            },
+            customer_notes,
+            balance: 0,
+            special_pricing: SpecialPricing::tax_exempt(
+                cloned.sales_tax_code.to_lowercase().contains("exempt"),
+            )
+            .to_field(),
+            accepts_marketing: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        })
+    }
+}
@@ -0,0 +1,503 @@
+//! Zettle (formerly iZettle) exports.
+//!
+//! The product library lists a row for each variant, the rows of a product
+//! following one another. Purchase history lists a row for each line item.
+
+use crate::{
+    parser::ParseFailure,
+    parser::{
+        add_variant_option, identify, lowercase_tags, merge_tags, named_contact, parse_flag,
+        resolve_employee, resolve_kiosk, resolve_store, split_payment_methods, vat_tax_code,
+        PaymentKind, PaymentMethodTable,
+    },
+    InlineDatabase,
+};
+use chrono::prelude::*;
+use csv::Reader;
+use open_stock::{
+    CustomerType, DiscountValue, FulfillmentStatus, Location, Order, OrderStatus,
+    OrderStatusAssignment, OrderType, Payment, PaymentAction, PaymentMethod, PaymentProcessor,
+    PaymentStatus, PickStatus, Price, Processable, Product, ProductIdentification, ProductInstance,
+    ProductPurchase, ProductVisibility, Quantity, Stock, StockInformation, Store, Transaction,
+    TransactionCustomer, TransactionType, VariantInformation,
+};
+use phf::phf_map;
+use serde::{Deserialize, Serialize};
+use std::{fs::File, str::FromStr};
+use uuid::Uuid;
+
+use super::{Parsable, ParseType};
+
+pub fn match_self(parse_type: ParseType) -> String {
+    let matchable = match parse_type {
+        ParseType::Store => "ZZZZ",
+        ParseType::Kiosk => "ZZZZ",
+        ParseType::Product => "Product Name,Variant Name,Option 1 Name,Option 1 Value,Option 2 Name,Option 2 Value,Category,Description,Unit,Price,Cost Price,VAT (%),SKU,Barcode,Track Stock,In Stock",
+        ParseType::ProductModifier => "ZZZZ",
+        ParseType::ProductCategory => "ZZZZ",
+        ParseType::Promotion => "ZZZZ",
+        ParseType::GiftCard => "ZZZZ",
+        ParseType::Customer => "ZZZZ",
+        ParseType::CustomerAddress => "ZZZZ",
+        ParseType::Transaction => "Date,Time,Receipt Number,Type,Staff,Device,Payment Method,Card Type,Product Name,Variant Name,SKU,Quantity,Unit Price,Discount,VAT (%),VAT Amount,Total,Currency",
+        ParseType::TransactionItem => "ZZZZ",
+        ParseType::Invalid => "ZZZZZ",
+    };
+
+    String::from_str(matchable).unwrap()
+}
+
+/// The store standing for the account, which holds all of its inventory.
+const ACCOUNT: &str = "Zettle";
+
+pub static PAYMENT_METHODS: PaymentMethodTable = phf_map! {
+    "card" => PaymentKind::Card,
+    "cash" => PaymentKind::Cash,
+    "invoice" => PaymentKind::Transfer,
+    "swish" => PaymentKind::Transfer,
+    "mobilepay" => PaymentKind::Transfer,
+    "vipps" => PaymentKind::Transfer,
+    "gift card" => PaymentKind::GiftCard,
+};
+
+/// Reads the separate date and time columns of a purchase,
+/// such as `2023-05-01` and `10:02:11`.
+fn parse_timestamp(date: &str, time: &str) -> Option<DateTime<Utc>> {
+    let value = format!("{} {}", date.trim(), time.trim());
+
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&value, format).ok())
+        .map(|timestamp| timestamp.and_utc())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProductRecord {
+    #[serde(rename = "Product Name")]
+    name: String,
+
+    #[serde(rename = "Variant Name")]
+    variant_name: String,
+
+    #[serde(rename = "Option 1 Name")]
+    option_one_name: String,
+
+    #[serde(rename = "Option 1 Value")]
+    option_one_value: String,
+
+    #[serde(rename = "Option 2 Name")]
+    option_two_name: String,
+
+    #[serde(rename = "Option 2 Value")]
+    option_two_value: String,
+
+    #[serde(rename = "Category")]
+    category: String,
+
+    #[serde(rename = "Description")]
+    description: String,
+
+    #[serde(rename = "Unit")]
+    unit: String,
+
+    #[serde(rename = "Price")]
+    price: String,
+
+    #[serde(rename = "Cost Price")]
+    cost_price: String,
+
+    #[serde(rename = "VAT (%)")]
+    vat: String,
+
+    #[serde(rename = "SKU")]
+    sku: String,
+
+    #[serde(rename = "Barcode")]
+    barcode: String,
+
+    #[serde(rename = "Track Stock")]
+    track_stock: String,
+
+    #[serde(rename = "In Stock")]
+    in_stock: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TransactionRecord {
+    #[serde(rename = "Date")]
+    date: String,
+
+    #[serde(rename = "Time")]
+    time: String,
+
+    #[serde(rename = "Receipt Number")]
+    receipt_number: String,
+
+    /// `Purchase` or `Refund`.
+    #[serde(rename = "Type")]
+    purchase_type: String,
+
+    #[serde(rename = "Staff")]
+    staff: String,
+
+    #[serde(rename = "Device")]
+    device: String,
+
+    #[serde(rename = "Payment Method")]
+    payment_method: String,
+
+    #[serde(rename = "Product Name")]
+    product_name: String,
+
+    #[serde(rename = "Variant Name")]
+    variant_name: String,
+
+    #[serde(rename = "SKU")]
+    sku: String,
+
+    #[serde(rename = "Quantity")]
+    quantity: String,
+
+    #[serde(rename = "Unit Price")]
+    unit_price: String,
+
+    #[serde(rename = "Discount")]
+    discount: String,
+
+    #[serde(rename = "Total")]
+    total: String,
+
+    #[serde(rename = "Currency")]
+    currency: String,
+}
+
+pub fn parse_type<T: Parsable<R>, R: for<'de> serde::Deserialize<'de>>(
+    mut reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let collected: Vec<Result<R, csv::Error>> = reader.deserialize().collect();
+
+    parse_collected(collected, db)
+}
+
+fn parse_collected<T: Parsable<R>, R>(
+    collected: Vec<Result<R, csv::Error>>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    let mut iterator: usize = 0;
+    let mut items: Vec<T> = vec![];
+
+    loop {
+        match T::parse_individual(&collected, &mut iterator, db) {
+            Ok(i) => items.push(i),
+            Err(err) => match err {
+                ParseFailure::EOFException => break,
+                error => {
+                    println!("[warn]: Parser Warning: {:?}", error);
+                }
+            },
+        }
+    }
+
+    Ok(items)
+}
+
+fn location(store: &Store) -> Location {
+    Location {
+        store_code: store.code.clone(),
+        store_id: store.id.clone(),
+        contact: store.contact.clone(),
+    }
+}
+
+/// Zettle keeps a single inventory for each account.
+fn account_store(db: &mut InlineDatabase) -> Option<Store> {
+    resolve_store(ACCOUNT, db)
+}
+
+fn build_variant(record: &ProductRecord, store: &Option<Store>) -> VariantInformation {
+    let price = record.price.parse::<f32>().unwrap_or(0.0);
+    let stock_tracking = parse_flag(&record.track_stock);
+
+    let stock = match store {
+        Some(store) if stock_tracking => vec![Stock {
+            store: location(store),
+            quantity: Quantity {
+                quantity_sellable: record.in_stock.parse::<f32>().unwrap_or(0.0),
+                quantity_unsellable: 0.0,
+                quantity_on_order: 0.0,
+                quantity_allocated: 0.0,
+            },
+        }],
+        _ => vec![],
+    };
+
+    VariantInformation {
+        name: if record.variant_name.is_empty() {
+            record.name.clone()
+        } else {
+            record.variant_name.clone()
+        },
+        stock,
+        images: vec![],
+        retail_price: price,
+        marginal_price: record.cost_price.parse::<f32>().unwrap_or(price),
+        loyalty_discount: DiscountValue::Absolute(0),
+        variant_code: vec![record.sku.clone()],
+        order_history: vec![],
+        stock_information: StockInformation {
+            stock_group: String::new(),
+            sales_group: record.category.clone(),
+            value_stream: String::new(),
+            brand: String::new(),
+            tax_code: vat_tax_code(&record.vat),
+            weight: "0".to_string(),
+            volume: "0.00".to_string(),
+            max_volume: "0.00".to_string(),
+            back_order: false,
+            discontinued: false,
+            non_diminishing: false,
+            shippable: true,
+            size_override_unit: String::new(),
+            size_x_unit: String::new(),
+            size_y_unit: String::new(),
+            size_z_unit: String::new(),
+            size_x: 0.0,
+            size_y: 0.0,
+            size_z: 0.0,
+            min_stock_before_alert: 0.0,
+            min_stock_level: 0.0,
+            colli: String::new(),
+        },
+        barcode: record.barcode.clone(),
+        id: Uuid::new_v4().to_string(),
+        buy_max: -1.0,
+        // Products sold by a unit (such as kg) may be sold in part.
+        buy_min: if record.unit.is_empty() { 1.0 } else { 0.0 },
+        identification: identify(&record.sku, &record.barcode),
+        stock_tracking,
+    }
+}
+
+impl Parsable<ProductRecord> for Product {
+    fn parse_individual(
+        reader: &[Result<ProductRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Product, ParseFailure> {
+        let first = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        let rows: Vec<&ProductRecord> = reader[*line..]
+            .iter()
+            .map_while(|row| row.as_ref().ok())
+            .take_while(|row| row.name == first.name)
+            .collect();
+
+        *line += rows.len();
+
+        // The item's category is kept as its only tag.
+        let mut tags = vec![];
+        merge_tags(
+            &mut tags,
+            [first.category.trim().to_string()],
+            lowercase_tags(),
+        );
+
+        let store = account_store(db);
+
+        let mut product = Product {
+            name: first.name.clone(),
+            company: String::new(),
+            variant_groups: vec![],
+            variants: vec![],
+            sku: first.sku.clone(),
+            images: vec![],
+            tags,
+            description: first.description.clone(),
+            specifications: vec![],
+            name_long: first.name.clone(),
+            identification: ProductIdentification {
+                sku: first.sku.clone(),
+                ..Default::default()
+            },
+            description_long: first.description.clone(),
+            visible: ProductVisibility::ShowWhenInStock,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        if !first.unit.is_empty() {
+            product
+                .specifications
+                .push(("Unit".to_string(), first.unit.clone()));
+        }
+
+        for row in &rows {
+            add_variant_option(&mut product, &row.option_one_name, &row.option_one_value);
+            add_variant_option(&mut product, &row.option_two_name, &row.option_two_value);
+
+            product.variants.push(build_variant(row, &store));
+        }
+
+        Ok(product)
+    }
+}
+
+impl Parsable<TransactionRecord> for Transaction {
+    fn parse_individual(
+        reader: &[Result<TransactionRecord, csv::Error>],
+        line: &mut usize,
+        db: &mut InlineDatabase,
+    ) -> Result<Transaction, ParseFailure> {
+        let purchase = match reader.get(*line) {
+            Some(Ok(value)) => value,
+            Some(Err(error)) => {
+                *line += 1;
+                return Err(ParseFailure::ReadFailure(error.to_string()));
+            }
+            None => return Err(ParseFailure::EOFException),
+        };
+
+        let items: Vec<&TransactionRecord> = reader[*line..]
+            .iter()
+            .map_while(|row| row.as_ref().ok())
+            .take_while(|row| row.receipt_number == purchase.receipt_number)
+            .collect();
+
+        *line += items.len();
+
+        let date = parse_timestamp(&purchase.date, &purchase.time).unwrap_or(Utc::now());
+        let transaction_type = if purchase.purchase_type.eq_ignore_ascii_case("refund") {
+            TransactionType::In
+        } else {
+            TransactionType::Out
+        };
+
+        let store = account_store(db);
+        let kiosk = store
+            .as_ref()
+            .and_then(|store| resolve_kiosk(&purchase.device, store, db));
+        let employee = resolve_employee(&purchase.staff, db);
+
+        let origin = match &store {
+            Some(store) => location(store),
+            None => Location {
+                contact: named_contact(&purchase.device),
+                store_code: "000".to_string(),
+                store_id: "000".to_string(),
+            },
+        };
+
+        let products = items
+            .iter()
+            .map(|item| {
+                let quantity = item.quantity.parse::<f32>().unwrap_or(1.0).abs();
+
+                ProductPurchase {
+                    id: Uuid::new_v4().to_string(),
+                    product_code: item.sku.clone(),
+                    product_name: item.product_name.clone(),
+                    product_sku: item.sku.clone(),
+                    product_cost: item.unit_price.parse::<f32>().unwrap_or(0.0),
+                    discount: DiscountValue::Absolute(
+                        item.discount.parse::<f32>().unwrap_or(0.0).abs().round() as u32,
+                    ),
+                    product_variant_name: if item.variant_name.is_empty() {
+                        item.product_name.clone()
+                    } else {
+                        item.variant_name.clone()
+                    },
+                    quantity,
+                    tags: vec![],
+                    transaction_type: transaction_type.clone(),
+                    instances: (0..quantity.ceil() as u32)
+                        .map(|_| ProductInstance {
+                            id: Uuid::new_v4().to_string(),
+                            fulfillment_status: FulfillmentStatus {
+                                pick_status: PickStatus::Picked,
+                                pick_history: vec![],
+                                last_updated: date,
+                                notes: vec![],
+                            },
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        let total: f32 = items
+            .iter()
+            .map(|item| item.total.parse::<f32>().unwrap_or(0.0))
+            .sum();
+
+        let payment_method = split_payment_methods(&purchase.payment_method, &PAYMENT_METHODS)
+            .into_iter()
+            .next()
+            .map_or(
+                PaymentMethod::Other(purchase.payment_method.clone()),
+                |split| split.method,
+            );
+
+        let payment = Payment {
+            id: Uuid::new_v4().to_string(),
+            payment_method,
+            fulfillment_date: date,
+            amount: Price {
+                quantity: total,
+                currency: purchase.currency.clone(),
+            },
+            processing_fee: Price {
+                quantity: 0.0,
+                currency: purchase.currency.clone(),
+            },
+            status: PaymentStatus::Complete(Processable::Anonymous(String::from("zettle"))),
+            processor: PaymentProcessor::anonymous(String::from("zettle")),
+            order_ids: vec![],
+            delay_action: PaymentAction::Complete,
+            delay_duration: String::new(),
+        };
+
+        Ok(Transaction {
+            id: Uuid::new_v4().to_string(),
+            customer: TransactionCustomer {
+                customer_type: CustomerType::Individual,
+                customer_id: String::new(),
+            },
+            kiosk: kiosk.map_or(String::new(), |kiosk| kiosk.id),
+            transaction_type,
+            products: vec![Order {
+                id: Uuid::new_v4().to_string(),
+                destination: origin.clone(),
+                origin,
+                products,
+                status: OrderStatusAssignment {
+                    status: OrderStatus::Fulfilled(date),
+                    assigned_products: vec![],
+                    timestamp: date,
+                },
+                status_history: vec![],
+                order_history: vec![],
+                previous_failed_fulfillment_attempts: vec![],
+                order_notes: vec![],
+                reference: purchase.receipt_number.clone(),
+                creation_date: date,
+                discount: DiscountValue::Absolute(0),
+                order_type: OrderType::Direct,
+            }],
+            order_total: total.round() as i64,
+            payment: vec![payment],
+            order_date: date,
+            order_notes: vec![],
+            salesperson: employee.map_or(String::new(), |employee| employee.id),
+            created_at: date,
+            updated_at: Utc::now(),
+        })
+    }
+}
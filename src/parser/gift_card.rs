@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// An issued gift card, carried over as an outstanding liability.
+///
+/// `open_stock` has no gift card type, so cards are kept alongside its
+/// entities in the output until they can be imported.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GiftCard {
+    pub id: String,
+    /// The code, or only its final characters where the source masks it.
+    pub code: String,
+    pub initial_value: f32,
+    /// The value remaining on the card, i.e. the liability still owed.
+    pub balance: f32,
+    pub currency: String,
+    /// The id of the customer the card was issued to, if they were imported.
+    pub customer_id: Option<String>,
+    /// The reference of the order the card was purchased in.
+    pub order_reference: String,
+    pub note: String,
+    pub disabled: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,108 @@
+use open_stock::ProductIdentification;
+
+/// A product code recognised from its length and check digit.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Barcode {
+    Ean13(String),
+    Ean8(String),
+    UpcA(String),
+    Isbn13(String),
+    Isbn10(String),
+}
+
+impl Barcode {
+    /// Recognises a barcode, ignoring the hyphens and spaces it is often
+    /// printed with. Returns `None` for unknown formats or a bad check digit.
+    pub fn parse(value: &str) -> Option<Barcode> {
+        let code: String = value.chars().filter(|c| !matches!(c, '-' | ' ')).collect();
+
+        if code.len() == 10 && has_valid_isbn10_check_digit(&code) {
+            return Some(Barcode::Isbn10(code));
+        }
+
+        if !code.chars().all(|c| c.is_ascii_digit()) || !has_valid_gtin_check_digit(&code) {
+            return None;
+        }
+
+        match code.len() {
+            8 => Some(Barcode::Ean8(code)),
+            12 => Some(Barcode::UpcA(code)),
+            // Bookland EANs are ISBN-13s.
+            13 if code.starts_with("978") || code.starts_with("979") => Some(Barcode::Isbn13(code)),
+            13 => Some(Barcode::Ean13(code)),
+            _ => None,
+        }
+    }
+}
+
+/// GTIN check digits weigh each data digit alternately by 3 and 1,
+/// starting from the digit nearest the check digit.
+fn has_valid_gtin_check_digit(code: &str) -> bool {
+    let digits: Vec<u32> = code.chars().filter_map(|c| c.to_digit(10)).collect();
+
+    match digits.split_last() {
+        Some((check, data)) if !data.is_empty() => {
+            let sum: u32 = data
+                .iter()
+                .rev()
+                .enumerate()
+                .map(|(i, digit)| if i % 2 == 0 { digit * 3 } else { *digit })
+                .sum();
+
+            (10 - sum % 10) % 10 == *check
+        }
+        _ => false,
+    }
+}
+
+/// ISBN-10 check digits make the weighted sum (10 down to 1) divisible
+/// by 11, where a check digit of `X` stands for 10.
+fn has_valid_isbn10_check_digit(code: &str) -> bool {
+    let mut sum = 0;
+
+    for (i, c) in code.chars().enumerate() {
+        let value = match c {
+            'X' | 'x' if i == 9 => 10,
+            _ => match c.to_digit(10) {
+                Some(digit) => digit,
+                None => return false,
+            },
+        };
+
+        sum += value * (10 - i as u32);
+    }
+
+    sum % 11 == 0
+}
+
+/// Builds a variant's identification from its source SKU and barcode,
+/// filing the barcode under the standard it was recognised as.
+pub fn identify(sku: &str, barcode: &str) -> ProductIdentification {
+    let mut identification = ProductIdentification {
+        sku: sku.trim().to_string(),
+        ..Default::default()
+    };
+
+    match Barcode::parse(barcode) {
+        Some(Barcode::Ean13(code)) | Some(Barcode::Ean8(code)) => identification.ean = code,
+        // A UPC-A is an EAN-13 with a leading zero.
+        Some(Barcode::UpcA(code)) => identification.ean = format!("0{}", code),
+        Some(Barcode::Isbn13(code)) => {
+            identification.isbn = code.clone();
+            identification.ean = code;
+        }
+        Some(Barcode::Isbn10(code)) => identification.isbn = code,
+        None => {}
+    }
+
+    identification
+}
+
+/// Builds the identification of a marketplace listing, whose identifier
+/// (such as an ASIN or an eBay item ID) is filed as its article code.
+pub fn identify_listing(sku: &str, barcode: &str, listing: &str) -> ProductIdentification {
+    ProductIdentification {
+        article_code: listing.trim().to_string(),
+        ..identify(sku, barcode)
+    }
+}
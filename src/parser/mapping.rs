@@ -0,0 +1,389 @@
+use open_stock::{
+    DiscountValue, PaymentMethod, Product, ProductVisibility, Variant, VariantCategory,
+};
+use phf::{phf_map, Map};
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumString;
+
+/// The special pricing of a customer, which `open_stock` keeps as JSON
+/// in `Customer.special_pricing`, such as `{"tax_exempt":true}`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SpecialPricing {
+    /// Exempt customers are charged prices exclusive of tax.
+    pub tax_exempt: bool,
+}
+
+impl SpecialPricing {
+    pub fn tax_exempt(tax_exempt: bool) -> Self {
+        SpecialPricing { tax_exempt }
+    }
+
+    /// The value of `Customer.special_pricing`, left empty
+    /// for customers who pay the usual prices.
+    pub fn to_field(&self) -> String {
+        if *self == SpecialPricing::default() {
+            String::new()
+        } else {
+            serde_json::to_string(self).unwrap_or_default()
+        }
+    }
+
+    /// Reads `Customer.special_pricing`, where empty or unknown values are the usual prices.
+    pub fn from_field(value: &str) -> Self {
+        serde_json::from_str(value).unwrap_or_default()
+    }
+}
+
+/// The kind of tender a source payment method name refers to.
+///
+/// `open_stock` has no dedicated variants for gift cards or store credit,
+/// so these are carried as well-known `PaymentMethod::Other` values.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PaymentKind {
+    Card,
+    Cash,
+    Transfer,
+    GiftCard,
+    StoreCredit,
+}
+
+impl From<PaymentKind> for PaymentMethod {
+    fn from(kind: PaymentKind) -> Self {
+        match kind {
+            PaymentKind::Card => PaymentMethod::Card,
+            PaymentKind::Cash => PaymentMethod::Cash,
+            PaymentKind::Transfer => PaymentMethod::Transfer,
+            PaymentKind::GiftCard => PaymentMethod::Other("GIFT_CARD".to_string()),
+            PaymentKind::StoreCredit => PaymentMethod::Other("STORE_CREDIT".to_string()),
+        }
+    }
+}
+
+/// Each format provides its own table, keyed by the lowercase name
+/// the source system exports for that payment method.
+pub type PaymentMethodTable = Map<&'static str, PaymentKind>;
+
+/// A single tender within a (possibly combined) payment method string.
+#[derive(Debug, Clone)]
+pub struct PaymentSplit {
+    pub method: PaymentMethod,
+    pub amount: Option<f32>,
+}
+
+impl PaymentSplit {
+    /// A tender named as the source exports it, translated by `table`.
+    pub fn new(name: &str, amount: Option<f32>, table: &PaymentMethodTable) -> Self {
+        PaymentSplit {
+            method: match_payment_method(name, table),
+            amount,
+        }
+    }
+}
+
+/// Translates a single source payment method name, falling back to
+/// `PaymentMethod::Other` when the table holds no match.
+pub fn match_payment_method(name: &str, table: &PaymentMethodTable) -> PaymentMethod {
+    match table.get(name.trim().to_lowercase().as_str()) {
+        Some(kind) => (*kind).into(),
+        None => PaymentMethod::Other(name.trim().to_string()),
+    }
+}
+
+/// Splits a combined payment method string such as
+/// `"Shopify Payments + Gift Card"` into its individual tenders.
+///
+/// An amount is read from a trailing parenthesised value when the source
+/// provides one, e.g. `"Gift Card (10.00)"`.
+pub fn split_payment_methods(value: &str, table: &PaymentMethodTable) -> Vec<PaymentSplit> {
+    value
+        .split('+')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let (name, amount) = match segment.rsplit_once('(') {
+                Some((name, rest)) if rest.ends_with(')') => {
+                    match rest.trim_end_matches(')').trim().parse::<f32>() {
+                        Ok(amount) => (name.trim(), Some(amount)),
+                        Err(_) => (segment, None),
+                    }
+                }
+                _ => (segment, None),
+            };
+
+            PaymentSplit::new(name, amount, table)
+        })
+        .collect()
+}
+
+/// Distributes `total` across the tenders of a combined payment.
+///
+/// A single tender, or a single tender missing its amount, receives the
+/// remainder of the total. When more than one amount is unknown the
+/// payment cannot be divided, and `None` is returned.
+pub fn apportion_payment(splits: &[PaymentSplit], total: f32) -> Option<Vec<(PaymentMethod, f32)>> {
+    let known: f32 = splits.iter().filter_map(|split| split.amount).sum();
+    let missing = splits.iter().filter(|split| split.amount.is_none()).count();
+
+    if missing > 1 {
+        return None;
+    }
+
+    Some(
+        splits
+            .iter()
+            .map(|split| (split.method.clone(), split.amount.unwrap_or(total - known)))
+            .collect(),
+    )
+}
+
+/// Lifecycle state of a product in its source system.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ProductStatus {
+    Active,
+    Draft,
+    Archived,
+}
+
+impl From<&str> for ProductStatus {
+    /// Unknown or missing statuses are treated as active,
+    /// as most sources only export sellable products.
+    fn from(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "draft" => ProductStatus::Draft,
+            "archived" => ProductStatus::Archived,
+            _ => ProductStatus::Active,
+        }
+    }
+}
+
+impl ProductStatus {
+    /// Only active products which are published to a sales channel are shown.
+    pub fn visibility(&self, published: bool) -> ProductVisibility {
+        match self {
+            ProductStatus::Active if published => ProductVisibility::ShowWhenInStock,
+            _ => ProductVisibility::AlwaysHidden,
+        }
+    }
+
+    /// Archived products are no longer sold, and will not be replenished.
+    pub fn discontinued(&self) -> bool {
+        *self == ProductStatus::Archived
+    }
+}
+
+/// Reads a boolean cell, accepting the spellings sources export
+/// such as `true`, `TRUE`, `yes` or `1`.
+pub fn parse_flag(value: &str) -> bool {
+    matches!(
+        value.trim().to_lowercase().as_str(),
+        "true" | "yes" | "y" | "1"
+    )
+}
+
+/// Reads an exported discount, which sources may give as the (negative)
+/// change made to the price, such as `-12.5`.
+///
+/// `DiscountValue` only holds whole numbers, so a discount of a fraction is
+/// kept as its whole part, and the exact amount is returned alongside it.
+/// Returns `None` if the value is not numeric.
+pub fn parse_discount(value: &str, percentage: bool) -> Option<(DiscountValue, f32)> {
+    let exact = value.trim().parse::<f32>().ok()?.abs();
+    let whole = exact.trunc() as u32;
+
+    let discount = if percentage {
+        DiscountValue::Percentage(whole)
+    } else {
+        DiscountValue::Absolute(whole)
+    };
+
+    Some((discount, exact))
+}
+
+/// The size of each supported weight unit, in kilograms.
+static WEIGHT_UNITS: Map<&'static str, f64> = phf_map! {
+    "g" => 0.001,
+    "grams" => 0.001,
+    "kg" => 1.0,
+    "kilograms" => 1.0,
+    "lb" => 0.45359237,
+    "lbs" => 0.45359237,
+    "pounds" => 0.45359237,
+    "oz" => 0.028349523125,
+    "ounces" => 0.028349523125,
+};
+
+/// Converts a weight into kilograms, the unit `StockInformation.weight` is kept in.
+///
+/// Returns `None` if the value is not numeric or the unit is not recognised.
+pub fn weight_in_kilograms(value: &str, unit: &str) -> Option<f64> {
+    let value = value.trim().parse::<f64>().ok()?;
+    let scale = WEIGHT_UNITS.get(unit.trim().to_lowercase().as_str())?;
+
+    Some(value * scale)
+}
+
+/// Whether weights can be read in `unit`, such as `lb`.
+pub fn is_weight_unit(unit: &str) -> bool {
+    WEIGHT_UNITS.contains_key(unit.trim().to_lowercase().as_str())
+}
+
+/// Names the tax code of a VAT rate, given as a percentage such as `20`,
+/// `20%` or `7,7` (a decimal comma, as European sources export), e.g. `VAT 20%`.
+///
+/// Rates which are not numeric are kept as they are.
+pub fn vat_tax_code(rate: &str) -> String {
+    let rate = rate.trim().trim_end_matches('%').trim();
+
+    if rate.is_empty() {
+        return String::new();
+    }
+
+    match rate.replace(',', ".").parse::<f32>() {
+        Ok(percentage) => format!("VAT {}%", percentage),
+        Err(_) => rate.to_string(),
+    }
+}
+
+/// The specification a product's store prices are kept in, as JSON.
+pub const STORE_PRICES: &str = "Store Prices";
+
+/// The price a variant sells for at a store, overriding its retail price,
+/// as `open_stock` holds a single price for each variant.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorePrice {
+    pub variant_code: String,
+    pub store_code: String,
+    pub price: f32,
+}
+
+/// The store prices of a product, as recorded by `add_store_price`.
+pub fn store_prices(product: &Product) -> Vec<StorePrice> {
+    product
+        .specifications
+        .iter()
+        .find(|(name, _)| name == STORE_PRICES)
+        .and_then(|(_, value)| serde_json::from_str(value).ok())
+        .unwrap_or_default()
+}
+
+/// Records the price a variant sells for at a store, if it differs from the
+/// variant's retail price. The overrides of a product are kept together as
+/// one specification, such as
+/// `("Store Prices", r#"[{"variant_code":"10001","store_code":"002","price":49.0}]"#)`.
+pub fn add_store_price(
+    product: &mut Product,
+    variant_code: &str,
+    retail_price: f32,
+    store_code: &str,
+    price: f32,
+) {
+    if price == retail_price {
+        return;
+    }
+
+    let mut prices = store_prices(product);
+    prices.push(StorePrice {
+        variant_code: variant_code.to_string(),
+        store_code: store_code.to_string(),
+        price,
+    });
+
+    let value = serde_json::to_string(&prices).expect("store prices are serializable");
+
+    match product
+        .specifications
+        .iter_mut()
+        .find(|(name, _)| name == STORE_PRICES)
+    {
+        Some((_, existing)) => *existing = value,
+        None => product
+            .specifications
+            .push((STORE_PRICES.to_string(), value)),
+    }
+}
+
+/// Where a source system's custom field is placed on a product.
+/// Parsed from its lowercase name, such as `tag`.
+#[derive(Debug, Copy, Clone, PartialEq, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum MetafieldTarget {
+    /// Kept as a key/value pair in `Product.specifications`.
+    Specification,
+    /// Each value is added to `Product.tags`.
+    Tag,
+    /// Appended to `Product.description_long`.
+    Description,
+    /// Not carried over.
+    Ignore,
+}
+
+/// Splits a delimited tag list, such as `"summer, sale, cotton"`, into
+/// trimmed tags without duplicates. Duplicates are matched regardless of
+/// case, keeping the first spelling unless `lowercase` is set.
+pub fn parse_tags(value: &str, separator: char, lowercase: bool) -> Vec<String> {
+    let mut tags = vec![];
+
+    merge_tags(
+        &mut tags,
+        value.split(separator).map(|tag| tag.trim().to_string()),
+        lowercase,
+    );
+
+    tags
+}
+
+/// Adds tags to an existing list, skipping empty tags and any already
+/// present regardless of case, and lowercasing them if `lowercase` is set.
+pub fn merge_tags(
+    tags: &mut Vec<String>,
+    additional: impl IntoIterator<Item = String>,
+    lowercase: bool,
+) {
+    for tag in additional {
+        let tag = if lowercase { tag.to_lowercase() } else { tag };
+
+        if !tag.is_empty()
+            && !tags
+                .iter()
+                .any(|existing| existing.to_lowercase() == tag.to_lowercase())
+        {
+            tags.push(tag);
+        }
+    }
+}
+
+/// Adds an option value to the product's variant group for the option,
+/// creating the group if needed. Values already present are skipped.
+pub fn add_variant_option(product: &mut Product, category: &str, value: &str) {
+    if category.is_empty() || value.is_empty() {
+        return;
+    }
+
+    let group = match product
+        .variant_groups
+        .iter_mut()
+        .find(|group| group.category == category)
+    {
+        Some(group) => group,
+        None => {
+            product.variant_groups.push(VariantCategory {
+                category: category.to_string(),
+                variants: vec![],
+            });
+
+            product.variant_groups.last_mut().unwrap()
+        }
+    };
+
+    if group.variants.iter().any(|variant| variant.name == value) {
+        return;
+    }
+
+    group.variants.push(Variant {
+        name: value.to_string(),
+        images: vec![],
+        marginal_price: 0.00,
+        variant_code: format!("{}-{}", category, value),
+        order_history: vec![],
+    });
+}
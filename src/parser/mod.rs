@@ -1,7 +1,23 @@
 pub mod read;
 pub mod parse;
 pub mod format;
+pub mod identification;
+pub mod mapping;
+pub mod options;
+pub mod gift_card;
+pub mod promotion;
+pub mod resolve;
+pub mod declarative;
+pub mod registry;
 
 pub use read::*;
 pub use parse::*;
-pub use format::*;
\ No newline at end of file
+pub use format::*;
+pub use identification::*;
+pub use mapping::*;
+pub use options::*;
+pub use gift_card::*;
+pub use promotion::*;
+pub use resolve::*;
+pub use declarative::*;
+pub use registry::*;
\ No newline at end of file
@@ -0,0 +1,89 @@
+use super::{is_weight_unit, MetafieldTarget};
+use std::{cell::RefCell, collections::HashMap};
+
+/// Choices made for a conversion which its exports do not carry, such as the
+/// unit a platform's weights are in.
+///
+/// Formats read them while the exports are read within `scope`, which
+/// `convert_from_directory` does for the options it is given. Outside of a
+/// scope every format reads with the defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConversionOptions {
+    /// Lowercases every tag, rather than keeping the first spelling of each.
+    pub lowercase_tags: bool,
+    weight_units: HashMap<String, String>,
+    metafield_targets: HashMap<String, MetafieldTarget>,
+}
+
+thread_local! {
+    static CURRENT: RefCell<ConversionOptions> = RefCell::new(ConversionOptions::default());
+}
+
+/// Puts back the options a scope replaced, even if reading panicked.
+struct Restore(Option<ConversionOptions>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        if let Some(previous) = self.0.take() {
+            CURRENT.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+}
+
+impl ConversionOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the weights of the named format's exports in `unit`, for platforms
+    /// which export weights in the unit the store is configured with.
+    ///
+    /// Returns `false`, leaving the format's unit as it was, if the unit is not recognised.
+    pub fn set_weight_unit(&mut self, format: &str, unit: &str) -> bool {
+        if !is_weight_unit(unit) {
+            return false;
+        }
+
+        self.weight_units
+            .insert(format.trim().to_string(), unit.trim().to_string());
+
+        true
+    }
+
+    /// Places the Shopify metafield `namespace.key`, replacing its default.
+    pub fn set_metafield_target(&mut self, path: &str, target: MetafieldTarget) {
+        self.metafield_targets
+            .insert(path.trim().to_string(), target);
+    }
+
+    /// Runs `read` with these options, restoring those it replaced afterwards.
+    pub fn scope<R>(&self, read: impl FnOnce() -> R) -> R {
+        let previous = CURRENT.with(|current| current.replace(self.clone()));
+        let _restore = Restore(Some(previous));
+
+        read()
+    }
+}
+
+/// Whether tags are lowercased in the current conversion.
+pub fn lowercase_tags() -> bool {
+    CURRENT.with(|current| current.borrow().lowercase_tags)
+}
+
+/// The unit the named format's weights are exported in, as set for the
+/// current conversion, otherwise the platform's `default`.
+pub fn weight_unit(format: &str, default: &str) -> String {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .weight_units
+            .get(format)
+            .map_or(default.to_string(), |unit| unit.clone())
+    })
+}
+
+/// Where the metafield `namespace.key` is placed in the current conversion,
+/// if it was placed at all.
+pub fn metafield_target(path: &str) -> Option<MetafieldTarget> {
+    CURRENT.with(|current| current.borrow().metafield_targets.get(path).copied())
+}
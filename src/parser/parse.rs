@@ -1,25 +1,11 @@
-use crate::parser::ParseType;
-use crate::{InlineDatabase};
-use crate::{
-    parser::format, parser::lightrail::CustomerRecord as lCR,
-    parser::lightrail::KioskRecord as lKR, parser::lightrail::ProductRecord as lPR,
-    parser::lightrail::StoreRecord as lSR, parser::lightrail::TransactionRecord as lTR,
-    parser::shopify::CustomerRecord as sCR, parser::shopify::KioskRecord as sKR,
-    parser::shopify::ProductRecord as sPR, parser::shopify::StoreRecord as sSR,
-    parser::shopify::TransactionRecord as sTR,
-};
+use crate::parser::{is_mapping_file, register_mapping_files, registry, ParseType};
 use core::fmt;
-use csv::Reader;
-use open_stock::{Customer, Kiosk, Product, Store, Transaction};
-use phf::{phf_map, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 use std::path::Path;
 use std::path::PathBuf;
-use std::{fs::{DirEntry, File}, fs, io::{BufRead, BufReader, Lines}, io, usize::MAX};
-use strsim::levenshtein;
-use strum::IntoEnumIterator;
+use std::{fs::File, fs, io::{BufRead, BufReader, Lines}};
 
 #[derive(Debug)]
 pub enum ParseFailure {
@@ -28,44 +14,6 @@ pub enum ParseFailure {
     EOFException,
 }
 
-// type Parser<T> = fn(Reader<File>) -> Result<Vec<T>, ParseFailure>;
-type KioskParser = fn(Reader<File>, &mut InlineDatabase) -> Result<Vec<Kiosk>, ParseFailure>;
-type StoreParser = fn(Reader<File>, &mut InlineDatabase) -> Result<Vec<Store>, ParseFailure>;
-type ProductParser = fn(Reader<File>, &mut InlineDatabase) -> Result<Vec<Product>, ParseFailure>;
-type CustomerParser = fn(Reader<File>, &mut InlineDatabase) -> Result<Vec<Customer>, ParseFailure>;
-type TransactionParser =
-    fn(Reader<File>, &mut InlineDatabase) -> Result<Vec<Transaction>, ParseFailure>;
-
-pub static KIOSK_FORMATS: Map<&'static str, KioskParser> = phf_map! {
-    "shopify" => format::shopify::parse_type::<open_stock::Kiosk, sKR>,
-    "lightrail" => format::lightrail::parse_type::<open_stock::Kiosk, lKR>
-};
-
-pub static STORE_FORMATS: Map<&'static str, StoreParser> = phf_map! {
-    "shopify" => format::shopify::parse_type::<open_stock::Store, sSR>,
-    "lightrail" => format::lightrail::parse_type::<open_stock::Store, lSR>
-};
-
-pub static PRODUCT_FORMATS: phf::Map<&'static str, ProductParser> = phf_map! {
-    "shopify" => format::shopify::parse_type::<open_stock::Product, sPR>,
-    "lightrail" => format::lightrail::parse_type::<open_stock::Product, lPR>
-};
-
-pub static CUSTOMER_FORMATS: Map<&'static str, CustomerParser> = phf_map! {
-    "shopify" => format::shopify::parse_type::<open_stock::Customer, sCR>,
-    "lightrail" => format::lightrail::parse_type::<open_stock::Customer, lCR>,
-};
-
-pub static TRANSACTION_FORMATS: Map<&'static str, TransactionParser> = phf_map! {
-    "shopify" => format::shopify::parse_type::<open_stock::Transaction, sTR>,
-    "lightrail" => format::lightrail::parse_type::<open_stock::Transaction, lTR>
-};
-
-pub static HEADER_FORMAT_MATCHERS: phf::Map<&'static str, fn(ParseType) -> String> = phf_map! {
-    "shopify" => format::shopify::match_self,
-    "lightrail" => format::lightrail::match_self
-};
-
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 pub struct Classification {
     pub score: usize,
@@ -87,8 +35,8 @@ impl Display for Classification {
     }
 }
 
-pub fn classify_type(entry: &PathBuf) -> Classification {
-    let path: std::path::PathBuf = entry.clone();
+pub fn classify_type(entry: &Path) -> Classification {
+    let path: std::path::PathBuf = entry.to_path_buf();
     let ospos_file = path
         .file_name()
         .unwrap()
@@ -97,11 +45,11 @@ pub fn classify_type(entry: &PathBuf) -> Classification {
         .to_string()
         .ends_with(".os");
 
-    if ospos_file {
+    if ospos_file || is_mapping_file(&path) {
         return Classification {
             score: 0,
             path: path.clone(),
-            branding: "ospos".to_string(),
+            branding: if ospos_file { "ospos" } else { "mapping" }.to_string(),
             variant: ParseType::Invalid,
         };
     }
@@ -113,9 +61,12 @@ pub fn classify_type(entry: &PathBuf) -> Classification {
     classify_from_value(path, lines)
 }
 
+#[allow(clippy::unnecessary_sort_by)]
 pub fn classify_by_path(path: &Path) -> Result<Vec<Classification>, std::io::Error> {
     println!("Traversing {}", path.to_str().unwrap_or_default());
 
+    register_mapping_files(path)?;
+
     traverse_directories(path, &classify_type).map(|mut v| {
         v.sort_by(|a, b| (a.variant as u32).cmp(&(b.variant as u32)));
         v
@@ -123,37 +74,20 @@ pub fn classify_by_path(path: &Path) -> Result<Vec<Classification>, std::io::Err
 }
 
 pub fn classify_from_value(path: PathBuf, mut lines: Lines<BufReader<File>>) -> Classification {
-    let mut best_match = Classification {
-        score: MAX,
-        path: path.clone(),
-        branding: "none".to_string(),
-        variant: ParseType::Product,
-    };
-
-    if let Some(Ok(line)) = lines.next() {
-        for (key, val) in HEADER_FORMAT_MATCHERS.into_iter() {
-            for variant in ParseType::iter() {
-                let comparative = val(variant);
-                let score = levenshtein(line.as_str(), comparative.as_str());
-
-                if score < best_match.score {
-                    best_match = Classification {
-                        branding: key.to_string(),
-                        score,
-                        path: path.clone(),
-                        variant,
-                    }
-                }
-            }
-        }
+    match lines.next() {
+        Some(Ok(line)) => registry().read().unwrap().classify(path, line.as_str()),
+        _ => Classification {
+            score: usize::MAX,
+            path,
+            branding: "none".to_string(),
+            variant: ParseType::Product,
+        },
     }
-
-    best_match
 }
 
 pub fn traverse_directories(
     dir: &Path,
-    cb: &dyn Fn(&PathBuf) -> Classification,
+    cb: &dyn Fn(&Path) -> Classification,
 ) -> Result<Vec<Classification>, std::io::Error> {
     let mut classifications = vec![];
 
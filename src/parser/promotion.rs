@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+/// The terms of a promotion which `open_stock` has no field for.
+///
+/// These are kept alongside the promotions in the output, linked by the
+/// promotion's id, until they can be imported.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PromotionTerms {
+    pub promotion_id: String,
+    pub code: String,
+    /// The exact discount, as a `DiscountValue` only holds whole numbers.
+    pub value: f32,
+    pub percentage: bool,
+    /// Whether the promotion waives shipping rather than discounting products.
+    pub free_shipping: bool,
+    /// The number of times the code may be redeemed, if it is limited.
+    pub usage_limit: Option<u32>,
+    pub times_used: u32,
+}
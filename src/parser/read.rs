@@ -1,7 +1,11 @@
+#![allow(clippy::manual_try_fold)]
+
 use core::fmt;
-use csv::Reader;
+use csv::{Reader, ReaderBuilder};
 use open_stock::{Customer, Product, Store, Transaction};
 use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
 
 pub struct Products(pub Vec<Product>);
 
@@ -45,7 +49,48 @@ impl fmt::Display for Stores {
 
 use crate::{parser::ParseType, InlineDatabase};
 
-use super::{CUSTOMER_FORMATS, KIOSK_FORMATS, PRODUCT_FORMATS, STORE_FORMATS, TRANSACTION_FORMATS};
+use super::find_format;
+
+/// The delimiters exports are separated by. PrestaShop separates
+/// columns by semicolons, and marketplaces export tab-separated files.
+const DELIMITERS: [u8; 3] = [b',', b';', b'\t'];
+
+/// Finds the delimiter of a header line, as the one which occurs most often
+/// outside of quotes. Commas are preferred when none of them occur.
+pub fn detect_delimiter(header: &str) -> u8 {
+    let mut counts = [0usize; DELIMITERS.len()];
+    let mut quoted = false;
+
+    for byte in header.bytes() {
+        if byte == b'"' {
+            quoted = !quoted;
+        } else if !quoted {
+            if let Some(index) = DELIMITERS.iter().position(|delimiter| *delimiter == byte) {
+                counts[index] += 1;
+            }
+        }
+    }
+
+    let mut best = 0;
+
+    for (index, count) in counts.iter().enumerate() {
+        if *count > counts[best] {
+            best = index;
+        }
+    }
+
+    DELIMITERS[best]
+}
+
+/// Opens an export for reading, separated by the delimiter of its header.
+pub fn open_reader(path: &Path) -> Result<Reader<File>, csv::Error> {
+    let mut header = String::new();
+    BufReader::new(File::open(path)?).read_line(&mut header)?;
+
+    ReaderBuilder::new()
+        .delimiter(detect_delimiter(&header))
+        .from_path(path)
+}
 
 pub fn read_file(
     reader: Reader<File>,
@@ -53,96 +98,57 @@ pub fn read_file(
     file_type: ParseType,
     db: &mut InlineDatabase,
 ) {
-    match file_type {
-        ParseType::Product => {
-            match PRODUCT_FORMATS.get(&format) {
-                Some(executor) => {
-                    let result = executor(reader, db);
-
-                    match result {
-                        Ok(mut pdt) => (db).0.append(&mut pdt),
-                        Err(e) => {
-                            // Handle error
-                            println!("[err]: Failed to parse row of input, reason: {:?}", e);
-                        }
-                    }
-                }
-                None => {
-                    panic!("No respective key exists, {}.", format)
-                }
-            }
-        }
-        ParseType::Kiosk => match KIOSK_FORMATS.get(&format) {
-            Some(executor) => {
-                let result = executor(reader, db);
-
-                match result {
-                    Ok(mut ksk) => (db).4.append(&mut ksk),
-                    Err(e) => {
-                        eprintln!("[err]: Failed to parse row of input, reason: {:?}", e);
-                    }
-                }
-            }
-            None => {
-                panic!("No respective key exists, {}.", format)
-            }
-        },
-        ParseType::Customer => {
-            match CUSTOMER_FORMATS.get(&format) {
-                Some(executor) => {
-                    let result = executor(reader, db);
-
-                    match result {
-                        Ok(mut custom) => (db).1.append(&mut custom),
-                        Err(e) => {
-                            // Handle error
-                            eprintln!("[err]: Failed to parse row of input, reason: {:?}", e);
-                        }
-                    }
-                }
-                None => {
-                    panic!("No respective key exists, {}.", format)
-                }
-            }
-        }
-        ParseType::Transaction => {
-            match TRANSACTION_FORMATS.get(&format) {
-                Some(executor) => {
-                    let result = executor(reader, db);
-
-                    match result {
-                        Ok(mut trans) => (db).2.append(&mut trans),
-                        Err(e) => {
-                            // Handle error
-                            eprintln!("[err]: Failed to parse row of input, reason: {:?}", e);
-                        }
-                    }
-                }
-                None => {
-                    panic!("No respective key exists, {}.", format)
-                }
-            }
-        }
-        ParseType::Store => {
-            match STORE_FORMATS.get(&format) {
-                Some(executor) => {
-                    let result = executor(reader, db);
-
-                    match result {
-                        Ok(mut store) => (db).3.append(&mut store),
-                        Err(e) => {
-                            // Handle error
-                            eprintln!("[err]: Failed to parse row of input, reason: {:?}", e);
-                        }
-                    }
-                }
-                None => {
-                    panic!("No respective key exists, {}.", format)
-                }
+    if let ParseType::Invalid = file_type {
+        println!("[log]: Did nothing, hit invalid file type.");
+        return;
+    }
+
+    match find_format(&format) {
+        Some(source) => {
+            if let Err(e) = source.read(reader, file_type, db) {
+                eprintln!("[err]: Failed to parse row of input, reason: {:?}", e);
             }
         }
-        ParseType::Invalid => {
-            println!("[log]: Did nothing, hit invalid file type.")
+        None => {
+            panic!("No respective key exists, {}.", format)
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::detect_delimiter;
+
+    #[test]
+    fn commas_are_detected_and_preferred() {
+        assert_eq!(detect_delimiter("Handle,Title,Body (HTML)"), b',');
+        assert_eq!(detect_delimiter("Handle"), b',');
+        assert_eq!(detect_delimiter(""), b',');
+    }
+
+    #[test]
+    fn semicolons_are_detected() {
+        assert_eq!(
+            detect_delimiter("Product ID;Active (0/1);Name *;Categories (x,y,z...)"),
+            b';'
+        );
+    }
+
+    #[test]
+    fn tabs_are_detected() {
+        assert_eq!(detect_delimiter("sku\tprice\tquantity\n"), b'\t');
+        assert_eq!(
+            detect_delimiter("item-name\titem-description, in full\tsku"),
+            b'\t'
+        );
+    }
+
+    #[test]
+    fn delimiters_within_quotes_are_not_counted() {
+        assert_eq!(
+            detect_delimiter("\"Name, first; last\";\"Email\";Phone"),
+            b';'
+        );
+        assert_eq!(detect_delimiter("\"a;b;c;d\",\"e;f\",g"), b',');
+    }
+}
@@ -0,0 +1,428 @@
+//! The formats exports are classified by and read with.
+//!
+//! Each compiled-in format declares its header templates and parsers in one
+//! place, in [`BUILTIN_FORMATS`]. Library consumers may add their own by
+//! implementing [`Format`] and registering it with [`register_format`], or
+//! read with a [`FormatRegistry`] of their own.
+
+use crate::{
+    parser::{
+        bigcommerce::CustomerRecord as bCR, bigcommerce::TransactionRecord as bTR,
+        clover::CustomerRecord as cCR, clover::ProductRecord as cPR,
+        clover::TransactionRecord as cTR, ebay::ProductRecord as ebPR,
+        ecwid::CustomerRecord as ecCR, ecwid::TransactionRecord as ecTR,
+        etsy::TransactionRecord as eTR, format, lightrail::CustomerRecord as lCR,
+        lightrail::KioskRecord as lKR, lightrail::ProductRecord as lPR,
+        lightrail::PromotionRecord as lPmR, lightrail::StoreRecord as lSR,
+        lightrail::TransactionRecord as lTR, odoo::CustomerRecord as oCR,
+        odoo::ProductRecord as oPR, odoo::TransactionRecord as oTR,
+        prestashop::CustomerRecord as pCR, prestashop::ProductRecord as pPR,
+        prestashop::TransactionRecord as pTR, quickbooks::CustomerRecord as qCR,
+        quickbooks::ProductRecord as qPR, shopify::CustomerRecord as sCR,
+        shopify::GiftCardRecord as sGR, shopify::KioskRecord as sKR,
+        shopify::PromotionRecord as sPmR, shopify::StoreRecord as sSR,
+        shopify::TransactionRecord as sTR, sumup::ProductRecord as suPR,
+        sumup::TransactionRecord as suTR, vend::CustomerRecord as vCR,
+        vend::TransactionRecord as vTR, xero::CustomerRecord as xCR, xero::ProductRecord as xPR,
+        zettle::ProductRecord as zPR, zettle::TransactionRecord as zTR, Classification, GiftCard,
+        ParseFailure, ParseType,
+    },
+    InlineDatabase,
+};
+use csv::Reader;
+use open_stock::{Customer, Kiosk, Product, Promotion, Store, Transaction};
+use std::{
+    fs::File,
+    path::PathBuf,
+    sync::{Arc, OnceLock, RwLock},
+};
+use strsim::levenshtein;
+use strum::IntoEnumIterator;
+
+pub type Parser<T> = fn(Reader<File>, &mut InlineDatabase) -> Result<Vec<T>, ParseFailure>;
+
+/// A source system whose exports can be classified and read.
+pub trait Format: Send + Sync {
+    /// Names the format, as the branding of the exports it classifies.
+    fn name(&self) -> &str;
+
+    /// The header line of the given type of export,
+    /// or `None` if the format does not read that type.
+    fn header(&self, parse_type: ParseType) -> Option<String>;
+
+    /// Reads an export of the given type into the database.
+    fn read(
+        &self,
+        reader: Reader<File>,
+        parse_type: ParseType,
+        db: &mut InlineDatabase,
+    ) -> Result<(), ParseFailure>;
+}
+
+/// A format compiled into the migrator, whose header templates are given
+/// by its module's `match_self` and which has a parser for each type of
+/// export it reads.
+#[derive(Clone, Copy)]
+pub struct CompiledFormat {
+    name: &'static str,
+    matcher: fn(ParseType) -> String,
+    stores: Option<Parser<Store>>,
+    kiosks: Option<Parser<Kiosk>>,
+    products: Option<Parser<Product>>,
+    product_modifiers: Option<Parser<Product>>,
+    product_categories: Option<Parser<Product>>,
+    promotions: Option<Parser<Promotion>>,
+    customers: Option<Parser<Customer>>,
+    customer_addresses: Option<Parser<Customer>>,
+    gift_cards: Option<Parser<GiftCard>>,
+    transactions: Option<Parser<Transaction>>,
+    transaction_items: Option<Parser<Transaction>>,
+}
+
+impl CompiledFormat {
+    pub const fn new(name: &'static str, matcher: fn(ParseType) -> String) -> Self {
+        Self {
+            name,
+            matcher,
+            stores: None,
+            kiosks: None,
+            products: None,
+            product_modifiers: None,
+            product_categories: None,
+            promotions: None,
+            customers: None,
+            customer_addresses: None,
+            gift_cards: None,
+            transactions: None,
+            transaction_items: None,
+        }
+    }
+
+    pub const fn stores(mut self, parser: Parser<Store>) -> Self {
+        self.stores = Some(parser);
+        self
+    }
+
+    pub const fn kiosks(mut self, parser: Parser<Kiosk>) -> Self {
+        self.kiosks = Some(parser);
+        self
+    }
+
+    pub const fn products(mut self, parser: Parser<Product>) -> Self {
+        self.products = Some(parser);
+        self
+    }
+
+    pub const fn product_modifiers(mut self, parser: Parser<Product>) -> Self {
+        self.product_modifiers = Some(parser);
+        self
+    }
+
+    pub const fn product_categories(mut self, parser: Parser<Product>) -> Self {
+        self.product_categories = Some(parser);
+        self
+    }
+
+    pub const fn promotions(mut self, parser: Parser<Promotion>) -> Self {
+        self.promotions = Some(parser);
+        self
+    }
+
+    pub const fn customers(mut self, parser: Parser<Customer>) -> Self {
+        self.customers = Some(parser);
+        self
+    }
+
+    pub const fn customer_addresses(mut self, parser: Parser<Customer>) -> Self {
+        self.customer_addresses = Some(parser);
+        self
+    }
+
+    pub const fn gift_cards(mut self, parser: Parser<GiftCard>) -> Self {
+        self.gift_cards = Some(parser);
+        self
+    }
+
+    pub const fn transactions(mut self, parser: Parser<Transaction>) -> Self {
+        self.transactions = Some(parser);
+        self
+    }
+
+    pub const fn transaction_items(mut self, parser: Parser<Transaction>) -> Self {
+        self.transaction_items = Some(parser);
+        self
+    }
+
+    fn reads(&self, parse_type: ParseType) -> bool {
+        match parse_type {
+            ParseType::Store => self.stores.is_some(),
+            ParseType::Kiosk => self.kiosks.is_some(),
+            ParseType::Product => self.products.is_some(),
+            ParseType::ProductModifier => self.product_modifiers.is_some(),
+            ParseType::ProductCategory => self.product_categories.is_some(),
+            ParseType::Promotion => self.promotions.is_some(),
+            ParseType::Customer => self.customers.is_some(),
+            ParseType::CustomerAddress => self.customer_addresses.is_some(),
+            ParseType::GiftCard => self.gift_cards.is_some(),
+            ParseType::Transaction => self.transactions.is_some(),
+            ParseType::TransactionItem => self.transaction_items.is_some(),
+            // Matched so that unrecognisable exports are passed over.
+            ParseType::Invalid => true,
+        }
+    }
+}
+
+fn run<T>(
+    parser: Option<Parser<T>>,
+    reader: Reader<File>,
+    db: &mut InlineDatabase,
+) -> Result<Vec<T>, ParseFailure> {
+    match parser {
+        Some(parser) => parser(reader, db),
+        None => Err(ParseFailure::FormatFailure(
+            "The format does not read this type of export.".to_string(),
+        )),
+    }
+}
+
+impl Format for CompiledFormat {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn header(&self, parse_type: ParseType) -> Option<String> {
+        if self.reads(parse_type) {
+            Some((self.matcher)(parse_type))
+        } else {
+            None
+        }
+    }
+
+    fn read(
+        &self,
+        reader: Reader<File>,
+        parse_type: ParseType,
+        db: &mut InlineDatabase,
+    ) -> Result<(), ParseFailure> {
+        match parse_type {
+            ParseType::Store => {
+                let mut stores = run(self.stores, reader, db)?;
+                db.3.append(&mut stores);
+            }
+            ParseType::Kiosk => {
+                let mut kiosks = run(self.kiosks, reader, db)?;
+                db.4.append(&mut kiosks);
+            }
+            ParseType::Product => {
+                let mut products = run(self.products, reader, db)?;
+                db.0.append(&mut products);
+            }
+            ParseType::ProductModifier => {
+                let mut products = run(self.product_modifiers, reader, db)?;
+                db.0.append(&mut products);
+            }
+            ParseType::ProductCategory => {
+                let mut products = run(self.product_categories, reader, db)?;
+                db.0.append(&mut products);
+            }
+            ParseType::Promotion => {
+                let mut promotions = run(self.promotions, reader, db)?;
+                db.6.append(&mut promotions);
+            }
+            ParseType::Customer => {
+                let mut customers = run(self.customers, reader, db)?;
+                db.1.append(&mut customers);
+            }
+            ParseType::CustomerAddress => {
+                let mut customers = run(self.customer_addresses, reader, db)?;
+                db.1.append(&mut customers);
+            }
+            ParseType::GiftCard => {
+                let mut cards = run(self.gift_cards, reader, db)?;
+                db.7.append(&mut cards);
+            }
+            ParseType::Transaction => {
+                let mut transactions = run(self.transactions, reader, db)?;
+                db.2.append(&mut transactions);
+            }
+            ParseType::TransactionItem => {
+                let mut transactions = run(self.transaction_items, reader, db)?;
+                db.2.append(&mut transactions);
+            }
+            ParseType::Invalid => {}
+        }
+
+        Ok(())
+    }
+}
+
+pub static BUILTIN_FORMATS: [CompiledFormat; 20] = [
+    CompiledFormat::new("shopify", format::shopify::match_self)
+        .stores(format::shopify::parse_type::<Store, sSR>)
+        .kiosks(format::shopify::parse_type::<Kiosk, sKR>)
+        .products(format::shopify::parse_products)
+        .promotions(format::shopify::parse_type::<Promotion, sPmR>)
+        .customers(format::shopify::parse_type::<Customer, sCR>)
+        .customer_addresses(format::shopify::parse_customer_addresses)
+        .gift_cards(format::shopify::parse_type::<GiftCard, sGR>)
+        .transactions(format::shopify::parse_type::<Transaction, sTR>),
+    CompiledFormat::new("vend", format::vend::match_self)
+        .products(format::vend::parse_products)
+        .customers(format::vend::parse_type::<Customer, vCR>)
+        .transactions(format::vend::parse_type::<Transaction, vTR>),
+    CompiledFormat::new("bigcommerce", format::bigcommerce::match_self)
+        .products(format::bigcommerce::parse_products)
+        .customers(format::bigcommerce::parse_type::<Customer, bCR>)
+        .transactions(format::bigcommerce::parse_type::<Transaction, bTR>),
+    CompiledFormat::new("magento", format::magento::match_self)
+        .products(format::magento::parse_products)
+        .customers(format::magento::parse_customers)
+        .customer_addresses(format::magento::parse_customer_addresses),
+    CompiledFormat::new("clover", format::clover::match_self)
+        .products(format::clover::parse_type::<Product, cPR>)
+        .product_modifiers(format::clover::parse_modifier_groups)
+        .product_categories(format::clover::parse_categories)
+        .customers(format::clover::parse_type::<Customer, cCR>)
+        .transactions(format::clover::parse_type::<Transaction, cTR>),
+    CompiledFormat::new("etsy", format::etsy::match_self)
+        .products(format::etsy::parse_products)
+        .transactions(format::etsy::parse_type::<Transaction, eTR>)
+        .transaction_items(format::etsy::parse_order_items),
+    CompiledFormat::new("zettle", format::zettle::match_self)
+        .products(format::zettle::parse_type::<Product, zPR>)
+        .transactions(format::zettle::parse_type::<Transaction, zTR>),
+    CompiledFormat::new("sumup", format::sumup::match_self)
+        .products(format::sumup::parse_type::<Product, suPR>)
+        .transactions(format::sumup::parse_type::<Transaction, suTR>),
+    CompiledFormat::new("loyverse", format::loyverse::match_self)
+        .products(format::loyverse::parse_products),
+    CompiledFormat::new("hike", format::hike::match_self).products(format::hike::parse_products),
+    CompiledFormat::new("prestashop", format::prestashop::match_self)
+        .products(format::prestashop::parse_type::<Product, pPR>)
+        .product_modifiers(format::prestashop::parse_combinations)
+        .customers(format::prestashop::parse_type::<Customer, pCR>)
+        .customer_addresses(format::prestashop::parse_customer_addresses)
+        .transactions(format::prestashop::parse_type::<Transaction, pTR>),
+    CompiledFormat::new("ecwid", format::ecwid::match_self)
+        .products(format::ecwid::parse_products)
+        .customers(format::ecwid::parse_type::<Customer, ecCR>)
+        .transactions(format::ecwid::parse_type::<Transaction, ecTR>),
+    CompiledFormat::new("odoo", format::odoo::match_self)
+        .products(format::odoo::parse_type::<Product, oPR>)
+        .product_modifiers(format::odoo::parse_variants)
+        .customers(format::odoo::parse_type::<Customer, oCR>)
+        .transactions(format::odoo::parse_type::<Transaction, oTR>)
+        .transaction_items(format::odoo::parse_order_lines),
+    CompiledFormat::new("amazon", format::amazon::match_self)
+        .products(format::amazon::parse_products),
+    CompiledFormat::new("ebay", format::ebay::match_self)
+        .products(format::ebay::parse_type::<Product, ebPR>),
+    CompiledFormat::new("quickbooks", format::quickbooks::match_self)
+        .products(format::quickbooks::parse_type::<Product, qPR>)
+        .customers(format::quickbooks::parse_type::<Customer, qCR>),
+    CompiledFormat::new("xero", format::xero::match_self)
+        .products(format::xero::parse_type::<Product, xPR>)
+        .customers(format::xero::parse_type::<Customer, xCR>),
+    CompiledFormat::new("stripe", format::stripe::match_self)
+        .transaction_items(format::stripe::parse_payments),
+    CompiledFormat::new("paypal", format::paypal::match_self)
+        .transaction_items(format::paypal::parse_activity),
+    CompiledFormat::new("lightrail", format::lightrail::match_self)
+        .stores(format::lightrail::parse_type::<Store, lSR>)
+        .kiosks(format::lightrail::parse_type::<Kiosk, lKR>)
+        .products(format::lightrail::parse_type::<Product, lPR>)
+        .promotions(format::lightrail::parse_type::<Promotion, lPmR>)
+        .customers(format::lightrail::parse_type::<Customer, lCR>)
+        .transactions(format::lightrail::parse_type::<Transaction, lTR>),
+];
+
+/// The formats exports are classified against, in the order registered.
+#[derive(Clone, Default)]
+pub struct FormatRegistry {
+    formats: Vec<Arc<dyn Format>>,
+}
+
+impl FormatRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry of the compiled-in formats.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+
+        for format in BUILTIN_FORMATS {
+            registry.register(format);
+        }
+
+        registry
+    }
+
+    /// Registers a format, replacing any of the same name.
+    pub fn register(&mut self, format: impl Format + 'static) {
+        self.formats
+            .retain(|existing| existing.name() != format.name());
+        self.formats.push(Arc::new(format));
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn Format>> {
+        self.formats
+            .iter()
+            .find(|format| format.name() == name)
+            .cloned()
+    }
+
+    pub fn formats(&self) -> impl Iterator<Item = &Arc<dyn Format>> {
+        self.formats.iter()
+    }
+
+    /// Finds the format and type of an export by the header template
+    /// nearest to its header line. Ties go to the first registered.
+    pub fn classify(&self, path: PathBuf, header: &str) -> Classification {
+        let mut best_match = Classification {
+            score: usize::MAX,
+            path: path.clone(),
+            branding: "none".to_string(),
+            variant: ParseType::Product,
+        };
+
+        for format in &self.formats {
+            for variant in ParseType::iter() {
+                let Some(comparative) = format.header(variant) else {
+                    continue;
+                };
+
+                let score = levenshtein(header, comparative.as_str());
+
+                if score < best_match.score {
+                    best_match = Classification {
+                        branding: format.name().to_string(),
+                        score,
+                        path: path.clone(),
+                        variant,
+                    }
+                }
+            }
+        }
+
+        best_match
+    }
+}
+
+static REGISTRY: OnceLock<RwLock<FormatRegistry>> = OnceLock::new();
+
+/// The registry exports are classified and read with,
+/// which holds the compiled-in formats until others are registered.
+pub fn registry() -> &'static RwLock<FormatRegistry> {
+    REGISTRY.get_or_init(|| RwLock::new(FormatRegistry::builtin()))
+}
+
+/// Registers a format for exports to be classified and read with,
+/// replacing any of the same name (including those compiled in).
+pub fn register_format(format: impl Format + 'static) {
+    registry().write().unwrap().register(format);
+}
+
+pub fn find_format(name: &str) -> Option<Arc<dyn Format>> {
+    registry().read().unwrap().get(name)
+}
@@ -0,0 +1,216 @@
+use crate::InlineDatabase;
+use chrono::Utc;
+use open_stock::{
+    AccountType, Address, ContactInformation, Email, Employee, EmployeeAuth, Kiosk,
+    KioskPreferences, MobileNumber, Name, Payment, PaymentAction, PaymentMethod, PaymentProcessor,
+    PaymentStatus, Price, Processable, Store, Transaction,
+};
+use uuid::Uuid;
+
+/// Contact information holding only a name, for entities
+/// which sources name but export no details for.
+pub fn named_contact(name: &str) -> ContactInformation {
+    ContactInformation {
+        name: name.to_string(),
+        mobile: MobileNumber::from(String::new()),
+        email: Email::from(String::new()),
+        landline: String::new(),
+        address: Address {
+            street: String::new(),
+            street2: String::new(),
+            city: String::new(),
+            country: String::new(),
+            po_code: String::new(),
+            lat: 0.0,
+            lon: 0.0,
+        },
+    }
+}
+
+/// Finds the store with the given name (such as a POS location or outlet),
+/// creating it the first time it is seen. An empty name yields `None`.
+pub fn resolve_store(name: &str, db: &mut InlineDatabase) -> Option<Store> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return None;
+    }
+
+    if let Some(store) = db.3.iter().find(|store| store.name == name) {
+        return Some(store.clone());
+    }
+
+    let store = Store {
+        id: Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        contact: named_contact(name),
+        code: format!("{:03}", db.3.len() + 1),
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+
+    db.3.push(store.clone());
+
+    Some(store)
+}
+
+/// Finds the kiosk (such as a POS device or register) with the given name
+/// within a store, creating it the first time it is seen. Kiosks are created
+/// enabled, as the sales attributed to them were rung up on them.
+pub fn resolve_kiosk(name: &str, store: &Store, db: &mut InlineDatabase) -> Option<Kiosk> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return None;
+    }
+
+    if let Some(kiosk) =
+        db.4.iter()
+            .find(|kiosk| kiosk.name == name && kiosk.store_id == store.id)
+    {
+        return Some(kiosk.clone());
+    }
+
+    let kiosk = Kiosk {
+        id: Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        store_id: store.id.clone(),
+        preferences: KioskPreferences {
+            printer_id: "".to_string(),
+        },
+        disabled: false,
+        last_online: Utc::now(),
+    };
+
+    db.4.push(kiosk.clone());
+
+    Some(kiosk)
+}
+
+/// Finds the employee with the given full name,
+/// creating them the first time they are seen.
+pub fn resolve_employee(name: &str, db: &mut InlineDatabase) -> Option<Employee> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return None;
+    }
+
+    if let Some(employee) = db.5.iter().find(|employee| employee.contact.name == name) {
+        return Some(employee.clone());
+    }
+
+    let mut names = name.splitn(2, ' ');
+
+    let employee = Employee {
+        id: Uuid::new_v4().to_string(),
+        rid: format!("{:04}", db.5.len() + 1),
+        name: Name {
+            first: names.next().unwrap_or_default().to_string(),
+            middle: String::new(),
+            last: names.next().unwrap_or_default().to_string(),
+        },
+        auth: EmployeeAuth {
+            // Credentials cannot be exported, the
+            // employee must be issued new ones.
+            hash: String::new(),
+        },
+        contact: named_contact(name),
+        clock_history: vec![],
+        level: vec![],
+        account_type: AccountType::FrontLine,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+
+    db.5.push(employee.clone());
+
+    Some(employee)
+}
+
+/// Order references as payment processors quote them, which may
+/// or may not carry the `#` the order export gives them.
+fn normalise_reference(value: &str) -> String {
+    value.trim().trim_start_matches('#').to_lowercase()
+}
+
+/// Finds the transaction holding the order with the given reference.
+pub fn find_transaction<'a>(
+    reference: &str,
+    db: &'a mut InlineDatabase,
+) -> Option<&'a mut Transaction> {
+    let reference = normalise_reference(reference);
+
+    if reference.is_empty() {
+        return None;
+    }
+
+    db.2.iter_mut().find(|transaction| {
+        transaction
+            .products
+            .iter()
+            .any(|order| normalise_reference(&order.reference) == reference)
+    })
+}
+
+/// Finds the payment of a transaction which a processor settled, being a
+/// payment of the same method not yet given a processor token. One of the
+/// same amount is preferred, and a payment is added if there is none.
+pub fn resolve_payment<'a>(
+    transaction: &'a mut Transaction,
+    method: PaymentMethod,
+    amount: f32,
+    currency: &str,
+    source: &str,
+) -> &'a mut Payment {
+    let open: Vec<usize> = (0..transaction.payment.len())
+        .filter(|index| transaction.payment[*index].processor.token.is_empty())
+        .collect();
+
+    let same_method = |index: &&usize| {
+        transaction.payment[**index].payment_method.to_string() == method.to_string()
+    };
+
+    let index = open
+        .iter()
+        .filter(same_method)
+        .find(|index| (transaction.payment[**index].amount.quantity - amount).abs() < 0.01)
+        .or_else(|| open.iter().find(same_method))
+        .copied();
+
+    match index {
+        Some(index) => &mut transaction.payment[index],
+        None => add_payment(transaction, method, amount, currency, source),
+    }
+}
+
+/// Adds a payment a processor made to a transaction, without settling any
+/// of its payments, for attempts which never paid for it.
+pub fn add_payment<'a>(
+    transaction: &'a mut Transaction,
+    method: PaymentMethod,
+    amount: f32,
+    currency: &str,
+    source: &str,
+) -> &'a mut Payment {
+    transaction.payment.push(Payment {
+        id: Uuid::new_v4().to_string(),
+        payment_method: method,
+        fulfillment_date: Utc::now(),
+        amount: Price {
+            quantity: amount,
+            currency: currency.to_string(),
+        },
+        processing_fee: Price {
+            quantity: 0.0,
+            currency: currency.to_string(),
+        },
+        status: PaymentStatus::Complete(Processable::Anonymous(source.to_string())),
+        processor: PaymentProcessor::anonymous(source.to_string()),
+        order_ids: vec![],
+        delay_action: PaymentAction::Complete,
+        delay_duration: String::new(),
+    });
+
+    transaction.payment.last_mut().unwrap()
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+use open_stock::ProductVisibility;
+
+#[test]
+fn child_asins_become_variants_of_their_parent() {
+    let db = common::parse(
+        "amazon",
+        "amazon_import",
+        &[("all_listings_report.txt", ParseType::Product)],
+    );
+    assert_eq!(db.0.len(), 3);
+
+    let sock = &db.0[0];
+    assert_eq!(sock.name, "Trail Running Sock");
+    assert_eq!(sock.sku, "SOCK-TR");
+    assert_eq!(sock.identification.article_code, "B0PARENT01");
+
+    let names: Vec<&str> = sock
+        .variants
+        .iter()
+        .map(|variant| variant.name.as_str())
+        .collect();
+    assert_eq!(names, ["Medium Black", "Large Black"]);
+
+    let medium = &sock.variants[0];
+    assert_eq!(medium.identification.sku, "SOCK-TR-M-BLK");
+    assert_eq!(medium.identification.article_code, "B0CHILD001");
+    assert_eq!(medium.identification.ean, "0012345678905");
+    assert_eq!(medium.retail_price, 12.99);
+    assert_eq!(medium.stock[0].quantity.quantity_sellable, 35.0);
+    assert_eq!(sock.variants[1].identification.article_code, "B0CHILD002");
+}
+
+#[test]
+fn listings_are_stocked_by_who_fulfils_them() {
+    let db = common::parse(
+        "amazon",
+        "amazon_import",
+        &[("all_listings_report.txt", ParseType::Product)],
+    );
+    assert_eq!(db.3.len(), 2);
+
+    let bottle =
+        db.0.iter()
+            .find(|product| product.sku == "BTL-750")
+            .unwrap();
+    assert_eq!(
+        bottle.variants[0].stock[0].store.contact.name,
+        "Fulfilled by Amazon"
+    );
+
+    let lantern = db.0.iter().find(|product| product.sku == "LANT-U").unwrap();
+    assert_eq!(lantern.variants[0].stock[0].store.contact.name, "Amazon");
+    assert_eq!(
+        lantern.specifications,
+        [("Condition".to_string(), "Used - Good".to_string())]
+    );
+    assert!(matches!(lantern.visible, ProductVisibility::AlwaysHidden));
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use odm_migration_utility::{
+    resolve_kiosk, resolve_store, InlineDatabase, ParseType, SpecialPricing,
+};
+use open_stock::PaymentMethod;
+
+#[test]
+fn sku_rows_become_variants_of_their_product() {
+    let db = common::parse(
+        "bigcommerce",
+        "bigcommerce_import",
+        &[("products-2023-05-07.csv", ParseType::Product)],
+    );
+    assert_eq!(db.0.len(), 2);
+
+    let cap =
+        db.0.iter()
+            .find(|product| product.name == "Trail Cap")
+            .unwrap();
+    assert_eq!(cap.variants.len(), 2);
+
+    let small = &cap.variants[0];
+    assert_eq!(small.name, "S/M Dark Blue");
+    assert_eq!(small.retail_price, 29.0);
+    assert_eq!(small.identification.ean, "4006381333931");
+    assert_eq!(small.stock[0].quantity.quantity_sellable, 14.0);
+
+    // The price rule adds 3.00 to the larger size.
+    assert_eq!(cap.variants[1].retail_price, 32.0);
+
+    // Weights are exported in pounds unless the store is set otherwise.
+    let weight = small.stock_information.weight.parse::<f64>().unwrap();
+    assert!((weight - 0.1 * 0.45359237).abs() < 1e-9);
+
+    let voucher =
+        db.0.iter()
+            .find(|product| product.name == "Gift Voucher PDF")
+            .unwrap();
+    assert!(voucher.variants[0].stock_information.non_diminishing);
+    assert!(!voucher.variants[0].stock_information.shippable);
+}
+
+#[test]
+fn orders_are_joined_to_customers_and_variants() {
+    let db = common::parse(
+        "bigcommerce",
+        "bigcommerce_import",
+        &[
+            ("customers-2023-05-07.csv", ParseType::Customer),
+            ("products-2023-05-07.csv", ParseType::Product),
+            ("orders-2023-05-07.csv", ParseType::Transaction),
+        ],
+    );
+
+    let cafe =
+        db.1.iter()
+            .find(|customer| customer.name == "Harbour Cafe")
+            .unwrap();
+    assert!(SpecialPricing::from_field(&cafe.special_pricing).tax_exempt);
+
+    let lena =
+        db.1.iter()
+            .find(|customer| customer.name == "Lena Park")
+            .unwrap();
+    let order =
+        db.2.iter()
+            .find(|transaction| transaction.products[0].reference == "100")
+            .unwrap();
+
+    assert_eq!(order.customer.customer_id, lena.id);
+    assert_eq!(
+        order.products[0]
+            .products
+            .iter()
+            .map(|purchase| purchase.product_code.as_str())
+            .collect::<Vec<_>>(),
+        vec!["CAP-SM-BLU", "CAP-LXL-BLU"]
+    );
+
+    // Store credit is redeemed before the card is charged the rest.
+    assert!(matches!(
+        &order.payment[0].payment_method,
+        PaymentMethod::Other(method) if method == "STORE_CREDIT"
+    ));
+    assert!((order.payment[0].amount.quantity - 10.0).abs() < 1e-4);
+    assert!(matches!(
+        order.payment[1].payment_method,
+        PaymentMethod::Card
+    ));
+    assert!((order.payment[1].amount.quantity - 61.0).abs() < 1e-4);
+}
+
+#[test]
+fn stock_and_orders_are_held_by_the_warehouse_whatever_stores_came_first() {
+    let mut db: InlineDatabase = Default::default();
+    let other = resolve_store("Pop-up", &mut db).unwrap();
+    resolve_kiosk("Pop-up Register", &other, &mut db);
+
+    common::read(
+        "bigcommerce",
+        "bigcommerce_import",
+        &[
+            ("products-2023-05-07.csv", ParseType::Product),
+            ("orders-2023-05-07.csv", ParseType::Transaction),
+        ],
+        &mut db,
+    );
+
+    let warehouse =
+        db.3.iter()
+            .find(|store| store.name == "BigCommerce")
+            .unwrap();
+    assert_ne!(warehouse.id, other.id);
+    assert_eq!(db.0[0].variants[0].stock[0].store.store_id, warehouse.id);
+
+    for order in &db.2 {
+        assert_eq!(order.products[0].origin.store_id, warehouse.id);
+        assert!(order.kiosk.is_empty());
+    }
+}
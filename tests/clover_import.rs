@@ -0,0 +1,79 @@
+mod common;
+
+use odm_migration_utility::{InlineDatabase, ParseType};
+use open_stock::PaymentMethod;
+
+fn parse() -> InlineDatabase {
+    common::parse(
+        "clover",
+        "clover_import",
+        &[
+            ("customers.csv", ParseType::Customer),
+            ("inventory_modifier_groups.csv", ParseType::ProductModifier),
+            ("inventory_items.csv", ParseType::Product),
+            ("inventory_categories.csv", ParseType::ProductCategory),
+            ("orders.csv", ParseType::Transaction),
+        ],
+    )
+}
+
+#[test]
+fn items_are_tagged_with_their_categories() {
+    let db = parse();
+    assert_eq!(db.0.len(), 5);
+
+    let tea =
+        db.0.iter()
+            .find(|product| product.name == "Loose Leaf Tea")
+            .unwrap();
+    assert_eq!(tea.tags, vec!["Tea", "Retail"]);
+    assert_eq!(tea.variants[0].stock[0].quantity.quantity_sellable, 42.5);
+    assert!(tea
+        .specifications
+        .contains(&("Price Unit".to_string(), "100g".to_string())));
+
+    // Items without a quantity are not stocked.
+    let latte = db.0.iter().find(|product| product.name == "Latte").unwrap();
+    assert_eq!(latte.tags, vec!["Coffee"]);
+    assert!(!latte.variants[0].stock_tracking);
+    assert!(latte.variants[0].stock.is_empty());
+}
+
+#[test]
+fn order_rows_are_grouped_and_joined_to_customers_and_devices() {
+    let db = parse();
+    assert_eq!(db.2.len(), 3);
+
+    let ana =
+        db.1.iter()
+            .find(|customer| customer.name == "Ana Lopez")
+            .unwrap();
+    let order = &db.2[0];
+
+    assert_eq!(order.customer.customer_id, ana.id);
+    assert_eq!(
+        order.products[0]
+            .products
+            .iter()
+            .map(|purchase| (purchase.product_code.as_str(), purchase.quantity))
+            .collect::<Vec<_>>(),
+        vec![("LAT-01", 2.0), ("FW-01", 1.0)]
+    );
+    assert!(matches!(
+        order.payment[0].payment_method,
+        PaymentMethod::Card
+    ));
+    assert!((order.payment[0].amount.quantity - 14.1).abs() < 1e-4);
+
+    let kiosk = db.4.iter().find(|kiosk| kiosk.id == order.kiosk).unwrap();
+    assert_eq!(kiosk.name, "Front Counter");
+
+    let employee =
+        db.5.iter()
+            .find(|employee| employee.id == order.salesperson)
+            .unwrap();
+    assert_eq!(employee.contact.name, "Kate Hill");
+
+    // The open order has not been paid.
+    assert!(db.2[2].payment.is_empty());
+}
@@ -0,0 +1,68 @@
+//! Fixture readers shared by the tests of each format.
+
+// Each test crate includes this module, using only some of it.
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use odm_migration_utility::{open_reader, FormatRegistry, InlineDatabase, ParseType};
+use open_stock::{Product, Transaction};
+
+/// Reads the given exports of `examples/<directory>` with the named
+/// built-in format, in order, into `db`.
+pub fn read(name: &str, directory: &str, files: &[(&str, ParseType)], db: &mut InlineDatabase) {
+    let format = FormatRegistry::builtin()
+        .get(name)
+        .unwrap_or_else(|| panic!("{} is registered", name));
+
+    for (path, parse_type) in files {
+        // Exports are not all separated by commas, such as
+        // Amazon's listings reports or PrestaShop's exports.
+        let reader = open_reader(&Path::new("examples").join(directory).join(path))
+            .expect("fixture should exist");
+
+        format
+            .read(reader, *parse_type, db)
+            .expect("fixture should parse");
+    }
+}
+
+/// Reads the given exports of `examples/<directory>` into an empty database.
+pub fn parse(name: &str, directory: &str, files: &[(&str, ParseType)]) -> InlineDatabase {
+    let mut db: InlineDatabase = Default::default();
+    read(name, directory, files, &mut db);
+
+    db
+}
+
+/// Finds the product of the given name.
+pub fn product<'a>(db: &'a InlineDatabase, name: &str) -> &'a Product {
+    db.0.iter()
+        .find(|product| product.name == name)
+        .unwrap_or_else(|| panic!("expected product {}", name))
+}
+
+/// Finds the order, or receipt, referenced by its products.
+pub fn order<'a>(db: &'a InlineDatabase, reference: &str) -> &'a Transaction {
+    db.2.iter()
+        .find(|transaction| transaction.products[0].reference == reference)
+        .unwrap_or_else(|| panic!("expected order {}", reference))
+}
+
+/// Reads the PrestaShop orders of `examples/<directory>`, then the
+/// payments which settle them with the named payment processor's format.
+pub fn settle(processor: &str, directory: &str, payments: &str) -> InlineDatabase {
+    let mut db = parse(
+        "prestashop",
+        directory,
+        &[("orders.csv", ParseType::Transaction)],
+    );
+    read(
+        processor,
+        directory,
+        &[(payments, ParseType::TransactionItem)],
+        &mut db,
+    );
+
+    db
+}
@@ -0,0 +1,76 @@
+use csv::Reader;
+use odm_migration_utility::{
+    find_format, register_mapping_files, registry, InlineDatabase, ParseType,
+};
+use std::path::{Path, PathBuf};
+
+const DIRECTORY: &str = "examples/declarative_import";
+
+fn read(format: &str, path: &str, parse_type: ParseType, db: &mut InlineDatabase) {
+    find_format(format)
+        .unwrap_or_else(|| panic!("{} should be registered", format))
+        .read(
+            Reader::from_path(Path::new(DIRECTORY).join(path)).expect("fixture should exist"),
+            parse_type,
+            db,
+        )
+        .expect("fixture should parse");
+}
+
+#[test]
+fn mapping_files_register_formats_which_classify_and_read_their_exports() {
+    register_mapping_files(Path::new(DIRECTORY)).unwrap();
+
+    let classification = registry().read().unwrap().classify(
+        PathBuf::from("items.csv"),
+        "Item,Code,Size,Colour,Price (cents),Cost (cents),Stock,Barcode,Tags",
+    );
+    assert_eq!(classification.branding, "tinypos-items");
+    assert_eq!(classification.variant, ParseType::Product);
+    assert_eq!(classification.score, 0);
+
+    let mut db: InlineDatabase = Default::default();
+    read("tinypos-items", "items.csv", ParseType::Product, &mut db);
+    read(
+        "tinypos-customers",
+        "customers.csv",
+        ParseType::Customer,
+        &mut db,
+    );
+
+    assert_eq!(db.0.len(), 2);
+    assert_eq!(db.3.len(), 1);
+    assert_eq!(db.3[0].name, "tinypos-items");
+
+    let shirt = &db.0[0];
+    assert_eq!(shirt.name, "Linen Shirt");
+    assert_eq!(shirt.tags, ["shirts", "linen"]);
+
+    let names: Vec<&str> = shirt
+        .variants
+        .iter()
+        .map(|variant| variant.name.as_str())
+        .collect();
+    assert_eq!(names, ["S White", "M White", "L Black"]);
+
+    let small = &shirt.variants[0];
+    assert_eq!(small.variant_code, ["LS-S-WHT"]);
+    assert_eq!(small.barcode, "9300000000019");
+    assert!((small.retail_price - 45.0).abs() < 1e-4);
+    assert!((small.marginal_price - 18.0).abs() < 1e-4);
+    assert_eq!(small.stock[0].quantity.quantity_sellable, 4.0);
+    assert_eq!(shirt.variants[1].stock[0].quantity.quantity_sellable, 0.0);
+
+    let jane = &db.1[0];
+    assert_eq!(jane.name, "Jane Doe");
+    assert_eq!(jane.contact.email.full, "jane.doe@example.com");
+    assert!(jane.accepts_marketing);
+
+    let notes: Vec<&str> = jane
+        .customer_notes
+        .iter()
+        .map(|note| note.message.as_str())
+        .collect();
+    assert_eq!(notes, ["Prefers email", "Allergic to wool"]);
+    assert!(db.1[1].customer_notes.is_empty());
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+
+#[test]
+fn variation_rows_become_variants_of_their_listing() {
+    let db = common::parse(
+        "ebay",
+        "ebay_import",
+        &[("file_exchange.csv", ParseType::Product)],
+    );
+    assert_eq!(db.0.len(), 3);
+
+    let jacket = &db.0[0];
+    assert_eq!(jacket.name, "Vintage Denim Jacket");
+    assert_eq!(jacket.images.len(), 2);
+
+    let names: Vec<&str> = jacket
+        .variants
+        .iter()
+        .map(|variant| variant.name.as_str())
+        .collect();
+    assert_eq!(names, ["S Blue", "M Blue", "L Blue"]);
+
+    let medium = &jacket.variants[1];
+    assert_eq!(medium.identification.sku, "JKT-DEN-M");
+    assert_eq!(medium.identification.article_code, "204512345678");
+    assert_eq!(medium.identification.ean, "0012345678905");
+    assert_eq!(medium.retail_price, 45.0);
+    assert_eq!(medium.stock[0].quantity.quantity_sellable, 5.0);
+    assert_eq!(jacket.variants[2].retail_price, 48.0);
+}
+
+#[test]
+fn listings_are_stocked_at_the_seller_store() {
+    let db = common::parse(
+        "ebay",
+        "ebay_import",
+        &[("file_exchange.csv", ParseType::Product)],
+    );
+    assert_eq!(db.3.len(), 1);
+    assert_eq!(db.3[0].name, "eBay");
+
+    let record =
+        db.0.iter()
+            .find(|product| product.name == "Signed Vinyl Record")
+            .unwrap();
+    assert_eq!(record.variants[0].stock[0].store.store_id, db.3[0].id);
+    assert!(record
+        .specifications
+        .contains(&("Listing Format".to_string(), "Auction".to_string())));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+use open_stock::PaymentMethod;
+
+#[test]
+fn variations_are_joined_to_their_product_by_sku() {
+    let db = common::parse(
+        "ecwid",
+        "ecwid_import",
+        &[("catalog.csv", ParseType::Product)],
+    );
+    assert_eq!(db.0.len(), 3);
+    assert_eq!(db.3.len(), 1);
+
+    let tee =
+        db.0.iter()
+            .find(|product| product.name == "Classic Tee")
+            .unwrap();
+    let names: Vec<&str> = tee
+        .variants
+        .iter()
+        .map(|variant| variant.name.as_str())
+        .collect();
+    assert_eq!(names, ["S Red", "M Blue"]);
+
+    let blue = &tee.variants[1];
+    assert_eq!(blue.identification.sku, "TEE-01-M-BLUE");
+    assert_eq!(blue.retail_price, 22.0);
+    assert_eq!(blue.stock_information.weight, "0.2");
+    assert_eq!(blue.stock[0].store.contact.name, "Ecwid");
+    assert_eq!(blue.stock[0].quantity.quantity_sellable, 4.0);
+}
+
+#[test]
+fn options_without_variations_become_variants_with_their_markup() {
+    let db = common::parse(
+        "ecwid",
+        "ecwid_import",
+        &[("catalog.csv", ParseType::Product)],
+    );
+
+    let mug =
+        db.0.iter()
+            .find(|product| product.name == "Enamel Mug")
+            .unwrap();
+    let names: Vec<&str> = mug
+        .variants
+        .iter()
+        .map(|variant| variant.name.as_str())
+        .collect();
+    assert_eq!(names, ["Matte", "Gloss"]);
+    assert!((mug.variants[1].retail_price - 13.2).abs() < 1e-4);
+    assert_eq!(mug.variants[0].stock_information.weight, "0.3");
+}
+
+#[test]
+fn orders_group_their_items_and_belong_to_their_customer() {
+    let db = common::parse(
+        "ecwid",
+        "ecwid_import",
+        &[
+            ("customers.csv", ParseType::Customer),
+            ("orders.csv", ParseType::Transaction),
+        ],
+    );
+    assert_eq!(db.2.len(), 3);
+
+    let jane =
+        db.1.iter()
+            .find(|customer| customer.contact.email.full == "jane@example.com")
+            .unwrap();
+    let first =
+        db.2.iter()
+            .find(|transaction| transaction.products[0].reference == "1001")
+            .unwrap();
+
+    assert_eq!(first.customer.customer_id, jane.id);
+    assert_eq!(first.products[0].products.len(), 2);
+    assert!(matches!(
+        first.payment[0].payment_method,
+        PaymentMethod::Card
+    ));
+    assert_eq!(first.payment[0].amount.quantity, 52.0);
+
+    // Orders awaiting payment have nothing settled against them.
+    let awaiting =
+        db.2.iter()
+            .find(|transaction| transaction.products[0].reference == "1003")
+            .unwrap();
+    assert!(awaiting.payment.is_empty());
+}
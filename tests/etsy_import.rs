@@ -0,0 +1,96 @@
+mod common;
+
+use odm_migration_utility::{etsy::LISTING_QUANTITY, ParseType};
+use open_stock::{DiscountValue, PaymentMethod};
+
+#[test]
+fn listings_with_variations_keep_their_quantity_on_the_product() {
+    let db = common::parse(
+        "etsy",
+        "etsy_import",
+        &[("EtsyListingsDownload.csv", ParseType::Product)],
+    );
+
+    let mug =
+        db.0.iter()
+            .find(|product| product.name == "Hand Thrown Mug")
+            .unwrap();
+    assert_eq!(
+        mug.variants
+            .iter()
+            .map(|variant| variant.variant_code[0].as_str())
+            .collect::<Vec<_>>(),
+        vec!["MUG-SPK-L", "MUG-SPK-R", "MUG-BLU-L", "MUG-BLU-R"]
+    );
+    assert!(mug
+        .specifications
+        .contains(&(LISTING_QUANTITY.to_string(), "12".to_string())));
+
+    // The listing's 12 are not divided between its variants, nor placed on the first.
+    for variant in &mug.variants {
+        assert!(!variant.stock_tracking);
+        assert!(variant.stock.is_empty());
+    }
+
+    let towel =
+        db.0.iter()
+            .find(|product| product.name == "Linen Tea Towel")
+            .unwrap();
+    assert!(towel.variants[0].stock_tracking);
+    assert_eq!(towel.variants[0].stock[0].store.contact.name, "Etsy");
+    assert_eq!(towel.variants[0].stock[0].quantity.quantity_sellable, 30.0);
+
+    // Without a SKU for each variant, they are known by the listing's.
+    let coaster =
+        db.0.iter()
+            .find(|product| product.name == "Oak Coaster")
+            .unwrap();
+    assert_eq!(
+        coaster
+            .variants
+            .iter()
+            .map(|variant| (variant.name.as_str(), variant.variant_code[0].as_str()))
+            .collect::<Vec<_>>(),
+        vec![("Oiled", "COASTER"), ("Waxed", "COASTER")]
+    );
+}
+
+#[test]
+fn order_items_are_joined_to_their_orders_by_id() {
+    let db = common::parse(
+        "etsy",
+        "etsy_import",
+        &[
+            ("EtsyListingsDownload.csv", ParseType::Product),
+            ("EtsySoldOrders.csv", ParseType::Transaction),
+            ("EtsySoldOrderItems.csv", ParseType::TransactionItem),
+        ],
+    );
+
+    // The item of order 2899999 has no order to join.
+    assert_eq!(db.2.len(), 2);
+
+    let order = &db.2[0];
+    assert_eq!(order.products[0].reference, "2890001");
+    assert_eq!(
+        order.products[0]
+            .products
+            .iter()
+            .map(|purchase| (purchase.product_code.as_str(), purchase.quantity))
+            .collect::<Vec<_>>(),
+        vec![("MUG-BLU-L", 2.0), ("TOWEL-01", 1.0)]
+    );
+    assert!(matches!(
+        order.products[0].discount,
+        DiscountValue::Absolute(10)
+    ));
+    assert_eq!(
+        order.products[0].order_notes[0].message,
+        "Coupon SPRING10 redeemed"
+    );
+    assert!(matches!(
+        order.payment[0].payment_method,
+        PaymentMethod::Card
+    ));
+    assert!((order.payment[0].amount.quantity - 98.0).abs() < 1e-4);
+}
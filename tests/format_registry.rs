@@ -0,0 +1,83 @@
+use csv::Reader;
+use odm_migration_utility::{
+    named_contact, Format, FormatRegistry, InlineDatabase, ParseFailure, ParseType,
+};
+use open_stock::Store;
+use std::{fs::File, path::PathBuf};
+
+/// A proprietary format, as a library consumer would declare it.
+struct Branches;
+
+impl Format for Branches {
+    fn name(&self) -> &str {
+        "branches"
+    }
+
+    fn header(&self, parse_type: ParseType) -> Option<String> {
+        match parse_type {
+            ParseType::Store => Some("Branch Code,Branch Name".to_string()),
+            _ => None,
+        }
+    }
+
+    fn read(
+        &self,
+        mut reader: Reader<File>,
+        _parse_type: ParseType,
+        db: &mut InlineDatabase,
+    ) -> Result<(), ParseFailure> {
+        for record in reader.records() {
+            let record = record.map_err(|error| ParseFailure::ReadFailure(error.to_string()))?;
+
+            db.3.push(Store {
+                id: record[0].to_string(),
+                name: record[1].to_string(),
+                contact: named_contact(&record[1]),
+                code: record[0].to_string(),
+                created_at: Default::default(),
+                updated_at: Default::default(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn registered_formats_classify_and_read_their_exports() {
+    let mut registry = FormatRegistry::builtin();
+    registry.register(Branches);
+
+    let classification =
+        registry.classify(PathBuf::from("branches.csv"), "Branch Code,Branch Name");
+
+    assert_eq!(classification.branding, "branches");
+    assert_eq!(classification.variant, ParseType::Store);
+    assert_eq!(classification.score, 0);
+
+    let path = std::env::temp_dir().join("odm_registry_branches.csv");
+    std::fs::write(&path, "Branch Code,Branch Name\n001,Fremantle\n002,Perth\n").unwrap();
+
+    let mut db: InlineDatabase = Default::default();
+
+    registry
+        .get("branches")
+        .expect("branches is registered")
+        .read(Reader::from_path(&path).unwrap(), ParseType::Store, &mut db)
+        .expect("export should parse");
+
+    assert_eq!(db.3.len(), 2);
+    assert_eq!(db.3[1].name, "Perth");
+}
+
+#[test]
+fn formats_of_the_same_name_are_replaced() {
+    let mut registry = FormatRegistry::builtin();
+    let count = registry.formats().count();
+
+    registry.register(Branches);
+    registry.register(Branches);
+
+    assert_eq!(registry.formats().count(), count + 1);
+    assert!(registry.get("shopify").is_some());
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use odm_migration_utility::{store_prices, ParseType, StorePrice};
+
+#[test]
+fn handles_group_variants_stocked_at_each_outlet() {
+    let db = common::parse(
+        "hike",
+        "hike_import",
+        &[("products.csv", ParseType::Product)],
+    );
+    assert_eq!(db.0.len(), 3);
+
+    let runner = &db.0[0];
+    assert_eq!(runner.name, "Trail Runner");
+    assert_eq!(runner.tags, vec!["shoes", "running"]);
+    assert_eq!(
+        runner
+            .variants
+            .iter()
+            .map(|variant| (variant.name.as_str(), variant.retail_price))
+            .collect::<Vec<_>>(),
+        vec![("9", 179.95), ("10", 179.95)]
+    );
+    assert_eq!(runner.variants[0].identification.ean, "9300000000019");
+    assert_eq!(
+        runner.variants[0]
+            .stock
+            .iter()
+            .map(|stock| (
+                stock.store.contact.name.as_str(),
+                stock.quantity.quantity_sellable
+            ))
+            .collect::<Vec<_>>(),
+        vec![("Sydney CBD", 5.0), ("Newtown", 2.0)]
+    );
+
+    // Services are not stocked.
+    assert!(!db.0[2].variants[0].stock_tracking);
+}
+
+#[test]
+fn outlet_prices_are_kept_for_each_variant_where_they_differ() {
+    let db = common::parse(
+        "hike",
+        "hike_import",
+        &[("products.csv", ParseType::Product)],
+    );
+    let newtown = db.3.iter().find(|store| store.name == "Newtown").unwrap();
+
+    assert_eq!(
+        store_prices(&db.0[0]),
+        ["TR-9", "TR-10"]
+            .iter()
+            .map(|code| StorePrice {
+                variant_code: code.to_string(),
+                store_code: newtown.code.clone(),
+                price: 169.95,
+            })
+            .collect::<Vec<_>>()
+    );
+
+    // The socks sell at the retail price everywhere.
+    assert!(store_prices(&db.0[1]).is_empty());
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use odm_migration_utility::{store_prices, ParseType, StorePrice};
+
+#[test]
+fn variants_are_stocked_at_each_store() {
+    let db = common::parse(
+        "loyverse",
+        "loyverse_import",
+        &[("export_items.csv", ParseType::Product)],
+    );
+    let shirt = common::product(&db, "Linen Shirt");
+
+    assert_eq!(
+        shirt
+            .variants
+            .iter()
+            .map(|variant| variant.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["S White", "M White", "M Sand"]
+    );
+    assert_eq!(shirt.variants[0].identification.ean, "5012345678900");
+    assert_eq!(
+        shirt.variants[2]
+            .stock
+            .iter()
+            .map(|stock| (
+                stock.store.contact.name.as_str(),
+                stock.quantity.quantity_sellable
+            ))
+            .collect::<Vec<_>>(),
+        vec![("Old Town", 2.0), ("Harbour", 5.0)]
+    );
+
+    // Items sold by weight can be bought in fractions.
+    assert_eq!(
+        common::product(&db, "Marinated Olives").variants[0].buy_min,
+        0.0
+    );
+}
+
+#[test]
+fn store_prices_are_kept_for_each_variant_where_they_differ() {
+    let db = common::parse(
+        "loyverse",
+        "loyverse_import",
+        &[("export_items.csv", ParseType::Product)],
+    );
+    let harbour = db.3.iter().find(|store| store.name == "Harbour").unwrap();
+
+    let shirt = common::product(&db, "Linen Shirt");
+    assert_eq!(shirt.variants[0].retail_price, 45.0);
+    assert_eq!(
+        store_prices(shirt),
+        ["10001", "10002", "10003"]
+            .iter()
+            .map(|code| StorePrice {
+                variant_code: code.to_string(),
+                store_code: harbour.code.clone(),
+                price: 49.0,
+            })
+            .collect::<Vec<_>>()
+    );
+
+    // Without a default price, the first store's is the retail price.
+    let olives = common::product(&db, "Marinated Olives");
+    assert_eq!(olives.variants[0].retail_price, 22.0);
+    assert_eq!(
+        store_prices(olives),
+        vec![StorePrice {
+            variant_code: "10010".to_string(),
+            store_code: harbour.code.clone(),
+            price: 24.0,
+        }]
+    );
+}
+
+#[test]
+fn composite_items_keep_the_quantity_of_each_included_item() {
+    let db = common::parse(
+        "loyverse",
+        "loyverse_import",
+        &[("export_items.csv", ParseType::Product)],
+    );
+    let gift_box = common::product(&db, "Gift Box");
+
+    assert_eq!(gift_box.variants.len(), 1);
+    assert!(gift_box.specifications.contains(&(
+        "Included Items".to_string(),
+        "0.25 x 10010, 1 x 10001".to_string()
+    )));
+}
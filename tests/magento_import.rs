@@ -0,0 +1,66 @@
+mod common;
+
+use odm_migration_utility::{ParseType, SpecialPricing};
+
+#[test]
+fn configurable_products_are_joined_to_their_simple_variants() {
+    let db = common::parse(
+        "magento",
+        "magento_import",
+        &[("catalog_product.csv", ParseType::Product)],
+    );
+
+    // The bundle cannot be imported, and the simple variants are not products of their own.
+    assert_eq!(
+        db.0.iter()
+            .map(|product| product.name.as_str())
+            .collect::<Vec<_>>(),
+        vec!["Chaz Hoodie", "Sprite Yoga Strap", "Training Guide"]
+    );
+
+    let hoodie = &db.0[0];
+    assert_eq!(
+        hoodie
+            .variants
+            .iter()
+            .map(|variant| (variant.name.as_str(), variant.retail_price))
+            .collect::<Vec<_>>(),
+        vec![("S Black", 52.0), ("M Black", 54.0), ("M Gray", 52.0)]
+    );
+    assert_eq!(hoodie.variants[0].identification.ean, "4006381333931");
+    assert_eq!(hoodie.variants[0].stock[0].quantity.quantity_sellable, 12.0);
+    assert_eq!(hoodie.tags, vec!["Tops", "Sale"]);
+
+    // Weights are exported in pounds unless the store is set otherwise.
+    let strap = &db.0[1].variants[0];
+    let weight = strap.stock_information.weight.parse::<f64>().unwrap();
+    assert!((weight - 0.3 * 0.45359237).abs() < 1e-9);
+    assert!(strap.stock_information.discontinued);
+
+    assert!(!db.0[2].variants[0].stock_information.shippable);
+}
+
+#[test]
+fn customers_are_merged_across_websites_with_their_addresses() {
+    let db = common::parse(
+        "magento",
+        "magento_import",
+        &[
+            ("customer_main.csv", ParseType::Customer),
+            ("customer_address.csv", ParseType::CustomerAddress),
+        ],
+    );
+    assert_eq!(db.1.len(), 2);
+
+    let roni = &db.1[0];
+    assert_eq!(roni.name, "Veronica Costello");
+    assert_eq!(roni.contact.address.city, "Calder, Michigan");
+    assert!(roni
+        .customer_notes
+        .iter()
+        .any(|note| note.message == "Address: 1 Woodward Ave, Detroit, Michigan, US"));
+
+    let sam = &db.1[1];
+    assert!(SpecialPricing::from_field(&sam.special_pricing).tax_exempt);
+    assert_eq!(sam.contact.address.country, "GB");
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use odm_migration_utility::{InlineDatabase, ParseType};
+use open_stock::{PaymentMethod, TransactionType};
+
+fn parse_all() -> InlineDatabase {
+    common::parse(
+        "odoo",
+        "odoo_import",
+        &[
+            ("product_template.csv", ParseType::Product),
+            ("product_product.csv", ParseType::ProductModifier),
+            ("res_partner.csv", ParseType::Customer),
+            ("pos_order.csv", ParseType::Transaction),
+            ("pos_order_line.csv", ParseType::TransactionItem),
+        ],
+    )
+}
+
+#[test]
+fn variants_are_joined_to_their_template_by_external_id() {
+    let db = common::parse(
+        "odoo",
+        "odoo_import",
+        &[
+            ("product_template.csv", ParseType::Product),
+            ("product_product.csv", ParseType::ProductModifier),
+        ],
+    );
+    assert_eq!(db.0.len(), 4);
+
+    let organizer =
+        db.0.iter()
+            .find(|product| product.name == "Desk Organizer")
+            .unwrap();
+    assert_eq!(
+        organizer.specifications,
+        [(
+            "External ID".to_string(),
+            "__export__.product_template_5".to_string()
+        )]
+    );
+
+    let names: Vec<&str> = organizer
+        .variants
+        .iter()
+        .map(|variant| variant.name.as_str())
+        .collect();
+    assert_eq!(names, ["S Steel", "M Steel", "M Wood"]);
+
+    let small = &organizer.variants[0];
+    assert!(small
+        .variant_code
+        .contains(&"__export__.product_product_12".to_string()));
+    assert_eq!(small.stock[0].store.contact.name, "Odoo");
+    assert_eq!(small.stock[0].quantity.quantity_sellable, 40.0);
+    assert_eq!(small.stock_information.weight, "0.45");
+
+    // Archived variants are kept, but discontinued.
+    assert!(organizer.variants[2].stock_information.discontinued);
+
+    // A variant of a template missing from the export is left out.
+    assert!(db
+        .0
+        .iter()
+        .all(|product| product.name != "Missing Template"));
+}
+
+#[test]
+fn order_lines_are_joined_to_their_order_and_product_by_external_id() {
+    let db = parse_all();
+    assert_eq!(db.2.len(), 4);
+
+    let deco =
+        db.1.iter()
+            .find(|customer| customer.name == "Deco Addict")
+            .unwrap();
+    let first =
+        db.2.iter()
+            .find(|transaction| transaction.products[0].reference == "Shop/0001")
+            .unwrap();
+
+    assert_eq!(first.customer.customer_id, deco.id);
+    assert!(matches!(
+        first.payment[0].payment_method,
+        PaymentMethod::Cash
+    ));
+    assert_eq!(first.payment[0].amount.quantity, 7.1);
+
+    let codes: Vec<&str> = first.products[0]
+        .products
+        .iter()
+        .map(|purchase| purchase.product_code.as_str())
+        .collect();
+    assert_eq!(codes, ["FURN_0001_S_STEEL", "CONS_0001"]);
+}
+
+#[test]
+fn refunds_are_received_at_their_point_of_sale() {
+    let db = parse_all();
+
+    let refund =
+        db.2.iter()
+            .find(|transaction| transaction.products[0].reference == "Bar/0001")
+            .unwrap();
+    assert!(matches!(refund.transaction_type, TransactionType::In));
+    assert_eq!(
+        refund.kiosk,
+        db.4.iter().find(|kiosk| kiosk.name == "Bar").unwrap().id
+    );
+    assert_eq!(
+        refund.products[0].products[0].product_code,
+        "FURN_0001_S_STEEL"
+    );
+}
@@ -0,0 +1,52 @@
+use odm_migration_utility::{apportion_payment, shopify::PAYMENT_METHODS, split_payment_methods};
+use open_stock::PaymentMethod;
+
+fn amounts(tenders: &[(PaymentMethod, f32)]) -> Vec<(String, f32)> {
+    tenders
+        .iter()
+        .map(|(method, amount)| (method.to_string(), (amount * 100.0).round() / 100.0))
+        .collect()
+}
+
+#[test]
+fn tenders_are_split_and_mapped() {
+    let splits = split_payment_methods("Shopify Payments + Gift Card", &PAYMENT_METHODS);
+
+    assert_eq!(splits.len(), 2);
+    assert!(matches!(splits[0].method, PaymentMethod::Card));
+    assert!(matches!(
+        &splits[1].method,
+        PaymentMethod::Other(method) if method == "GIFT_CARD"
+    ));
+    assert!(splits.iter().all(|split| split.amount.is_none()));
+
+    let unknown = split_payment_methods("Layby", &PAYMENT_METHODS);
+    assert!(matches!(
+        &unknown[0].method,
+        PaymentMethod::Other(method) if method == "Layby"
+    ));
+}
+
+#[test]
+fn given_amounts_are_kept_and_the_rest_goes_to_the_remaining_tender() {
+    let splits = split_payment_methods("Gift Card (10.00) + Shopify Payments", &PAYMENT_METHODS);
+    let tenders = apportion_payment(&splits, 30.0).expect("payment should divide");
+
+    assert_eq!(
+        amounts(&tenders),
+        vec![
+            (
+                PaymentMethod::Other("GIFT_CARD".to_string()).to_string(),
+                10.0
+            ),
+            (PaymentMethod::Card.to_string(), 20.0),
+        ]
+    );
+}
+
+#[test]
+fn tenders_without_amounts_cannot_be_divided() {
+    let splits = split_payment_methods("Gift Card + Cash", &PAYMENT_METHODS);
+
+    assert!(apportion_payment(&splits, 50.0).is_none());
+}
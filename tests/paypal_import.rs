@@ -0,0 +1,52 @@
+mod common;
+
+use open_stock::{PaymentAction, PaymentStatus};
+
+#[test]
+fn payments_settle_their_order_by_invoice_number() {
+    let db = common::settle("paypal", "paypal_import", "activity.csv");
+    let order = common::order(&db, "QWPLMNBVC");
+
+    let paid = order
+        .payment
+        .iter()
+        .find(|payment| payment.processor.token == "5TY05013RG002845M")
+        .unwrap();
+    assert!(matches!(paid.status, PaymentStatus::Complete(_)));
+    assert_eq!(paid.amount.quantity, 1250.0);
+    assert_eq!(paid.processing_fee.quantity, 43.4);
+
+    // The denied attempt before it is kept, but settles nothing.
+    let denied = order
+        .payment
+        .iter()
+        .find(|payment| payment.processor.token == "3RS94012QF001734L")
+        .unwrap();
+    assert!(matches!(denied.status, PaymentStatus::Failed(_)));
+    assert!(matches!(denied.delay_action, PaymentAction::Cancel));
+    assert!(order
+        .payment
+        .iter()
+        .all(|payment| !payment.processor.token.is_empty()));
+}
+
+#[test]
+fn refunds_are_noted_on_the_order_of_the_payment_they_return() {
+    let db = common::settle("paypal", "paypal_import", "activity.csv");
+    let order = common::order(&db, "RTYUIOPAS");
+
+    assert!(order
+        .payment
+        .iter()
+        .any(|payment| payment.processor.token == "8AB12345CD678901E"));
+    assert!(order
+        .order_notes
+        .iter()
+        .any(|note| note.message == "Refunded 60.00 EUR in PayPal transaction 9CD23456EF789012G"));
+
+    // Withdrawals belong to no order.
+    assert!(db.2.iter().all(|transaction| transaction
+        .payment
+        .iter()
+        .all(|payment| payment.processor.token != "1EF34567GH890123I")));
+}
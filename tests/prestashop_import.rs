@@ -0,0 +1,84 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+
+#[test]
+fn combinations_are_joined_to_their_product_by_reference() {
+    let db = common::parse(
+        "prestashop",
+        "prestashop_import",
+        &[
+            ("products.csv", ParseType::Product),
+            ("combinations.csv", ParseType::ProductModifier),
+        ],
+    );
+    assert_eq!(db.0.len(), 3);
+
+    let shirt =
+        db.0.iter()
+            .find(|product| product.name == "Hummingbird printed t-shirt")
+            .unwrap();
+    let names: Vec<&str> = shirt
+        .variants
+        .iter()
+        .map(|variant| variant.name.as_str())
+        .collect();
+    assert_eq!(names, ["S White", "M White", "L Black"]);
+
+    let black = &shirt.variants[2];
+    assert_eq!(black.identification.sku, "demo_1_l_black");
+    assert!((black.retail_price - 21.62).abs() < 1e-4);
+    assert_eq!(black.stock[0].store.contact.name, "PrestaShop");
+    assert_eq!(black.stock[0].quantity.quantity_sellable, 40.0);
+
+    // A combination of a product missing from the export is left out.
+    assert!(db
+        .0
+        .iter()
+        .flat_map(|product| &product.variants)
+        .all(|variant| variant.identification.sku != "demo_99_xl"));
+}
+
+#[test]
+fn weights_are_read_in_kilograms() {
+    let db = common::parse(
+        "prestashop",
+        "prestashop_import",
+        &[
+            ("products.csv", ParseType::Product),
+            ("combinations.csv", ParseType::ProductModifier),
+        ],
+    );
+
+    let shirt = &db.0[0];
+    assert_eq!(shirt.variants[0].stock_information.weight, "0.3");
+    // The combination's impact on weight is added to the product's.
+    assert_eq!(shirt.variants[2].stock_information.weight, "0.35");
+}
+
+#[test]
+fn orders_belong_to_their_customer_at_the_shop() {
+    let db = common::parse(
+        "prestashop",
+        "prestashop_import",
+        &[
+            ("customers.csv", ParseType::Customer),
+            ("addresses.csv", ParseType::CustomerAddress),
+            ("orders.csv", ParseType::Transaction),
+        ],
+    );
+    assert_eq!(db.3.len(), 1);
+
+    let john =
+        db.1.iter()
+            .find(|customer| customer.contact.email.full == "pub@prestashop.com")
+            .unwrap();
+    let delivered =
+        db.2.iter()
+            .find(|transaction| transaction.products[0].reference == "OHSATSERP")
+            .unwrap();
+
+    assert_eq!(delivered.customer.customer_id, john.id);
+    assert_eq!(delivered.products[0].origin.store_code, db.3[0].code);
+    assert!((delivered.payment[0].amount.quantity - 69.9).abs() < 1e-4);
+}
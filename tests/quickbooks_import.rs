@@ -0,0 +1,56 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+
+#[test]
+fn items_keep_their_accounts_in_their_stock_information() {
+    let db = common::parse(
+        "quickbooks",
+        "quickbooks_import",
+        &[("products_and_services.csv", ParseType::Product)],
+    );
+    assert_eq!(db.0.len(), 4);
+
+    let fountain = &db.0[0];
+    assert_eq!(fountain.name, "Rock Fountain");
+    assert_eq!(
+        fountain.tags,
+        ["Landscaping", "Fountains", "Garden Supplies"]
+    );
+
+    let information = &fountain.variants[0].stock_information;
+    assert_eq!(information.stock_group, "Inventory Asset");
+    assert_eq!(information.sales_group, "Sales of Product Income");
+    assert_eq!(information.value_stream, "Cost of Goods Sold");
+
+    let sprinklers =
+        db.0.iter()
+            .find(|product| product.name == "Sprinkler Heads")
+            .unwrap();
+    let information = &sprinklers.variants[0].stock_information;
+    assert_eq!(information.stock_group, "");
+    assert_eq!(information.value_stream, "Purchases");
+}
+
+#[test]
+fn only_inventory_items_are_counted_at_the_company_store() {
+    let db = common::parse(
+        "quickbooks",
+        "quickbooks_import",
+        &[("products_and_services.csv", ParseType::Product)],
+    );
+    assert_eq!(db.3.len(), 1);
+    assert_eq!(db.3[0].name, "QuickBooks");
+
+    let pump = db.0.iter().find(|product| product.name == "Pump").unwrap();
+    assert_eq!(pump.variants[0].retail_price, 1250.0);
+    assert_eq!(pump.variants[0].stock[0].store.store_id, db.3[0].id);
+    assert_eq!(pump.variants[0].stock[0].quantity.quantity_sellable, 25.0);
+
+    let design =
+        db.0.iter()
+            .find(|product| product.name == "Design")
+            .unwrap();
+    assert!(design.variants[0].stock.is_empty());
+    assert!(!design.variants[0].stock_tracking);
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use odm_migration_utility::{InlineDatabase, ParseType, SpecialPricing};
+use open_stock::Customer;
+
+fn parse_customers() -> InlineDatabase {
+    common::parse(
+        "shopify",
+        "shopify_customers",
+        &[
+            ("customers_export.csv", ParseType::Customer),
+            ("customer_addresses_export.csv", ParseType::CustomerAddress),
+        ],
+    )
+}
+
+fn find<'a>(db: &'a InlineDatabase, name: &str) -> &'a Customer {
+    db.1.iter()
+        .find(|customer| customer.name == name)
+        .unwrap_or_else(|| panic!("expected customer {}", name))
+}
+
+fn notes(customer: &Customer) -> Vec<&str> {
+    customer
+        .customer_notes
+        .iter()
+        .map(|note| note.message.as_str())
+        .collect()
+}
+
+#[test]
+fn customer_columns_are_mapped() {
+    let db = parse_customers();
+    assert_eq!(db.1.len(), 2);
+
+    let ava = find(&db, "Ava Jones");
+    assert_eq!(ava.contact.name, "Ava Jones");
+    assert_eq!(ava.contact.address.street2, "Level 2");
+    assert_eq!(ava.contact.address.city, "Auckland");
+    assert_eq!(ava.contact.address.country, "New Zealand");
+    assert!(ava.accepts_marketing);
+    assert!(ava.special_pricing.is_empty());
+    assert_eq!(
+        notes(ava),
+        vec![
+            "Prefers email",
+            "Tags: VIP, wholesale",
+            "Shopify history: 3 orders, 250.00 spent",
+            "Address: 22 Beach Rd, Piha, Auckland, New Zealand, 0772",
+        ]
+    );
+
+    let ben = find(&db, "Ben Smith");
+    assert_eq!(ben.contact.name, "Ben Smith (Smith Cafe)");
+    assert!(!ben.accepts_marketing);
+    assert!(SpecialPricing::from_field(&ben.special_pricing).tax_exempt);
+}
+
+#[test]
+fn sms_consent_is_only_noted_when_it_differs_from_email() {
+    let db = parse_customers();
+
+    assert!(!notes(find(&db, "Ava Jones"))
+        .iter()
+        .any(|note| note.contains("SMS")));
+    assert!(notes(find(&db, "Ben Smith")).contains(&"Accepts SMS marketing: yes"));
+}
+
+#[test]
+fn default_addresses_replace_the_contact_address() {
+    let db = parse_customers();
+    let ben = find(&db, "Ben Smith");
+
+    assert_eq!(ben.contact.address.street, "9 Willis St");
+    assert_eq!(ben.contact.address.country, "New Zealand");
+    assert!(notes(ben).contains(&"Address: 5 Cuba St, Wellington, NZ, 6011"));
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use odm_migration_utility::{InlineDatabase, ParseType, PromotionTerms};
+use open_stock::{DiscountValue, PromotionGet};
+
+fn terms<'a>(db: &'a InlineDatabase, code: &str) -> &'a PromotionTerms {
+    db.8.iter()
+        .find(|terms| terms.code == code)
+        .unwrap_or_else(|| panic!("expected terms of {}", code))
+}
+
+#[test]
+fn discounts_keep_their_exact_value_and_usage() {
+    let mut db = InlineDatabase::default();
+    common::read(
+        "shopify",
+        "shopify_discounts",
+        &[("discounts_export.csv", ParseType::Promotion)],
+        &mut db,
+    );
+
+    assert_eq!(db.6.len(), 3);
+
+    let autumn =
+        db.6.iter()
+            .find(|promotion| promotion.name == "AUTUMN")
+            .unwrap();
+    assert!(matches!(
+        autumn.get,
+        PromotionGet::SoloThis(DiscountValue::Percentage(12))
+    ));
+    assert_eq!(
+        terms(&db, "AUTUMN"),
+        &PromotionTerms {
+            promotion_id: autumn.id.clone(),
+            code: "AUTUMN".to_string(),
+            value: 12.5,
+            percentage: true,
+            free_shipping: false,
+            usage_limit: Some(200),
+            times_used: 37,
+        }
+    );
+
+    let take = terms(&db, "TAKE5");
+    assert!((take.value - 5.0).abs() < 1e-4);
+    assert!(!take.percentage);
+    assert_eq!(take.usage_limit, None);
+    assert_eq!(take.times_used, 4);
+}
+
+#[test]
+fn free_shipping_is_kept_with_its_terms() {
+    let mut db = InlineDatabase::default();
+    common::read(
+        "shopify",
+        "shopify_discounts",
+        &[("discounts_export.csv", ParseType::Promotion)],
+        &mut db,
+    );
+
+    let shipping =
+        db.6.iter()
+            .find(|promotion| promotion.name == "SHIPFREE")
+            .unwrap();
+    assert!(matches!(
+        shipping.get,
+        PromotionGet::Any((quantity, DiscountValue::Absolute(0))) if quantity == 2.0
+    ));
+
+    let terms = terms(&db, "SHIPFREE");
+    assert!(terms.free_shipping);
+    assert_eq!(terms.usage_limit, Some(1));
+    assert_eq!(terms.times_used, 1);
+}
+
+#[test]
+fn orders_are_discounted_by_the_amount_they_saved() {
+    let mut db = InlineDatabase::default();
+    common::read(
+        "shopify",
+        "shopify_discounts",
+        &[("discounts_export.csv", ParseType::Promotion)],
+        &mut db,
+    );
+    common::read(
+        "shopify",
+        "shopify_orders",
+        &[("orders_export.csv", ParseType::Transaction)],
+        &mut db,
+    );
+
+    let autumn =
+        db.6.iter()
+            .find(|promotion| promotion.name == "AUTUMN")
+            .unwrap();
+    let order = &db
+        .2
+        .iter()
+        .find(|transaction| transaction.products[0].reference == "#1001")
+        .unwrap()
+        .products[0];
+
+    // The promotion takes 12.5% off, but the order saved 9.50.
+    assert!(matches!(order.discount, DiscountValue::Absolute(9)));
+    assert_eq!(
+        order.order_notes[0].message,
+        format!(
+            "Discount code AUTUMN redeemed for 9.50, promotion {}",
+            autumn.id
+        )
+    );
+    assert!(matches!(
+        order.products[0].discount,
+        DiscountValue::Absolute(9)
+    ));
+
+    let pos = &db
+        .2
+        .iter()
+        .find(|transaction| transaction.products[0].reference == "#1002")
+        .unwrap()
+        .products[0];
+    assert!(matches!(pos.discount, DiscountValue::Absolute(0)));
+    assert!(pos.order_notes.is_empty());
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use chrono::{TimeZone, Utc};
+use odm_migration_utility::{GiftCard, InlineDatabase, ParseType};
+
+fn parse_gift_cards() -> InlineDatabase {
+    let mut db = common::parse(
+        "shopify",
+        "shopify_customers",
+        &[("customers_export.csv", ParseType::Customer)],
+    );
+    common::read(
+        "shopify",
+        "shopify_gift_cards",
+        &[("gift_cards_export.csv", ParseType::GiftCard)],
+        &mut db,
+    );
+
+    db
+}
+
+fn find<'a>(db: &'a InlineDatabase, code: &str) -> &'a GiftCard {
+    db.7.iter()
+        .find(|card| card.code == code)
+        .unwrap_or_else(|| panic!("expected gift card {}", code))
+}
+
+#[test]
+fn gift_cards_keep_their_codes_and_balances() {
+    let db = parse_gift_cards();
+    assert_eq!(db.7.len(), 3);
+
+    let birthday = find(&db, "x7k2");
+    assert!((birthday.initial_value - 100.0).abs() < 1e-4);
+    assert!((birthday.balance - 62.5).abs() < 1e-4);
+    assert_eq!(birthday.currency, "NZD");
+    assert_eq!(birthday.order_reference, "#1001");
+    assert_eq!(birthday.note, "Birthday gift");
+    assert!(!birthday.disabled);
+
+    let spent = find(&db, "q9m4");
+    assert!((spent.initial_value - 50.0).abs() < 1e-4);
+    assert_eq!(spent.balance, 0.0);
+    assert!(spent.disabled);
+}
+
+#[test]
+fn gift_cards_are_linked_to_their_customers() {
+    let db = parse_gift_cards();
+
+    let ava =
+        db.1.iter()
+            .find(|customer| customer.name == "Ava Jones")
+            .unwrap();
+    assert_eq!(find(&db, "x7k2").customer_id, Some(ava.id.clone()));
+
+    // Pat was never imported as a customer, and the last card was issued to no one.
+    assert_eq!(find(&db, "q9m4").customer_id, None);
+    assert_eq!(find(&db, "h3d8").customer_id, None);
+}
+
+#[test]
+fn gift_cards_expire_at_the_end_of_their_expiry_date() {
+    let db = parse_gift_cards();
+
+    assert_eq!(
+        find(&db, "x7k2").expires_at,
+        Some(Utc.with_ymd_and_hms(2025, 1, 5, 23, 59, 59).unwrap())
+    );
+    assert_eq!(find(&db, "h3d8").expires_at, None);
+    assert_eq!(
+        find(&db, "h3d8").created_at,
+        Utc.with_ymd_and_hms(2024, 2, 10, 3, 30, 0).unwrap()
+    );
+}
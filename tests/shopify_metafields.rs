@@ -0,0 +1,27 @@
+mod common;
+
+use odm_migration_utility::{ConversionOptions, MetafieldTarget, ParseType};
+
+#[test]
+fn configured_targets_replace_the_defaults() {
+    let mut options = ConversionOptions::new();
+    options.set_metafield_target("custom.fabric", "tag".parse::<MetafieldTarget>().unwrap());
+    options.set_metafield_target("descriptors.care_guide", MetafieldTarget::Ignore);
+
+    let db = options.scope(|| {
+        common::parse(
+            "shopify",
+            "shopify_metafields",
+            &[("products_export.csv", ParseType::Product)],
+        )
+    });
+
+    let beanie = common::product(&db, "Merino Beanie");
+
+    assert!(beanie.tags.contains(&"Merino wool".to_string()));
+    assert!(!beanie.specifications.iter().any(|(key, _)| key == "Fabric"));
+    assert!(!beanie.description_long.contains("Hand wash cold."));
+
+    // Those not configured keep their default placement.
+    assert!(beanie.tags.contains(&"charcoal".to_string()));
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+use open_stock::PaymentMethod;
+
+#[test]
+fn combined_payment_methods_without_amounts_are_kept_whole() {
+    let db = common::parse(
+        "shopify",
+        "shopify_orders",
+        &[("orders_export.csv", ParseType::Transaction)],
+    );
+
+    // The export carries no amount for each tender, so none is guessed.
+    let online = common::order(&db, "#1001");
+    assert_eq!(online.payment.len(), 1);
+    assert!(matches!(
+        &online.payment[0].payment_method,
+        PaymentMethod::Other(method) if method == "Shopify Payments + Gift Card"
+    ));
+    assert!((online.payment[0].amount.quantity - 80.0).abs() < 1e-4);
+
+    let pos = common::order(&db, "#1002");
+    assert_eq!(pos.payment.len(), 1);
+    assert!(matches!(pos.payment[0].payment_method, PaymentMethod::Cash));
+    assert!((pos.payment[0].amount.quantity - 45.0).abs() < 1e-4);
+}
+
+#[test]
+fn pos_sales_are_attributed_to_their_store_kiosk_and_employee() {
+    let db = common::parse(
+        "shopify",
+        "shopify_orders",
+        &[("orders_export.csv", ParseType::Transaction)],
+    );
+    let pos = common::order(&db, "#1002");
+
+    let store = db.3.iter().find(|store| store.name == "Ponsonby").unwrap();
+    assert_eq!(pos.products[0].origin.store_id, store.id);
+
+    let kiosk = db.4.iter().find(|kiosk| kiosk.id == pos.kiosk).unwrap();
+    assert_eq!(kiosk.name, "Shopify POS 42");
+    assert_eq!(kiosk.store_id, store.id);
+    assert!(!kiosk.disabled);
+
+    let employee =
+        db.5.iter()
+            .find(|employee| employee.id == pos.salesperson)
+            .unwrap();
+    assert_eq!(employee.contact.name, "Jamie Lee");
+}
+
+#[test]
+fn online_orders_originate_from_the_online_store() {
+    let db = common::parse(
+        "shopify",
+        "shopify_orders",
+        &[("orders_export.csv", ParseType::Transaction)],
+    );
+
+    let online =
+        db.3.iter()
+            .find(|store| store.name == "Online Store")
+            .unwrap();
+    assert_eq!(
+        common::order(&db, "#1001").products[0].origin.store_id,
+        online.id
+    );
+    // Nor is the POS sale's register.
+    assert!(common::order(&db, "#1001").kiosk.is_empty());
+
+    // The POS sale comes first, but its store is not taken for online orders.
+    let pos = db.3.iter().find(|store| store.name == "Ponsonby").unwrap();
+    assert_ne!(pos.id, online.id);
+    assert_eq!(
+        common::order(&db, "#1002").products[0].origin.store_id,
+        pos.id
+    );
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+use open_stock::ProductVisibility;
+
+#[test]
+fn active_published_products_are_visible_and_sellable() {
+    let db = common::parse(
+        "shopify",
+        "large_import",
+        &[("demo_products_export.csv", ParseType::Product)],
+    );
+    let tee = common::product(&db, "Explore Graphic Tee");
+
+    assert!(matches!(tee.visible, ProductVisibility::ShowWhenInStock));
+    assert_eq!(tee.variants.len(), 3);
+
+    for variant in &tee.variants {
+        let info = &variant.stock_information;
+
+        assert!(!info.discontinued);
+        assert!(info.shippable);
+        assert!(!info.back_order);
+        assert_eq!(info.weight, "25");
+        assert!(info.size_x_unit.is_empty());
+    }
+}
+
+#[test]
+fn lifecycle_status_maps_to_visibility_and_discontinued() {
+    let db = common::parse(
+        "shopify",
+        "shopify_lifecycle",
+        &[("products_export.csv", ParseType::Product)],
+    );
+
+    let apron = common::product(&db, "Linen Apron");
+    assert!(matches!(apron.visible, ProductVisibility::ShowWhenInStock));
+    assert!(!apron.variants[0].stock_information.discontinued);
+    assert!(apron.variants[0].stock_information.back_order);
+
+    let tote = common::product(&db, "Canvas Tote");
+    assert!(matches!(tote.visible, ProductVisibility::AlwaysHidden));
+    assert!(!tote.variants[0].stock_information.discontinued);
+
+    let scarf = common::product(&db, "Wool Scarf");
+    assert!(matches!(scarf.visible, ProductVisibility::AlwaysHidden));
+    assert!(scarf.variants[0].stock_information.discontinued);
+    assert!(!scarf.variants[0].stock_information.shippable);
+}
+
+#[test]
+fn weight_is_converted_from_grams_to_kilograms() {
+    let db = common::parse(
+        "shopify",
+        "shopify_lifecycle",
+        &[("products_export.csv", ParseType::Product)],
+    );
+
+    let weight = |name: &str| -> f64 {
+        common::product(&db, name).variants[0]
+            .stock_information
+            .weight
+            .parse()
+            .expect("weight should be numeric")
+    };
+
+    assert!((weight("Linen Apron") - 0.35).abs() < 1e-9);
+    assert!((weight("Canvas Tote") - 0.45359237).abs() < 1e-9);
+}
+
+#[test]
+fn identification_comes_from_handle_sku_and_barcode() {
+    let db = common::parse(
+        "shopify",
+        "shopify_lifecycle",
+        &[("products_export.csv", ParseType::Product)],
+    );
+
+    let apron = common::product(&db, "Linen Apron");
+    assert_eq!(apron.sku, "linen-apron");
+    assert_eq!(apron.variants[0].identification.sku, "APRON");
+    assert_eq!(apron.variants[0].identification.ean, "4006381333931");
+
+    let tote = common::product(&db, "Canvas Tote");
+    assert_eq!(tote.variants[0].identification.isbn, "9780306406157");
+
+    // Placeholder barcodes fail validation and are not filed as an EAN.
+    let tees = common::parse(
+        "shopify",
+        "large_import",
+        &[("demo_products_export.csv", ParseType::Product)],
+    );
+    let tee = common::product(&tees, "Explore Graphic Tee");
+    assert_eq!(tee.variants[0].barcode, "ABCDEFGHIJKLMNOP");
+    assert!(tee.variants[0].identification.ean.is_empty());
+}
+
+#[test]
+fn gallery_includes_image_only_rows_in_position_order() {
+    let db = common::parse(
+        "shopify",
+        "shopify_gallery",
+        &[("products_export.csv", ParseType::Product)],
+    );
+    assert_eq!(db.0.len(), 2);
+
+    let cdn = "https://cdn.shopify.com/s/files/1/demo/";
+    let mug = common::product(&db, "Ceramic Mug");
+
+    assert_eq!(mug.variants.len(), 2);
+    assert_eq!(
+        mug.images,
+        vec![
+            format!("{}mug-front.jpg", cdn),
+            format!("{}mug-side.jpg", cdn),
+            format!("{}mug-white.jpg", cdn),
+            format!("{}mug-black.jpg", cdn),
+        ]
+    );
+    assert!(mug.specifications.contains(&(
+        "Image 2 Alt Text".to_string(),
+        "Mug from the side".to_string()
+    )));
+
+    let towel = common::product(&db, "Tea Towel");
+    assert_eq!(towel.images, vec![format!("{}towel.jpg", cdn)]);
+    assert!(towel.variants[0].images.is_empty());
+}
+
+#[test]
+fn catalog_attributes_become_specifications_tags_and_descriptions() {
+    let db = common::parse(
+        "shopify",
+        "shopify_metafields",
+        &[("products_export.csv", ParseType::Product)],
+    );
+    let beanie = common::product(&db, "Merino Beanie");
+
+    let specification = |name: &str| {
+        beanie
+            .specifications
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+
+    assert_eq!(specification("SEO Title"), Some("Merino Beanie | My Store"));
+    assert_eq!(specification("Gender"), Some("unisex"));
+    assert_eq!(specification("Custom Label 0"), Some("winter"));
+    assert_eq!(specification("Fabric"), Some("Merino wool"));
+    assert_eq!(specification("MPN"), None);
+    assert_eq!(specification("Product rating"), None);
+
+    assert_eq!(
+        beanie.tags,
+        vec!["winter", "Wool", "sale", "charcoal", "navy"]
+    );
+    assert!(beanie.description_long.ends_with("Hand wash cold."));
+    assert_eq!(beanie.description, "<p>A warm beanie.</p>");
+}
+
+#[test]
+fn gift_cards_are_not_stocked() {
+    let db = common::parse(
+        "shopify",
+        "shopify_lifecycle",
+        &[("products_export.csv", ParseType::Product)],
+    );
+
+    let card = common::product(&db, "Gift Card");
+    assert!(!card.variants[0].stock_tracking);
+    assert!(card.variants[0].stock.is_empty());
+    assert!(card.variants[0].stock_information.non_diminishing);
+
+    let apron = common::product(&db, "Linen Apron");
+    assert!(apron.variants[0].stock_tracking);
+    assert!(!apron.variants[0].stock_information.non_diminishing);
+}
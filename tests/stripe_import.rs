@@ -0,0 +1,122 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+use open_stock::{PaymentAction, PaymentMethod, PaymentStatus, Processable};
+
+#[test]
+fn charges_settle_the_payments_of_their_order() {
+    let db = common::settle("stripe", "stripe_import", "unified_payments.csv");
+    let order = common::order(&db, "VPXQDRTQA");
+    assert_eq!(order.payment.len(), 1);
+
+    let payment = &order.payment[0];
+    assert_eq!(payment.processor.token, "ch_3N4aQx2eZvKYlo2C0a1b2c3d");
+    assert_eq!(payment.amount.quantity, 45.0);
+    assert_eq!(payment.processing_fee.quantity, 1.56);
+    assert!(matches!(payment.delay_action, PaymentAction::Complete));
+
+    match &payment.status {
+        PaymentStatus::Complete(Processable::CardDetails(card)) => {
+            assert_eq!(card.card_brand, "Visa");
+            assert_eq!(card.last_4, "4242");
+        }
+        status => panic!("expected a completed card payment, got {:?}", status),
+    }
+}
+
+#[test]
+fn failed_charges_are_kept_without_settling_their_order() {
+    let db = common::settle("stripe", "stripe_import", "unified_payments.csv");
+    let order = common::order(&db, "GHTRSAQWE");
+
+    let failed = order
+        .payment
+        .iter()
+        .find(|payment| payment.processor.token == "ch_3N4bRy2eZvKYlo2C1x2y3z4w")
+        .unwrap();
+    assert!(matches!(failed.status, PaymentStatus::Failed(_)));
+    assert!(matches!(failed.delay_action, PaymentAction::Cancel));
+
+    // The charge which went through settles the order's payment instead.
+    let paid = order
+        .payment
+        .iter()
+        .find(|payment| payment.processor.token == "ch_3N4bSz2eZvKYlo2C5t6u7v8s")
+        .unwrap();
+    assert!(matches!(paid.status, PaymentStatus::Complete(_)));
+    assert_eq!(paid.amount.quantity, 120.0);
+    assert!(order
+        .payment
+        .iter()
+        .all(|payment| !payment.processor.token.is_empty()));
+
+    assert!(order
+        .order_notes
+        .iter()
+        .any(|note| note.message.contains("failed: card_declined")));
+}
+
+#[test]
+fn refunded_charges_are_kept_without_settling_their_order() {
+    let db = common::settle("stripe", "stripe_import", "unified_payments.csv");
+    let order = common::order(&db, "LMQZXKWPA");
+
+    let refunded = order
+        .payment
+        .iter()
+        .find(|payment| payment.processor.token == "ch_3N4cTa2eZvKYlo2C9p8o7n6m")
+        .unwrap();
+    assert!(matches!(refunded.status, PaymentStatus::Unfulfilled(_)));
+    assert!(matches!(refunded.delay_action, PaymentAction::Cancel));
+    assert!(order.payment.iter().all(|payment| matches!(
+        payment.status,
+        PaymentStatus::Unfulfilled(_)
+    ) || payment.processor.token.is_empty()));
+
+    assert!(order
+        .order_notes
+        .iter()
+        .any(|note| note.message == "Refunded 32.50 EUR of charge ch_3N4cTa2eZvKYlo2C9p8o7n6m"));
+}
+
+#[test]
+fn charges_settle_shopify_orders_by_the_reference_they_describe() {
+    let mut db = common::parse(
+        "shopify",
+        "shopify_orders",
+        &[("orders_export.csv", ParseType::Transaction)],
+    );
+    common::read(
+        "stripe",
+        "stripe_import",
+        &[("shopify_charges.csv", ParseType::TransactionItem)],
+        &mut db,
+    );
+
+    let order = common::order(&db, "#1003");
+    assert_eq!(order.payment.len(), 1);
+
+    let payment = &order.payment[0];
+    assert_eq!(payment.processor.token, "ch_3N4eVc2eZvKYlo2C8g7f6e5d");
+    assert_eq!(payment.processing_fee.quantity, 1.17);
+    assert!(matches!(payment.status, PaymentStatus::Complete(_)));
+
+    // The charge's metadata holds an order id, which is the reference of
+    // another order. A card charge of that order is added beside its cash
+    // payment, which it did not settle.
+    let cash = common::order(&db, "#1002");
+    assert_eq!(cash.payment.len(), 2);
+    assert!(matches!(
+        cash.payment[0].payment_method,
+        PaymentMethod::Cash
+    ));
+    assert!(cash.payment[0].processor.token.is_empty());
+    assert!(matches!(
+        cash.payment[1].payment_method,
+        PaymentMethod::Card
+    ));
+    assert_eq!(
+        cash.payment[1].processor.token,
+        "ch_3N4fWd2eZvKYlo2C2s3t4u5v"
+    );
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+use open_stock::{PaymentMethod, TransactionType};
+
+#[test]
+fn variations_are_grouped_with_decimal_comma_prices() {
+    let db = common::parse(
+        "sumup",
+        "sumup_import",
+        &[("item_library.csv", ParseType::Product)],
+    );
+    assert_eq!(db.0.len(), 3);
+
+    let latte = &db.0[0];
+    assert_eq!(
+        latte
+            .variants
+            .iter()
+            .map(|variant| (variant.name.as_str(), variant.retail_price))
+            .collect::<Vec<_>>(),
+        vec![("Regular", 3.5), ("Large", 4.2)]
+    );
+
+    let tote = &db.0[2];
+    assert_eq!(tote.variants[0].identification.ean, "4006381333931");
+    assert_eq!(tote.variants[0].stock[0].store.contact.name, "SumUp");
+    assert_eq!(tote.variants[0].stock[0].quantity.quantity_sellable, 10.0);
+}
+
+#[test]
+fn described_items_are_joined_to_the_library() {
+    let db = common::parse(
+        "sumup",
+        "sumup_import",
+        &[
+            ("item_library.csv", ParseType::Product),
+            ("transactions.csv", ParseType::Transaction),
+        ],
+    );
+
+    // The failed transaction is not carried over.
+    assert_eq!(db.2.len(), 3);
+
+    let sale = &db.2[0];
+    assert_eq!(
+        sale.products[0]
+            .products
+            .iter()
+            .map(|purchase| (purchase.product_code.as_str(), purchase.quantity))
+            .collect::<Vec<_>>(),
+        vec![("CRO-01", 2.0), ("LAT-R", 1.0)]
+    );
+    assert!(matches!(
+        sale.payment[0].payment_method,
+        PaymentMethod::Card
+    ));
+    assert!((sale.payment[0].amount.quantity - 8.2).abs() < 1e-4);
+    assert_eq!(
+        sale.products[0].order_notes[0].message,
+        "Tip of 0.50 EUR included in the total"
+    );
+
+    let employee =
+        db.5.iter()
+            .find(|employee| employee.id == sale.salesperson)
+            .unwrap();
+    assert_eq!(employee.contact.name, "Lena Vogel");
+
+    // The postcard is not in the library, and is kept by its description.
+    let cash = &db.2[1];
+    assert_eq!(cash.products[0].products[1].product_name, "Postcard");
+    assert!(cash.products[0].products[1].product_code.is_empty());
+
+    assert!(matches!(db.2[2].transaction_type, TransactionType::In));
+}
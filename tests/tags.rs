@@ -0,0 +1,69 @@
+mod common;
+
+use odm_migration_utility::{merge_tags, parse_tags, ConversionOptions, ParseType};
+
+#[test]
+fn duplicates_are_matched_regardless_of_case() {
+    let mut tags = parse_tags("Über, summer,über, SUMMER, ", ',', false);
+    assert_eq!(tags, vec!["Über", "summer"]);
+
+    merge_tags(&mut tags, ["ÜBER".to_string(), "Sale".to_string()], false);
+    assert_eq!(tags, vec!["Über", "summer", "Sale"]);
+}
+
+#[test]
+fn tags_are_lowercased_when_asked() {
+    assert_eq!(
+        parse_tags("Über, Summer,über", ',', true),
+        vec!["über", "summer"]
+    );
+}
+
+#[test]
+fn lowercasing_applies_to_every_format() {
+    let mut options = ConversionOptions::new();
+    options.lowercase_tags = true;
+
+    let db = options.scope(|| {
+        common::parse(
+            "shopify",
+            "shopify_metafields",
+            &[("products_export.csv", ParseType::Product)],
+        )
+    });
+
+    let beanie = common::product(&db, "Merino Beanie");
+
+    assert_eq!(
+        beanie.tags,
+        vec!["winter", "wool", "sale", "charcoal", "navy"]
+    );
+}
+
+#[test]
+fn category_tags_are_lowercased_too() {
+    let mut options = ConversionOptions::new();
+    options.lowercase_tags = true;
+
+    let db = options.scope(|| {
+        let mut db = common::parse(
+            "magento",
+            "magento_import",
+            &[("catalog_product.csv", ParseType::Product)],
+        );
+        common::read(
+            "loyverse",
+            "loyverse_import",
+            &[("export_items.csv", ParseType::Product)],
+            &mut db,
+        );
+
+        db
+    });
+
+    assert_eq!(
+        common::product(&db, "Chaz Hoodie").tags,
+        vec!["tops", "sale"]
+    );
+    assert_eq!(common::product(&db, "Linen Shirt").tags, vec!["clothing"]);
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+use open_stock::{PaymentMethod, TransactionType};
+
+#[test]
+fn variants_are_grouped_by_handle_with_stock_per_outlet() {
+    let db = common::parse(
+        "vend",
+        "vend_import",
+        &[("product_export.csv", ParseType::Product)],
+    );
+    assert_eq!(db.0.len(), 2);
+
+    let sock =
+        db.0.iter()
+            .find(|product| product.name == "Crew Sock")
+            .unwrap();
+    assert_eq!(sock.variants.len(), 3);
+    assert_eq!(sock.tags, vec!["socks", "cotton"]);
+
+    let small = &sock.variants[0];
+    assert_eq!(small.name, "Small Black");
+    assert_eq!(small.identification.sku, "10001");
+    assert_eq!(
+        small
+            .stock
+            .iter()
+            .map(|stock| (
+                stock.store.contact.name.as_str(),
+                stock.quantity.quantity_sellable
+            ))
+            .collect::<Vec<_>>(),
+        vec![("Main Outlet", 24.0), ("Wellington", 8.0)]
+    );
+
+    // The export has no barcode column, so the SKU is not read as one.
+    let wrap =
+        db.0.iter()
+            .find(|product| product.name == "Gift Wrap")
+            .unwrap();
+    assert_eq!(wrap.variants[0].identification.sku, "4006381333931");
+    assert!(wrap.variants[0].identification.ean.is_empty());
+    assert!(wrap.variants[0].barcode.is_empty());
+    assert!(!wrap.variants[0].stock_tracking);
+}
+
+#[test]
+fn sales_are_joined_to_customers_by_code() {
+    let db = common::parse(
+        "vend",
+        "vend_import",
+        &[
+            ("customer_export.csv", ParseType::Customer),
+            ("product_export.csv", ParseType::Product),
+            ("sales_ledger.csv", ParseType::Transaction),
+        ],
+    );
+
+    // The voided receipt 1003 is not carried over.
+    assert_eq!(db.2.len(), 3);
+
+    let mary =
+        db.1.iter()
+            .find(|customer| customer.name == "Mary Smith")
+            .unwrap();
+
+    let sale = common::order(&db, "1001");
+    assert_eq!(sale.customer.customer_id, mary.id);
+    assert_eq!(sale.products[0].origin.store_code, db.3[0].code);
+    assert_eq!(sale.payment.len(), 2);
+    assert!(matches!(
+        sale.payment[0].payment_method,
+        PaymentMethod::Cash
+    ));
+    assert!((sale.payment[0].amount.quantity - 20.0).abs() < 1e-4);
+    assert!(matches!(
+        &sale.payment[1].payment_method,
+        PaymentMethod::Other(method) if method == "GIFT_CARD"
+    ));
+
+    let walk_in = common::order(&db, "1002");
+    assert!(walk_in.customer.customer_id.is_empty());
+    assert_eq!(walk_in.products[0].origin.contact.name, "Wellington");
+
+    // The refund names Mary differently, but is joined by her code.
+    let refund = common::order(&db, "1004");
+    assert_eq!(refund.customer.customer_id, mary.id);
+    assert!(matches!(refund.transaction_type, TransactionType::In));
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use odm_migration_utility::{weight_unit, ConversionOptions, ParseType};
+
+#[test]
+fn weights_are_read_in_the_unit_set_for_their_format() {
+    let mut options = ConversionOptions::new();
+
+    assert!(!options.set_weight_unit("bigcommerce", "stone"));
+    assert_eq!(options.scope(|| weight_unit("bigcommerce", "lb")), "lb");
+
+    assert!(options.set_weight_unit("bigcommerce", "kg"));
+    assert_eq!(options.scope(|| weight_unit("bigcommerce", "lb")), "kg");
+
+    let db = options.scope(|| {
+        common::parse(
+            "bigcommerce",
+            "bigcommerce_import",
+            &[("products-2023-05-07.csv", ParseType::Product)],
+        )
+    });
+
+    assert_eq!(db.0[0].variants[0].stock_information.weight, "0.1");
+}
+
+#[test]
+fn weights_outside_a_scope_are_read_in_the_default_unit() {
+    let mut options = ConversionOptions::new();
+    options.set_weight_unit("bigcommerce", "kg");
+
+    options.scope(|| ());
+
+    assert_eq!(weight_unit("bigcommerce", "lb"), "lb");
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+
+#[test]
+fn items_keep_their_accounts_in_their_stock_information() {
+    let db = common::parse(
+        "xero",
+        "xero_import",
+        &[("inventory_items.csv", ParseType::Product)],
+    );
+    assert_eq!(db.0.len(), 4);
+
+    let balls =
+        db.0.iter()
+            .find(|product| product.sku == "GB1-White")
+            .unwrap();
+    let information = &balls.variants[0].stock_information;
+    assert_eq!(information.stock_group, "630");
+    assert_eq!(information.sales_group, "200");
+    assert_eq!(information.value_stream, "310");
+
+    // Untracked items are costed against their purchases account.
+    let consulting =
+        db.0.iter()
+            .find(|product| product.sku == "Consult")
+            .unwrap();
+    let information = &consulting.variants[0].stock_information;
+    assert_eq!(information.stock_group, "");
+    assert_eq!(information.value_stream, "429");
+}
+
+#[test]
+fn only_tracked_items_are_stocked_at_the_organisation_store() {
+    let db = common::parse(
+        "xero",
+        "xero_import",
+        &[("inventory_items.csv", ParseType::Product)],
+    );
+    assert_eq!(db.3.len(), 1);
+    assert_eq!(db.3[0].name, "Xero");
+
+    let book = db.0.iter().find(|product| product.sku == "BOOK").unwrap();
+    assert_eq!(book.variants[0].retail_price, 19.95);
+    assert_eq!(book.variants[0].stock[0].store.store_id, db.3[0].id);
+    assert_eq!(book.variants[0].stock[0].quantity.quantity_sellable, 18.0);
+
+    let rate = db.0.iter().find(|product| product.sku == "DevD").unwrap();
+    assert!(rate.variants[0].stock.is_empty());
+    assert!(!rate.variants[0].stock_tracking);
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use odm_migration_utility::ParseType;
+use open_stock::{PaymentMethod, TransactionType};
+
+#[test]
+fn library_rows_are_grouped_into_products_by_name() {
+    let db = common::parse(
+        "zettle",
+        "zettle_import",
+        &[("product_library.csv", ParseType::Product)],
+    );
+    assert_eq!(db.0.len(), 3);
+
+    let beanie = &db.0[0];
+    assert_eq!(beanie.name, "Wool Beanie");
+    assert_eq!(
+        beanie
+            .variants
+            .iter()
+            .map(|variant| (
+                variant.name.as_str(),
+                variant.stock[0].quantity.quantity_sellable
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("Grey / S-M", 14.0),
+            ("Grey / L-XL", 6.0),
+            ("Mustard / S-M", 3.0)
+        ]
+    );
+    assert_eq!(beanie.variants[0].identification.ean, "7350053850019");
+
+    // Untracked products are not stocked.
+    let bun = &db.0[1];
+    assert!(!bun.variants[0].stock_tracking);
+    assert!(bun.variants[0].stock.is_empty());
+}
+
+#[test]
+fn purchase_rows_are_grouped_into_receipts() {
+    let db = common::parse(
+        "zettle",
+        "zettle_import",
+        &[
+            ("product_library.csv", ParseType::Product),
+            ("purchases.csv", ParseType::Transaction),
+        ],
+    );
+    assert_eq!(db.2.len(), 3);
+
+    let receipt = &db.2[0];
+    assert_eq!(receipt.products[0].reference, "101");
+    assert_eq!(
+        receipt.products[0]
+            .products
+            .iter()
+            .map(|purchase| (purchase.product_code.as_str(), purchase.quantity))
+            .collect::<Vec<_>>(),
+        vec![("BEANIE-G-SM", 1.0), ("BUN-01", 2.0)]
+    );
+    assert!(matches!(
+        receipt.payment[0].payment_method,
+        PaymentMethod::Card
+    ));
+    assert!((receipt.payment[0].amount.quantity - 38.0).abs() < 1e-4);
+
+    let kiosk = db.4.iter().find(|kiosk| kiosk.id == receipt.kiosk).unwrap();
+    assert_eq!(kiosk.name, "iPad Counter");
+
+    // Swish is a bank transfer.
+    assert!(matches!(
+        db.2[1].payment[0].payment_method,
+        PaymentMethod::Transfer
+    ));
+
+    let refund = &db.2[2];
+    assert!(matches!(refund.transaction_type, TransactionType::In));
+    assert!((refund.payment[0].amount.quantity + 29.0).abs() < 1e-4);
+}